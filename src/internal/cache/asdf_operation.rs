@@ -27,6 +27,8 @@ pub struct AsdfOperationCache {
         skip_serializing_if = "AsdfOperationUpdateCache::is_empty"
     )]
     pub update_cache: AsdfOperationUpdateCache,
+    #[serde(default = "HashMap::new", skip_serializing_if = "HashMap::is_empty")]
+    pub plugins: HashMap<String, AsdfPlugin>,
     #[serde(
         default = "utils::origin_of_time",
         with = "time::serde::rfc3339",
@@ -72,6 +74,39 @@ impl AsdfOperationCache {
             .get_asdf_plugin_versions(plugin, Duration::hours(1))
     }
 
+    pub fn set_asdf_plugin(
+        &mut self,
+        plugin: &str,
+        url: Option<String>,
+        git_ref: Option<String>,
+        commit: &str,
+    ) -> bool {
+        if let Some(existing) = self.plugins.get(plugin) {
+            if existing.url == url && existing.git_ref == git_ref && existing.commit == commit {
+                return false;
+            }
+
+            // If the plugin code changed, the cached list of versions
+            // might not be accurate anymore
+            if existing.commit != commit {
+                self.update_cache.plugins_versions.remove(plugin);
+            }
+        }
+
+        self.plugins.insert(
+            plugin.to_string(),
+            AsdfPlugin {
+                url,
+                git_ref,
+                commit: commit.to_string(),
+                updated_at: OffsetDateTime::now_utc(),
+            },
+        );
+        self.updated();
+
+        true
+    }
+
     pub fn add_installed(&mut self, workdir_id: &str, tool: &str, version: &str) -> bool {
         let inserted = if let Some(install) = self
             .installed
@@ -99,7 +134,7 @@ impl AsdfOperationCache {
 
 impl Empty for AsdfOperationCache {
     fn is_empty(&self) -> bool {
-        self.installed.is_empty() && self.update_cache.is_empty()
+        self.installed.is_empty() && self.update_cache.is_empty() && self.plugins.is_empty()
     }
 }
 
@@ -108,6 +143,7 @@ impl CacheObject for AsdfOperationCache {
         Self {
             installed: Vec::new(),
             update_cache: AsdfOperationUpdateCache::new(),
+            plugins: HashMap::new(),
             updated_at: utils::origin_of_time(),
         }
     }
@@ -140,6 +176,21 @@ pub struct AsdfInstalled {
     pub required_by: BTreeSet<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AsdfPlugin {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    pub commit: String,
+    #[serde(
+        default = "utils::origin_of_time",
+        with = "time::serde::rfc3339",
+        skip_serializing_if = "utils::is_origin_of_time"
+    )]
+    pub updated_at: OffsetDateTime,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AsdfOperationUpdateCache {
     #[serde(
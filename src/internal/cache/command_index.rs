@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::fs::Metadata;
+use std::io;
+use std::time::UNIX_EPOCH;
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::internal::cache::handler::exclusive;
+use crate::internal::cache::handler::shared;
+use crate::internal::cache::loaders::get_command_index_cache;
+use crate::internal::cache::loaders::set_command_index_cache;
+use crate::internal::cache::utils;
+use crate::internal::cache::CacheObject;
+use crate::internal::config::CommandSyntax;
+
+const COMMAND_INDEX_CACHE_NAME: &str = "command_index";
+
+/// Version of the format of the index, which needs to be increased every
+/// time the way the details of the commands are parsed changes, so that
+/// the entries of an index written by a different version are read again
+pub const COMMAND_INDEX_VERSION: u32 = 2;
+
+/// Index of the commands found in the omnipath, so that they can be listed
+/// without walking the directories which did not change, nor reading the
+/// metadata of the files which did not change
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommandIndexCache {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub directories: BTreeMap<String, CommandIndexDirectory>,
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, CommandIndexEntry>,
+    #[serde(default = "utils::origin_of_time", with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl CommandIndexCache {
+    /// Drops the content of the index if it was written with a different
+    /// version of the format
+    fn check_version(mut self) -> Self {
+        if self.version != COMMAND_INDEX_VERSION {
+            self = Self::new_empty();
+        }
+        self
+    }
+
+    pub fn directory(&self, path: &str, mtime: u64) -> Option<&CommandIndexDirectory> {
+        self.directories.get(path).filter(|dir| dir.mtime == mtime)
+    }
+
+    pub fn command(
+        &self,
+        path: &str,
+        mtime: u64,
+        metadata_mtime: Option<u64>,
+    ) -> Option<&CommandIndexEntry> {
+        self.commands
+            .get(path)
+            .filter(|entry| entry.mtime == mtime && entry.metadata_mtime == metadata_mtime)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CommandIndexDirectory {
+    pub mtime: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub directories: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommandIndexEntry {
+    pub mtime: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_mtime: Option<u64>,
+    /// The path of the file once symbolic links are resolved, if different
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub real_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<CommandIndexDetails>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommandIndexDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    #[serde(default, skip_serializing_if = "utils::is_false")]
+    pub autocompletion: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub syntax: Option<CommandSyntax>,
+}
+
+/// The modification time of a file or directory, in nanoseconds since the
+/// epoch, as used to key the entries of the index
+pub fn index_mtime(metadata: &Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+impl CacheObject for CommandIndexCache {
+    fn new_empty() -> Self {
+        Self {
+            version: COMMAND_INDEX_VERSION,
+            directories: BTreeMap::new(),
+            commands: BTreeMap::new(),
+            updated_at: utils::origin_of_time(),
+        }
+    }
+
+    fn get() -> Self {
+        get_command_index_cache()
+    }
+
+    fn shared() -> io::Result<Self> {
+        shared::<Self>(COMMAND_INDEX_CACHE_NAME).map(Self::check_version)
+    }
+
+    fn exclusive<F>(processing_fn: F) -> io::Result<Self>
+    where
+        F: FnOnce(&mut Self) -> bool,
+    {
+        exclusive::<Self, _, fn(Self)>(
+            COMMAND_INDEX_CACHE_NAME,
+            |index: &mut Self| {
+                *index = index.clone().check_version();
+                processing_fn(index)
+            },
+            set_command_index_cache,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_version() {
+        let index: CommandIndexCache = serde_json::from_str(
+            r#"{"commands":{"/bin/cmd":{"mtime":1}},"updated_at":"2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(index.version, 0);
+        assert!(index.check_version().commands.is_empty());
+
+        let json = format!(
+            r#"{{"version":{},"commands":{{"/bin/cmd":{{"mtime":1}}}}}}"#,
+            COMMAND_INDEX_VERSION
+        );
+        let index: CommandIndexCache = serde_json::from_str(&json).unwrap();
+        let index = index.check_version();
+        assert_eq!(index.version, COMMAND_INDEX_VERSION);
+        assert!(index.command("/bin/cmd", 1, None).is_some());
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::io;
+
+use serde::Deserialize;
+use serde::Serialize;
+use time::Duration;
+use time::OffsetDateTime;
+
+use crate::internal::cache::handler::exclusive;
+use crate::internal::cache::handler::shared;
+use crate::internal::cache::loaders::get_github_release_operation_cache;
+use crate::internal::cache::loaders::set_github_release_operation_cache;
+use crate::internal::cache::utils;
+use crate::internal::cache::utils::Empty;
+use crate::internal::cache::CacheObject;
+
+const GITHUB_RELEASE_OPERATION_CACHE_NAME: &str = "github_release_operation";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GithubReleaseOperationCache {
+    #[serde(default = "HashMap::new", skip_serializing_if = "HashMap::is_empty")]
+    pub releases: HashMap<String, GithubReleaseTags>,
+    #[serde(
+        default = "utils::origin_of_time",
+        with = "time::serde::rfc3339",
+        skip_serializing_if = "utils::is_origin_of_time"
+    )]
+    pub updated_at: OffsetDateTime,
+}
+
+impl GithubReleaseOperationCache {
+    pub fn updated(&mut self) {
+        self.updated_at = OffsetDateTime::now_utc();
+    }
+
+    pub fn set_release_tags(&mut self, key: &str, tags: Vec<String>) {
+        self.releases.insert(
+            key.to_string(),
+            GithubReleaseTags {
+                updated_at: OffsetDateTime::now_utc(),
+                tags,
+            },
+        );
+        self.updated();
+    }
+
+    pub fn get_release_tags(&self, key: &str) -> Option<Vec<String>> {
+        // TODO: add configuration option for the duration?
+        let expire_after = Duration::hours(1);
+        self.releases
+            .get(key)
+            .filter(|release| (release.updated_at + expire_after) >= OffsetDateTime::now_utc())
+            .map(|release| release.tags.clone())
+    }
+}
+
+impl Empty for GithubReleaseOperationCache {
+    fn is_empty(&self) -> bool {
+        self.releases.is_empty()
+    }
+}
+
+impl CacheObject for GithubReleaseOperationCache {
+    fn new_empty() -> Self {
+        Self {
+            releases: HashMap::new(),
+            updated_at: utils::origin_of_time(),
+        }
+    }
+
+    fn get() -> Self {
+        get_github_release_operation_cache()
+    }
+
+    fn shared() -> io::Result<Self> {
+        shared::<Self>(GITHUB_RELEASE_OPERATION_CACHE_NAME)
+    }
+
+    fn exclusive<F>(processing_fn: F) -> io::Result<Self>
+    where
+        F: FnOnce(&mut Self) -> bool,
+    {
+        exclusive::<Self, F, fn(Self)>(
+            GITHUB_RELEASE_OPERATION_CACHE_NAME,
+            processing_fn,
+            set_github_release_operation_cache,
+        )
+    }
+}
+
+/// The tags of the releases of a repository, as listed by the API
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GithubReleaseTags {
+    #[serde(
+        default = "utils::origin_of_time",
+        with = "time::serde::rfc3339",
+        skip_serializing_if = "utils::is_origin_of_time"
+    )]
+    pub updated_at: OffsetDateTime,
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_tags_expire() {
+        let mut cache = GithubReleaseOperationCache::new_empty();
+        assert_eq!(cache.get_release_tags("owner/name"), None);
+
+        cache.set_release_tags("owner/name", vec!["v1.0.0".to_string()]);
+        assert_eq!(
+            cache.get_release_tags("owner/name"),
+            Some(vec!["v1.0.0".to_string()])
+        );
+        assert_eq!(cache.get_release_tags("owner/other"), None);
+
+        cache.releases.get_mut("owner/name").unwrap().updated_at =
+            OffsetDateTime::now_utc() - Duration::hours(2);
+        assert_eq!(cache.get_release_tags("owner/name"), None);
+    }
+}
@@ -2,6 +2,7 @@ use std::env;
 use std::fs::File;
 use std::fs::OpenOptions;
 use std::io;
+use std::io::BufReader;
 use std::io::Read;
 use std::io::Seek;
 use std::io::Write;
@@ -130,7 +131,7 @@ where
     // TODO: re-evaluate, but shared lock does not seem necessary
     // let _file_lock = file.lock_shared();
 
-    let cache: C = serde_json::from_reader(file)?;
+    let cache: C = serde_json::from_reader(BufReader::new(file))?;
     Ok(cache)
 }
 
@@ -4,6 +4,8 @@ use lazy_static::lazy_static;
 
 use crate::internal::cache::AsdfOperationCache;
 use crate::internal::cache::CacheObject;
+use crate::internal::cache::CommandIndexCache;
+use crate::internal::cache::GithubReleaseOperationCache;
 use crate::internal::cache::HomebrewOperationCache;
 use crate::internal::cache::OmniPathCache;
 use crate::internal::cache::RepositoriesCache;
@@ -12,6 +14,10 @@ use crate::internal::cache::UpEnvironmentsCache;
 lazy_static! {
     static ref ASDF_OPERATION_CACHE: Mutex<AsdfOperationCache> =
         Mutex::new(AsdfOperationCache::new_load());
+    static ref COMMAND_INDEX_CACHE: Mutex<CommandIndexCache> =
+        Mutex::new(CommandIndexCache::new_load());
+    static ref GITHUB_RELEASE_OPERATION_CACHE: Mutex<GithubReleaseOperationCache> =
+        Mutex::new(GithubReleaseOperationCache::new_load());
     static ref HOMEBREW_OPERATION_CACHE: Mutex<HomebrewOperationCache> =
         Mutex::new(HomebrewOperationCache::new_load());
     static ref OMNIPATH_CACHE: Mutex<OmniPathCache> = Mutex::new(OmniPathCache::new_load());
@@ -33,6 +39,14 @@ pub fn get_asdf_operation_cache() -> AsdfOperationCache {
     generic_get_cache(&ASDF_OPERATION_CACHE)
 }
 
+pub fn get_command_index_cache() -> CommandIndexCache {
+    generic_get_cache(&COMMAND_INDEX_CACHE)
+}
+
+pub fn get_github_release_operation_cache() -> GithubReleaseOperationCache {
+    generic_get_cache(&GITHUB_RELEASE_OPERATION_CACHE)
+}
+
 pub fn get_homebrew_operation_cache() -> HomebrewOperationCache {
     generic_get_cache(&HOMEBREW_OPERATION_CACHE)
 }
@@ -61,6 +75,14 @@ pub fn set_asdf_operation_cache(cache_set: AsdfOperationCache) {
     generic_set_cache(&ASDF_OPERATION_CACHE, cache_set);
 }
 
+pub fn set_command_index_cache(cache_set: CommandIndexCache) {
+    generic_set_cache(&COMMAND_INDEX_CACHE, cache_set);
+}
+
+pub fn set_github_release_operation_cache(cache_set: GithubReleaseOperationCache) {
+    generic_set_cache(&GITHUB_RELEASE_OPERATION_CACHE, cache_set);
+}
+
 pub fn set_homebrew_operation_cache(cache_set: HomebrewOperationCache) {
     generic_set_cache(&HOMEBREW_OPERATION_CACHE, cache_set);
 }
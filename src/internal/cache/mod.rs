@@ -9,6 +9,12 @@ pub mod asdf_operation;
 pub use asdf_operation::AsdfInstalled;
 pub use asdf_operation::AsdfOperationCache;
 
+pub mod command_index;
+pub use command_index::CommandIndexCache;
+
+pub mod github_release_operation;
+pub use github_release_operation::GithubReleaseOperationCache;
+
 pub mod handler;
 
 pub mod homebrew_operation;
@@ -23,6 +29,7 @@ pub use omnipath::OmniPathCache;
 
 pub mod repositories;
 pub use repositories::RepositoriesCache;
+pub use repositories::WatchedFile;
 
 pub mod up_environments;
 pub use up_environments::UpEnvironment;
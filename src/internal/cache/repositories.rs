@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::io;
@@ -22,6 +23,8 @@ pub struct RepositoriesCache {
     pub trusted: BTreeSet<String>,
     #[serde(default = "HashMap::new", skip_serializing_if = "HashMap::is_empty")]
     pub fingerprints: HashMap<String, RepositoryFingerprints>,
+    #[serde(default = "HashMap::new", skip_serializing_if = "HashMap::is_empty")]
+    pub watched_files: HashMap<String, BTreeMap<String, WatchedFile>>,
     #[serde(default = "utils::origin_of_time", with = "time::serde::rfc3339")]
     pub updated_at: OffsetDateTime,
 }
@@ -47,12 +50,42 @@ impl RepositoriesCache {
         fingerprint_type: &str,
         fingerprint: u64,
     ) -> bool {
-        let cur_fingerprint = match self.fingerprints.get(repository) {
+        self.get_fingerprint(repository, fingerprint_type) == fingerprint
+    }
+
+    pub fn get_watched_files(&self, repository: &str) -> BTreeMap<String, WatchedFile> {
+        self.watched_files
+            .get(repository)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn update_watched_files(
+        &mut self,
+        repository: &str,
+        watched_files: BTreeMap<String, WatchedFile>,
+    ) -> bool {
+        if self.watched_files.get(repository) == Some(&watched_files)
+            || (watched_files.is_empty() && !self.watched_files.contains_key(repository))
+        {
+            return false;
+        }
+
+        if watched_files.is_empty() {
+            self.watched_files.remove(repository);
+        } else {
+            self.watched_files
+                .insert(repository.to_string(), watched_files);
+        }
+        self.updated_at = OffsetDateTime::now_utc();
+        true
+    }
+
+    pub fn get_fingerprint(&self, repository: &str, fingerprint_type: &str) -> u64 {
+        match self.fingerprints.get(repository) {
             Some(repo) => repo.get(fingerprint_type),
             None => 0,
-        };
-
-        cur_fingerprint == fingerprint
+        }
     }
 
     pub fn update_fingerprint(
@@ -112,6 +145,7 @@ impl CacheObject for RepositoriesCache {
         Self {
             trusted: BTreeSet::new(),
             fingerprints: HashMap::new(),
+            watched_files: HashMap::new(),
             updated_at: utils::origin_of_time(),
         }
     }
@@ -167,3 +201,15 @@ impl Empty for RepositoryFingerprints {
         self.fingerprints.is_empty()
     }
 }
+
+/// The state of a file watched by `omni up`, so that its content only
+/// needs to be hashed again when its size or modification time changes
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WatchedFile {
+    #[serde(rename = "s")]
+    pub size: u64,
+    #[serde(rename = "m")]
+    pub mtime: u128,
+    #[serde(rename = "h")]
+    pub hash: String,
+}
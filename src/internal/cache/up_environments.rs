@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::io;
+use std::path::Path;
 use std::path::PathBuf;
 
 use serde::Deserialize;
@@ -15,6 +16,8 @@ use crate::internal::cache::loaders::set_up_environments_cache;
 use crate::internal::cache::utils;
 use crate::internal::cache::utils::Empty;
 use crate::internal::cache::CacheObject;
+use crate::internal::config::EnvSecretConfig;
+use crate::internal::config::RepoShellAliasConfig;
 
 const UP_ENVIRONMENTS_CACHE_NAME: &str = "up_environments";
 
@@ -31,39 +34,54 @@ impl UpEnvironmentsCache {
         self.updated_at = OffsetDateTime::now_utc();
     }
 
-    pub fn set_env_vars(&mut self, workdir_id: &str, env_vars: HashMap<String, String>) -> bool {
-        if let Some(env) = self.env.get_mut(workdir_id) {
-            env.env_vars = env_vars;
-        } else {
-            let mut env = UpEnvironment::new();
-            env.env_vars = env_vars;
-            self.env.insert(workdir_id.to_string(), env);
+    fn workdir_env(&mut self, workdir_id: &str) -> &mut UpEnvironment {
+        self.env
+            .entry(workdir_id.to_string())
+            .or_insert_with(UpEnvironment::new)
+    }
+
+    pub fn set_env_vars(
+        &mut self,
+        workdir_id: &str,
+        env_vars: HashMap<String, String>,
+        secrets: HashMap<String, EnvSecretConfig>,
+        source: &str,
+    ) -> bool {
+        let env = self.workdir_env(workdir_id);
+        for key in env_vars.keys().chain(secrets.keys()) {
+            env.sources.insert(key.to_string(), source.to_string());
         }
+        env.env_vars = env_vars;
+        env.secrets = secrets;
         self.updated();
         true
     }
 
-    pub fn add_env_var(&mut self, workdir_id: &str, key: &str, value: &str) -> bool {
-        if let Some(env) = self.env.get_mut(workdir_id) {
-            env.env_vars.insert(key.to_string(), value.to_string());
-        } else {
-            let mut env = UpEnvironment::new();
-            env.env_vars.insert(key.to_string(), value.to_string());
-            self.env.insert(workdir_id.to_string(), env);
-        }
+    pub fn set_shell_aliases(
+        &mut self,
+        workdir_id: &str,
+        shell_aliases: HashMap<String, RepoShellAliasConfig>,
+    ) -> bool {
+        let env = self.workdir_env(workdir_id);
+        env.shell_aliases = shell_aliases;
         self.updated();
         true
     }
 
-    pub fn add_path(&mut self, workdir_id: &str, path: PathBuf) -> bool {
-        if let Some(env) = self.env.get_mut(workdir_id) {
-            env.paths.retain(|p| p != &path);
-            env.paths.push(path);
-        } else {
-            let mut env = UpEnvironment::new();
-            env.paths.push(path);
-            self.env.insert(workdir_id.to_string(), env);
-        }
+    pub fn add_env_var(&mut self, workdir_id: &str, key: &str, value: &str, source: &str) -> bool {
+        let env = self.workdir_env(workdir_id);
+        env.env_vars.insert(key.to_string(), value.to_string());
+        env.sources.insert(key.to_string(), source.to_string());
+        self.updated();
+        true
+    }
+
+    pub fn add_path(&mut self, workdir_id: &str, path: PathBuf, source: &str) -> bool {
+        let env = self.workdir_env(workdir_id);
+        env.sources
+            .insert(path.to_string_lossy().to_string(), source.to_string());
+        env.paths.retain(|p| p != &path);
+        env.paths.push(path);
         self.updated();
         true
     }
@@ -174,6 +192,15 @@ pub struct UpEnvironment {
     pub paths: Vec<PathBuf>,
     #[serde(default = "HashMap::new", skip_serializing_if = "HashMap::is_empty")]
     pub env_vars: HashMap<String, String>,
+    /// Only the commands providing the secrets are stored, never their values
+    #[serde(default = "HashMap::new", skip_serializing_if = "HashMap::is_empty")]
+    pub secrets: HashMap<String, EnvSecretConfig>,
+    #[serde(default = "HashMap::new", skip_serializing_if = "HashMap::is_empty")]
+    pub shell_aliases: HashMap<String, RepoShellAliasConfig>,
+    /// The up operation that added each of the environment variables
+    /// (by name) and paths, so the dynamic environment can be explained
+    #[serde(default = "HashMap::new", skip_serializing_if = "HashMap::is_empty")]
+    pub sources: HashMap<String, String>,
 }
 
 impl UpEnvironment {
@@ -182,9 +209,26 @@ impl UpEnvironment {
             versions: Vec::new(),
             paths: Vec::new(),
             env_vars: HashMap::new(),
+            secrets: HashMap::new(),
+            shell_aliases: HashMap::new(),
+            sources: HashMap::new(),
         }
     }
 
+    pub fn env_var_source(&self, key: &str) -> String {
+        self.sources
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| "up".to_string())
+    }
+
+    pub fn path_source(&self, path: &Path) -> String {
+        self.sources
+            .get(path.to_string_lossy().as_ref())
+            .cloned()
+            .unwrap_or_else(|| "up".to_string())
+    }
+
     pub fn versions_for_dir(&self, dir: &str) -> Vec<UpVersion> {
         let mut versions: BTreeMap<String, UpVersion> = BTreeMap::new();
 
@@ -0,0 +1,490 @@
+use std::path::Path;
+use std::process::exit;
+
+use crate::internal::commands::builtin::HelpCommand;
+use crate::internal::commands::utils::abs_path;
+use crate::internal::config::CommandSyntax;
+use crate::internal::config::SyntaxOptArg;
+use crate::internal::config::SyntaxOptArgType;
+use crate::internal::env::Shell;
+use crate::internal::user_interface::StringColor;
+use crate::internal::ORG_LOADER;
+use crate::omni_error;
+
+const ARG_ENV_PREFIX: &str = "OMNI_ARG_";
+
+/// Parses the arguments of a command following its syntax, and exports the
+/// parsed values in the environment as `OMNI_ARG_<NAME>` variables; repeated
+/// values are exported as `OMNI_ARG_<NAME>_<index>`, with their number in
+/// `OMNI_ARG_<NAME>_COUNT`. This exits with a usage error if the arguments
+/// do not match the syntax.
+pub fn export_syntax_arguments(syntax: &CommandSyntax, called_as: &[String], argv: &[String]) {
+    let vars = match syntax_arguments_env(syntax, argv) {
+        Ok(vars) => vars,
+        Err(err) => {
+            match err.kind() {
+                clap::error::ErrorKind::DisplayHelp
+                | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+                    HelpCommand::new().exec(called_as.to_vec());
+                }
+                _ => {
+                    let err_str = format!("{}", err);
+                    let err_str = err_str
+                        .split('\n')
+                        .take_while(|line| !line.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let err_str = err_str.trim_start_matches("error: ");
+                    omni_error!(err_str);
+                }
+            }
+            exit(1);
+        }
+    };
+
+    // Values exported by a parent omni command are not relevant to this one
+    for key in inherited_arguments_env() {
+        std::env::remove_var(key);
+    }
+
+    for (key, value) in vars {
+        std::env::set_var(key, value);
+    }
+}
+
+/// Parses the arguments of a command following its syntax, and returns the
+/// `OMNI_ARG_*` environment variables for the parsed values
+pub fn syntax_arguments_env(
+    syntax: &CommandSyntax,
+    argv: &[String],
+) -> Result<Vec<(String, String)>, clap::Error> {
+    let mut vars = vec![];
+    for (name, values) in parse_syntax_arguments(syntax, argv)? {
+        match values {
+            SyntaxArgValues::Single(value) => {
+                vars.push((format!("{}{}", ARG_ENV_PREFIX, name), value));
+            }
+            SyntaxArgValues::Multiple(values) => {
+                vars.push((
+                    format!("{}{}_COUNT", ARG_ENV_PREFIX, name),
+                    values.len().to_string(),
+                ));
+                for (idx, value) in values.into_iter().enumerate() {
+                    vars.push((format!("{}{}_{}", ARG_ENV_PREFIX, name, idx), value));
+                }
+            }
+        }
+    }
+
+    Ok(vars)
+}
+
+/// The `OMNI_ARG_*` environment variables of the current process, which
+/// were exported for the command being run
+pub fn inherited_arguments_env() -> Vec<String> {
+    std::env::vars()
+        .map(|(key, _)| key)
+        .filter(|key| key.starts_with(ARG_ENV_PREFIX))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SyntaxArgValues {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+fn parse_syntax_arguments(
+    syntax: &CommandSyntax,
+    argv: &[String],
+) -> Result<Vec<(String, SyntaxArgValues)>, clap::Error> {
+    let mut parse_argv = vec!["".to_string()];
+    parse_argv.extend(argv.iter().cloned());
+
+    let uses_help_flag = syntax.parameters.iter().any(|param| {
+        param
+            .flags()
+            .iter()
+            .any(|flag| flag == "-h" || flag == "--help")
+    });
+
+    let mut command = clap::Command::new("")
+        .disable_help_subcommand(true)
+        .disable_version_flag(true)
+        .disable_help_flag(uses_help_flag);
+
+    for param in syntax.parameters.iter() {
+        command = command.arg(clap_arg(param));
+    }
+
+    let matches = command.try_get_matches_from(&parse_argv)?;
+
+    let mut values = vec![];
+    for param in syntax.parameters.iter() {
+        let name = param.env_name();
+
+        let param_values = match param.arg_type {
+            _ if !takes_value(param) => vec![matches.get_flag(&name).to_string()],
+            Some(SyntaxOptArgType::Int) => matches
+                .get_many::<i64>(&name)
+                .map(|values| values.map(|value| value.to_string()).collect())
+                .unwrap_or_default(),
+            Some(SyntaxOptArgType::Path) => matches
+                .get_many::<String>(&name)
+                .map(|values| {
+                    values
+                        .map(|value| abs_path(value).to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => matches
+                .get_many::<String>(&name)
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+        };
+
+        if param.is_multiple() {
+            values.push((name, SyntaxArgValues::Multiple(param_values)));
+        } else if let Some(value) = param_values.into_iter().next() {
+            values.push((name, SyntaxArgValues::Single(value)));
+        }
+    }
+
+    Ok(values)
+}
+
+fn clap_arg(param: &SyntaxOptArg) -> clap::Arg {
+    let mut arg = clap::Arg::new(param.env_name());
+
+    for flag in param.flags() {
+        arg = match flag.strip_prefix("--") {
+            Some(long) => arg.long(long.to_string()),
+            None => match flag.chars().nth(1) {
+                Some(short) if flag.len() == 2 => arg.short(short),
+                _ => arg.long(flag.trim_start_matches('-').to_string()),
+            },
+        };
+    }
+
+    // Flags do not take values, so they cannot be required nor have a
+    // default value
+    if !takes_value(param) {
+        return arg.action(clap::ArgAction::SetTrue);
+    }
+
+    arg = match &param.arg_type {
+        Some(SyntaxOptArgType::Int) => arg
+            .value_parser(clap::value_parser!(i64))
+            .allow_negative_numbers(true),
+        Some(SyntaxOptArgType::Enum(values)) => {
+            arg.value_parser(clap::builder::PossibleValuesParser::new(values.clone()))
+        }
+        _ => arg,
+    };
+
+    if param.is_multiple() {
+        arg = arg.action(clap::ArgAction::Append);
+        if param.is_positional() {
+            arg = arg.num_args(1..);
+        }
+    } else {
+        arg = arg.action(clap::ArgAction::Set);
+    }
+
+    if let Some(default) = &param.default {
+        arg = arg.default_value(default.clone());
+    }
+
+    arg.required(param.required && param.default.is_none())
+}
+
+/// Prints the completions for the word at `comp_cword` in the arguments of
+/// a command, following its syntax: the flags of its options, and the
+/// values of the option or positional parameter being completed.
+pub fn complete_syntax(syntax: &CommandSyntax, comp_cword: usize, argv: &[String]) {
+    let current = argv.get(comp_cword).cloned().unwrap_or_default();
+
+    // Go over the previous words to find which parameter the current word
+    // is a value of, if any
+    let mut positional_idx = 0;
+    let mut only_positional = false;
+    let mut value_of = None;
+    for word in argv.iter().take(comp_cword) {
+        if value_of.take().is_some() {
+            continue;
+        }
+
+        if !only_positional && word == "--" {
+            only_positional = true;
+        } else if !only_positional && word.starts_with('-') && word.len() > 1 {
+            if !word.contains('=') {
+                value_of = find_option(syntax, word).filter(|param| takes_value(param));
+            }
+        } else if let Some(param) = positional_param(syntax, positional_idx) {
+            if !param.is_multiple() {
+                positional_idx += 1;
+            }
+        }
+    }
+
+    if let Some(param) = value_of {
+        print_completions(complete_values(param, &current));
+        return;
+    }
+
+    if !only_positional && current.starts_with('-') {
+        // Values can also be provided as `--option=value`
+        if let Some((flag, value)) = current.split_once('=') {
+            if let Some(param) = find_option(syntax, flag).filter(|param| takes_value(param)) {
+                print_completions(
+                    complete_values(param, value)
+                        .into_iter()
+                        .map(|value| format!("{}={}", flag, value))
+                        .collect(),
+                );
+            }
+            return;
+        }
+
+        print_completions(
+            syntax
+                .parameters
+                .iter()
+                .flat_map(|param| param.flags())
+                .filter(|flag| flag.starts_with(&current))
+                .collect(),
+        );
+        return;
+    }
+
+    if let Some(param) = positional_param(syntax, positional_idx) {
+        print_completions(complete_values(param, &current));
+    }
+}
+
+fn find_option<'a>(syntax: &'a CommandSyntax, word: &str) -> Option<&'a SyntaxOptArg> {
+    let flag = word.split_once('=').map_or(word, |(flag, _)| flag);
+    syntax
+        .parameters
+        .iter()
+        .find(|param| param.flags().iter().any(|f| f == flag))
+}
+
+fn positional_param(syntax: &CommandSyntax, idx: usize) -> Option<&SyntaxOptArg> {
+    syntax
+        .parameters
+        .iter()
+        .filter(|param| param.is_positional())
+        .nth(idx)
+}
+
+/// Whether the parameter takes a value; options without a type are flags,
+/// unless their name shows a value, such as `--format <format>`, or they
+/// have a command to complete their values
+fn takes_value(param: &SyntaxOptArg) -> bool {
+    if param.is_positional() || param.complete.is_some() {
+        return true;
+    }
+
+    match param.arg_type {
+        Some(SyntaxOptArgType::Flag) => false,
+        Some(_) => true,
+        None => param
+            .name
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .any(|part| !part.is_empty() && !part.starts_with('-')),
+    }
+}
+
+fn complete_values(param: &SyntaxOptArg, current: &str) -> Vec<String> {
+    // A completion command takes precedence over the type of the parameter
+    if let Some(complete) = &param.complete {
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(complete)
+            .arg(&param.name)
+            .arg(current)
+            .stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .output();
+
+        return match output {
+            Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter(|line| !line.is_empty() && line.starts_with(current))
+                .map(|line| line.to_string())
+                .collect(),
+            _ => vec![],
+        };
+    }
+
+    match &param.arg_type {
+        Some(SyntaxOptArgType::Enum(values)) => values
+            .iter()
+            .filter(|value| value.starts_with(current))
+            .cloned()
+            .collect(),
+        Some(SyntaxOptArgType::Path) => complete_path(current),
+        Some(SyntaxOptArgType::Repo) => {
+            let add_space = if Shell::current().is_fish() { " " } else { "" };
+            ORG_LOADER
+                .complete(current)
+                .into_iter()
+                .map(|repo| format!("{}{}", repo, add_space))
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+fn complete_path(current: &str) -> Vec<String> {
+    let (list_dir, prefix) = match current.rfind('/') {
+        Some(slash) => (current[..=slash].to_string(), current[..=slash].to_string()),
+        None => (".".to_string(), "".to_string()),
+    };
+
+    let entries = match std::fs::read_dir(Path::new(&list_dir)) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut completions = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = format!("{}{}", prefix, name);
+            if !path.starts_with(current) {
+                return None;
+            }
+
+            // Hidden files are only shown when explicitly asked for
+            if name.starts_with('.') && !current[prefix.len()..].starts_with('.') {
+                return None;
+            }
+
+            if entry.path().is_dir() {
+                Some(format!("{}/", path))
+            } else {
+                Some(path)
+            }
+        })
+        .collect::<Vec<_>>();
+    completions.sort();
+    completions
+}
+
+fn print_completions(completions: Vec<String>) {
+    for completion in completions {
+        println!("{}", completion);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syntax(parameters: Vec<SyntaxOptArg>) -> CommandSyntax {
+        CommandSyntax {
+            usage: None,
+            parameters,
+        }
+    }
+
+    fn param(name: &str, arg_type: Option<SyntaxOptArgType>) -> SyntaxOptArg {
+        SyntaxOptArg {
+            name: name.to_string(),
+            arg_type,
+            ..Default::default()
+        }
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn untyped_options_are_flags() {
+        let syntax = syntax(vec![
+            param("-v, --verbose", None),
+            param("--format <format>", None),
+            param("target", Some(SyntaxOptArgType::String)),
+        ]);
+
+        let values =
+            parse_syntax_arguments(&syntax, &argv(&["--verbose", "--format", "json", "prod"]))
+                .unwrap();
+        assert_eq!(
+            values,
+            vec![
+                (
+                    "VERBOSE".to_string(),
+                    SyntaxArgValues::Single("true".to_string())
+                ),
+                (
+                    "FORMAT".to_string(),
+                    SyntaxArgValues::Single("json".to_string())
+                ),
+                (
+                    "TARGET".to_string(),
+                    SyntaxArgValues::Single("prod".to_string())
+                ),
+            ]
+        );
+
+        let values = parse_syntax_arguments(&syntax, &argv(&["prod"])).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                (
+                    "VERBOSE".to_string(),
+                    SyntaxArgValues::Single("false".to_string())
+                ),
+                (
+                    "TARGET".to_string(),
+                    SyntaxArgValues::Single("prod".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn options_with_completion_take_values() {
+        let mut branch = param("--branch", None);
+        branch.complete = Some("git branch".to_string());
+        let syntax = syntax(vec![branch]);
+
+        let values = parse_syntax_arguments(&syntax, &argv(&["--branch", "main"])).unwrap();
+        assert_eq!(
+            values,
+            vec![(
+                "BRANCH".to_string(),
+                SyntaxArgValues::Single("main".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn typed_options_take_values() {
+        let syntax = syntax(vec![
+            param("-c, --count", Some(SyntaxOptArgType::Int)),
+            param("--dry-run", Some(SyntaxOptArgType::Flag)),
+        ]);
+
+        let values = parse_syntax_arguments(&syntax, &argv(&["-c", "-3", "--dry-run"])).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                (
+                    "COUNT".to_string(),
+                    SyntaxArgValues::Single("-3".to_string())
+                ),
+                (
+                    "DRY_RUN".to_string(),
+                    SyntaxArgValues::Single("true".to_string())
+                ),
+            ]
+        );
+
+        assert!(parse_syntax_arguments(&syntax, &argv(&["-c", "many"])).is_err());
+    }
+}
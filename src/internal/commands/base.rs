@@ -1,21 +1,32 @@
 use std::process::exit;
 
+use crate::internal::commands::arguments::complete_syntax;
+use crate::internal::commands::arguments::export_syntax_arguments;
 use crate::internal::commands::builtin::CdCommand;
 use crate::internal::commands::builtin::CloneCommand;
 use crate::internal::commands::builtin::ConfigBootstrapCommand;
 use crate::internal::commands::builtin::ConfigPathSwitchCommand;
+use crate::internal::commands::builtin::EnvCommand;
+use crate::internal::commands::builtin::EnvExportCommand;
+use crate::internal::commands::builtin::ExecCommand;
+use crate::internal::commands::builtin::ForeachCommand;
 use crate::internal::commands::builtin::HelpCommand;
 use crate::internal::commands::builtin::HookCommand;
 use crate::internal::commands::builtin::HookEnvCommand;
 use crate::internal::commands::builtin::HookInitCommand;
+use crate::internal::commands::builtin::HookPromptCommand;
 use crate::internal::commands::builtin::HookUuidCommand;
 use crate::internal::commands::builtin::ScopeCommand;
 use crate::internal::commands::builtin::StatusCommand;
 use crate::internal::commands::builtin::TidyCommand;
 use crate::internal::commands::builtin::UpCommand;
+use crate::internal::commands::builtin::UpExportCommand;
 use crate::internal::commands::fromconfig::ConfigCommand;
+use crate::internal::commands::fromjustfile::JustfileCommand;
 use crate::internal::commands::frommakefile::MakefileCommand;
+use crate::internal::commands::frompackagejson::PackageJsonCommand;
 use crate::internal::commands::frompath::PathCommand;
+use crate::internal::commands::fromtaskfile::TaskfileCommand;
 use crate::internal::commands::utils::abs_or_rel_path;
 use crate::internal::commands::void::VoidCommand;
 use crate::internal::config::CommandSyntax;
@@ -31,17 +42,26 @@ pub enum Command {
     BuiltinClone(CloneCommand),
     BuiltinConfigBootstrap(ConfigBootstrapCommand),
     BuiltinConfigPathSwitch(ConfigPathSwitchCommand),
+    BuiltinEnv(EnvCommand),
+    BuiltinEnvExport(EnvExportCommand),
+    BuiltinExec(ExecCommand),
+    BuiltinForeach(ForeachCommand),
     BuiltinHelp(HelpCommand),
     BuiltinHook(HookCommand),
     BuiltinHookEnv(HookEnvCommand),
     BuiltinHookInit(HookInitCommand),
+    BuiltinHookPrompt(HookPromptCommand),
     BuiltinHookUuid(HookUuidCommand),
     BuiltinScope(ScopeCommand),
     BuiltinStatus(StatusCommand),
     BuiltinTidy(TidyCommand),
     BuiltinUp(UpCommand),
+    BuiltinUpExport(UpExportCommand),
     FromConfig(ConfigCommand),
     FromMakefile(MakefileCommand),
+    FromJustfile(JustfileCommand),
+    FromPackageJson(PackageJsonCommand),
+    FromTaskfile(TaskfileCommand),
     FromPath(PathCommand),
     Void(VoidCommand),
 }
@@ -53,18 +73,27 @@ impl Command {
             Command::BuiltinClone(command) => command.name(),
             Command::BuiltinConfigBootstrap(command) => command.name(),
             Command::BuiltinConfigPathSwitch(command) => command.name(),
+            Command::BuiltinEnv(command) => command.name(),
+            Command::BuiltinEnvExport(command) => command.name(),
+            Command::BuiltinExec(command) => command.name(),
+            Command::BuiltinForeach(command) => command.name(),
             Command::BuiltinHelp(command) => command.name(),
             Command::BuiltinHook(command) => command.name(),
             Command::BuiltinHookEnv(command) => command.name(),
             Command::BuiltinHookInit(command) => command.name(),
+            Command::BuiltinHookPrompt(command) => command.name(),
             Command::BuiltinHookUuid(command) => command.name(),
             Command::BuiltinScope(command) => command.name(),
             Command::BuiltinStatus(command) => command.name(),
             Command::BuiltinTidy(command) => command.name(),
             Command::BuiltinUp(command) => command.name(),
+            Command::BuiltinUpExport(command) => command.name(),
             Command::FromPath(command) => command.name(),
             Command::FromConfig(command) => command.name(),
             Command::FromMakefile(command) => command.name(),
+            Command::FromJustfile(command) => command.name(),
+            Command::FromPackageJson(command) => command.name(),
+            Command::FromTaskfile(command) => command.name(),
             Command::Void(command) => command.name(),
         }
     }
@@ -79,18 +108,27 @@ impl Command {
             Command::BuiltinClone(command) => command.aliases(),
             Command::BuiltinConfigBootstrap(command) => command.aliases(),
             Command::BuiltinConfigPathSwitch(command) => command.aliases(),
+            Command::BuiltinEnv(command) => command.aliases(),
+            Command::BuiltinEnvExport(command) => command.aliases(),
+            Command::BuiltinExec(command) => command.aliases(),
+            Command::BuiltinForeach(command) => command.aliases(),
             Command::BuiltinHelp(command) => command.aliases(),
             Command::BuiltinHook(command) => command.aliases(),
             Command::BuiltinHookEnv(command) => command.aliases(),
             Command::BuiltinHookInit(command) => command.aliases(),
+            Command::BuiltinHookPrompt(command) => command.aliases(),
             Command::BuiltinHookUuid(command) => command.aliases(),
             Command::BuiltinScope(command) => command.aliases(),
             Command::BuiltinStatus(command) => command.aliases(),
             Command::BuiltinTidy(command) => command.aliases(),
             Command::BuiltinUp(command) => command.aliases(),
+            Command::BuiltinUpExport(command) => command.aliases(),
             Command::FromPath(command) => command.aliases(),
             Command::FromConfig(command) => command.aliases(),
             Command::FromMakefile(command) => command.aliases(),
+            Command::FromJustfile(command) => command.aliases(),
+            Command::FromPackageJson(command) => command.aliases(),
+            Command::FromTaskfile(command) => command.aliases(),
             Command::Void(command) => command.aliases(),
         }
     }
@@ -113,7 +151,12 @@ impl Command {
     pub fn has_source(&self) -> bool {
         matches!(
             self,
-            Command::FromPath(_) | Command::FromConfig(_) | Command::FromMakefile(_)
+            Command::FromPath(_)
+                | Command::FromConfig(_)
+                | Command::FromMakefile(_)
+                | Command::FromJustfile(_)
+                | Command::FromPackageJson(_)
+                | Command::FromTaskfile(_)
         )
     }
 
@@ -123,18 +166,27 @@ impl Command {
             Command::BuiltinClone(_) => "builtin".to_string(),
             Command::BuiltinConfigBootstrap(_) => "builtin".to_string(),
             Command::BuiltinConfigPathSwitch(_) => "builtin".to_string(),
+            Command::BuiltinEnv(_) => "builtin".to_string(),
+            Command::BuiltinEnvExport(_) => "builtin".to_string(),
+            Command::BuiltinExec(_) => "builtin".to_string(),
+            Command::BuiltinForeach(_) => "builtin".to_string(),
             Command::BuiltinHelp(_) => "builtin".to_string(),
             Command::BuiltinHook(_) => "builtin".to_string(),
             Command::BuiltinHookEnv(_) => "builtin".to_string(),
             Command::BuiltinHookInit(_) => "builtin".to_string(),
+            Command::BuiltinHookPrompt(_) => "builtin".to_string(),
             Command::BuiltinHookUuid(_) => "builtin".to_string(),
             Command::BuiltinScope(_) => "builtin".to_string(),
             Command::BuiltinStatus(_) => "builtin".to_string(),
             Command::BuiltinTidy(_) => "builtin".to_string(),
             Command::BuiltinUp(_) => "builtin".to_string(),
+            Command::BuiltinUpExport(_) => "builtin".to_string(),
             Command::FromPath(command) => command.source(),
             Command::FromConfig(command) => command.source(),
             Command::FromMakefile(command) => command.source(),
+            Command::FromJustfile(command) => command.source(),
+            Command::FromPackageJson(command) => command.source(),
+            Command::FromTaskfile(command) => command.source(),
             Command::Void(_) => "auto-generated".to_string(),
         }
     }
@@ -168,6 +220,7 @@ impl Command {
         let path = abs_or_rel_path(&source);
         match self {
             Command::FromMakefile(command) => format!("{}:{}", path, command.lineno()),
+            Command::FromJustfile(command) => format!("{}:{}", path, command.lineno()),
             _ => path,
         }
     }
@@ -178,18 +231,27 @@ impl Command {
             Command::BuiltinClone(command) => command.syntax(),
             Command::BuiltinConfigBootstrap(command) => command.syntax(),
             Command::BuiltinConfigPathSwitch(command) => command.syntax(),
+            Command::BuiltinEnv(command) => command.syntax(),
+            Command::BuiltinEnvExport(command) => command.syntax(),
+            Command::BuiltinExec(command) => command.syntax(),
+            Command::BuiltinForeach(command) => command.syntax(),
             Command::BuiltinHelp(command) => command.syntax(),
             Command::BuiltinHook(command) => command.syntax(),
             Command::BuiltinHookEnv(command) => command.syntax(),
             Command::BuiltinHookInit(command) => command.syntax(),
+            Command::BuiltinHookPrompt(command) => command.syntax(),
             Command::BuiltinHookUuid(command) => command.syntax(),
             Command::BuiltinScope(command) => command.syntax(),
             Command::BuiltinStatus(command) => command.syntax(),
             Command::BuiltinTidy(command) => command.syntax(),
             Command::BuiltinUp(command) => command.syntax(),
+            Command::BuiltinUpExport(command) => command.syntax(),
             Command::FromPath(command) => command.syntax(),
             Command::FromConfig(command) => command.syntax(),
             Command::FromMakefile(command) => command.syntax(),
+            Command::FromJustfile(command) => command.syntax(),
+            Command::FromPackageJson(command) => command.syntax(),
+            Command::FromTaskfile(command) => command.syntax(),
             Command::Void(command) => command.syntax(),
         }
     }
@@ -200,18 +262,27 @@ impl Command {
             Command::BuiltinClone(command) => command.category(),
             Command::BuiltinConfigBootstrap(command) => command.category(),
             Command::BuiltinConfigPathSwitch(command) => command.category(),
+            Command::BuiltinEnv(command) => command.category(),
+            Command::BuiltinEnvExport(command) => command.category(),
+            Command::BuiltinExec(command) => command.category(),
+            Command::BuiltinForeach(command) => command.category(),
             Command::BuiltinHelp(command) => command.category(),
             Command::BuiltinHook(command) => command.category(),
             Command::BuiltinHookEnv(command) => command.category(),
             Command::BuiltinHookInit(command) => command.category(),
+            Command::BuiltinHookPrompt(command) => command.category(),
             Command::BuiltinHookUuid(command) => command.category(),
             Command::BuiltinScope(command) => command.category(),
             Command::BuiltinStatus(command) => command.category(),
             Command::BuiltinTidy(command) => command.category(),
             Command::BuiltinUp(command) => command.category(),
+            Command::BuiltinUpExport(command) => command.category(),
             Command::FromPath(command) => command.category(),
             Command::FromConfig(command) => command.category(),
             Command::FromMakefile(command) => command.category(),
+            Command::FromJustfile(command) => command.category(),
+            Command::FromPackageJson(command) => command.category(),
+            Command::FromTaskfile(command) => command.category(),
             Command::Void(command) => command.category(),
         }
     }
@@ -222,18 +293,27 @@ impl Command {
             Command::BuiltinClone(command) => command.help(),
             Command::BuiltinConfigBootstrap(command) => command.help(),
             Command::BuiltinConfigPathSwitch(command) => command.help(),
+            Command::BuiltinEnv(command) => command.help(),
+            Command::BuiltinEnvExport(command) => command.help(),
+            Command::BuiltinExec(command) => command.help(),
+            Command::BuiltinForeach(command) => command.help(),
             Command::BuiltinHelp(command) => command.help(),
             Command::BuiltinHook(command) => command.help(),
             Command::BuiltinHookEnv(command) => command.help(),
             Command::BuiltinHookInit(command) => command.help(),
+            Command::BuiltinHookPrompt(command) => command.help(),
             Command::BuiltinHookUuid(command) => command.help(),
             Command::BuiltinScope(command) => command.help(),
             Command::BuiltinStatus(command) => command.help(),
             Command::BuiltinTidy(command) => command.help(),
             Command::BuiltinUp(command) => command.help(),
+            Command::BuiltinUpExport(command) => command.help(),
             Command::FromPath(command) => command.help(),
             Command::FromConfig(command) => command.help(),
             Command::FromMakefile(command) => command.help(),
+            Command::FromJustfile(command) => command.help(),
+            Command::FromPackageJson(command) => command.help(),
+            Command::FromTaskfile(command) => command.help(),
             Command::Void(command) => command.help(),
         };
 
@@ -325,11 +405,17 @@ impl Command {
         } else {
             self.name().clone()
         };
-        let name = name.join(" ");
+        let name_parts = name;
+        let name = name_parts.join(" ");
         std::env::set_var("OMNI_SUBCOMMAND", name.clone());
 
         match self {
-            Command::FromPath(_) | Command::FromConfig(_) | Command::FromMakefile(_) => {
+            Command::FromPath(_)
+            | Command::FromConfig(_)
+            | Command::FromMakefile(_)
+            | Command::FromJustfile(_)
+            | Command::FromPackageJson(_)
+            | Command::FromTaskfile(_) => {
                 // Check if the workdir where the command is located is trusted
                 if !is_trusted_or_ask(
                     &self.source_dir(),
@@ -347,23 +433,40 @@ impl Command {
             _ => {}
         }
 
+        // Parse the arguments of the commands with a typed syntax, so that
+        // they do not have to do it themselves
+        if let Command::FromPath(_) | Command::FromConfig(_) = self {
+            if let Some(syntax) = self.syntax().filter(|syntax| syntax.parses_args()) {
+                export_syntax_arguments(&syntax, &name_parts, &argv);
+            }
+        }
+
         match self {
             Command::BuiltinCd(command) => command.exec(argv),
             Command::BuiltinClone(command) => command.exec(argv),
             Command::BuiltinConfigBootstrap(command) => command.exec(argv),
             Command::BuiltinConfigPathSwitch(command) => command.exec(argv),
+            Command::BuiltinEnv(command) => command.exec(argv),
+            Command::BuiltinEnvExport(command) => command.exec(argv),
+            Command::BuiltinExec(command) => command.exec(argv),
+            Command::BuiltinForeach(command) => command.exec(argv),
             Command::BuiltinHelp(command) => command.exec(argv),
             Command::BuiltinHook(_command) => {}
             Command::BuiltinHookEnv(command) => command.exec(argv),
             Command::BuiltinHookInit(command) => command.exec(argv),
+            Command::BuiltinHookPrompt(command) => command.exec(argv),
             Command::BuiltinHookUuid(command) => command.exec(argv),
             Command::BuiltinScope(command) => command.exec(argv),
             Command::BuiltinStatus(command) => command.exec(argv),
             Command::BuiltinTidy(command) => command.exec(argv),
             Command::BuiltinUp(command) => command.exec(argv),
+            Command::BuiltinUpExport(command) => command.exec(argv),
             Command::FromPath(command) => command.exec(argv, called_as),
             Command::FromConfig(command) => command.exec(argv),
             Command::FromMakefile(command) => command.exec(argv),
+            Command::FromJustfile(command) => command.exec(argv),
+            Command::FromPackageJson(command) => command.exec(argv),
+            Command::FromTaskfile(command) => command.exec(argv),
             Command::Void(_) => {}
         }
         panic!("Command::exec() not implemented");
@@ -375,25 +478,45 @@ impl Command {
             Command::BuiltinClone(command) => command.autocompletion(),
             Command::BuiltinConfigBootstrap(command) => command.autocompletion(),
             Command::BuiltinConfigPathSwitch(command) => command.autocompletion(),
+            Command::BuiltinEnv(command) => command.autocompletion(),
+            Command::BuiltinEnvExport(command) => command.autocompletion(),
+            Command::BuiltinExec(command) => command.autocompletion(),
+            Command::BuiltinForeach(command) => command.autocompletion(),
             Command::BuiltinHelp(command) => command.autocompletion(),
             Command::BuiltinHook(command) => command.autocompletion(),
             Command::BuiltinHookEnv(command) => command.autocompletion(),
             Command::BuiltinHookInit(command) => command.autocompletion(),
+            Command::BuiltinHookPrompt(command) => command.autocompletion(),
             Command::BuiltinHookUuid(command) => command.autocompletion(),
             Command::BuiltinScope(command) => command.autocompletion(),
             Command::BuiltinStatus(command) => command.autocompletion(),
             Command::BuiltinTidy(command) => command.autocompletion(),
             Command::BuiltinUp(command) => command.autocompletion(),
-            Command::FromPath(command) => command.autocompletion(),
-            Command::FromConfig(_command) => false,
+            Command::BuiltinUpExport(command) => command.autocompletion(),
+            Command::FromPath(command) => command.autocompletion() || self.has_syntax_completion(),
+            Command::FromConfig(_command) => self.has_syntax_completion(),
             Command::FromMakefile(_command) => false,
+            Command::FromJustfile(_command) => false,
+            Command::FromPackageJson(_command) => false,
+            Command::FromTaskfile(_command) => false,
             Command::Void(_) => false,
         }
     }
 
+    fn has_syntax_completion(&self) -> bool {
+        self.syntax()
+            .map(|syntax| !syntax.parameters.is_empty())
+            .unwrap_or(false)
+    }
+
     pub fn autocomplete(&self, comp_cword: usize, argv: Vec<String>) {
         match self {
-            Command::FromPath(_) | Command::FromConfig(_) | Command::FromMakefile(_) => {
+            Command::FromPath(_)
+            | Command::FromConfig(_)
+            | Command::FromMakefile(_)
+            | Command::FromJustfile(_)
+            | Command::FromPackageJson(_)
+            | Command::FromTaskfile(_) => {
                 // Check if the workdir where the command is located is trusted
                 if !is_trusted(&self.source_dir()) {
                     exit(1);
@@ -407,23 +530,44 @@ impl Command {
             Command::BuiltinClone(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinConfigBootstrap(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinConfigPathSwitch(command) => command.autocomplete(comp_cword, argv),
+            Command::BuiltinEnv(command) => command.autocomplete(comp_cword, argv),
+            Command::BuiltinEnvExport(command) => command.autocomplete(comp_cword, argv),
+            Command::BuiltinExec(command) => command.autocomplete(comp_cword, argv),
+            Command::BuiltinForeach(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinHelp(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinHook(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinHookEnv(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinHookInit(command) => command.autocomplete(comp_cword, argv),
+            Command::BuiltinHookPrompt(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinHookUuid(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinScope(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinStatus(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinTidy(command) => command.autocomplete(comp_cword, argv),
             Command::BuiltinUp(command) => command.autocomplete(comp_cword, argv),
+            Command::BuiltinUpExport(command) => command.autocomplete(comp_cword, argv),
             Command::FromPath(command) => {
                 // Load the dynamic environment for that command
                 update_dynamic_env_for_command(&self.source_dir());
 
-                command.autocomplete(comp_cword, argv)
+                // Commands handling their own completion take precedence
+                // over the completion from their syntax
+                if command.autocompletion() {
+                    command.autocomplete(comp_cword, argv)
+                } else if let Some(syntax) = self.syntax() {
+                    complete_syntax(&syntax, comp_cword, &argv);
+                }
+            }
+            Command::FromConfig(_command) => {
+                update_dynamic_env_for_command(&self.source_dir());
+
+                if let Some(syntax) = self.syntax() {
+                    complete_syntax(&syntax, comp_cword, &argv);
+                }
             }
-            Command::FromConfig(_command) => {}
             Command::FromMakefile(_command) => {}
+            Command::FromJustfile(_command) => {}
+            Command::FromPackageJson(_command) => {}
+            Command::FromTaskfile(_command) => {}
             Command::Void(_) => {}
         }
     }
@@ -432,6 +576,9 @@ impl Command {
         match self {
             Command::FromConfig(_) => 1,
             Command::FromMakefile(_) => 2,
+            Command::FromJustfile(_) => 3,
+            Command::FromPackageJson(_) => 4,
+            Command::FromTaskfile(_) => 5,
             Command::Void(command) => command.type_sort_order(),
             _ => match self.category() {
                 Some(_) => 0,
@@ -153,6 +153,7 @@ impl CdCommand {
                         .to_string()
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "--[no-]include-packages".to_string(),
@@ -165,6 +166,7 @@ impl CdCommand {
                         .to_string()
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "repo".to_string(),
@@ -178,6 +180,7 @@ impl CdCommand {
                         .to_string()
                     ),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
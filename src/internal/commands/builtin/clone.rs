@@ -150,6 +150,7 @@ impl CloneCommand {
                             .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "repo".to_string(),
@@ -163,11 +164,13 @@ impl CloneCommand {
                         .to_string(),
                     ),
                     required: true,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "options...".to_string(),
                     desc: Some("Any additional options to pass to git clone.".to_string()),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
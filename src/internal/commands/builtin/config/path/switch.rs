@@ -174,6 +174,7 @@ impl ConfigPathSwitchCommand {
                         .to_string()
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "repo".to_string(),
@@ -187,6 +188,7 @@ impl ConfigPathSwitchCommand {
                         .to_string()
                     ),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
@@ -0,0 +1,223 @@
+use std::process::exit;
+
+use once_cell::sync::OnceCell;
+
+use crate::internal::commands::builtin::HelpCommand;
+use crate::internal::config::CommandSyntax;
+use crate::internal::config::SyntaxOptArg;
+use crate::internal::dynenv::explain_dynamic_env;
+use crate::internal::dynenv::DynamicEnvChange;
+use crate::internal::dynenv::DynamicEnvChangeOperation;
+use crate::internal::user_interface::StringColor;
+use crate::omni_error;
+use crate::omni_info;
+
+#[derive(Debug, Clone)]
+struct EnvCommandArgs {
+    json: bool,
+}
+
+impl EnvCommandArgs {
+    fn parse(argv: Vec<String>) -> Self {
+        let mut parse_argv = vec!["".to_string()];
+        parse_argv.extend(argv);
+
+        let matches = clap::Command::new("")
+            .disable_help_subcommand(true)
+            .disable_version_flag(true)
+            .arg(
+                clap::Arg::new("json")
+                    .long("json")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .try_get_matches_from(&parse_argv);
+
+        if let Err(err) = matches {
+            match err.kind() {
+                clap::error::ErrorKind::DisplayHelp
+                | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+                    HelpCommand::new().exec(vec!["env".to_string()]);
+                }
+                clap::error::ErrorKind::DisplayVersion => {
+                    unreachable!("version flag is disabled");
+                }
+                _ => {
+                    let err_str = format!("{}", err);
+                    let err_str = err_str
+                        .split('\n')
+                        .take_while(|line| !line.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let err_str = err_str.trim_start_matches("error: ");
+                    omni_error!(err_str);
+                }
+            }
+            exit(1);
+        }
+
+        let matches = matches.unwrap();
+
+        Self {
+            json: *matches.get_one::<bool>("json").unwrap_or(&false),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnvCommand {
+    cli_args: OnceCell<EnvCommandArgs>,
+}
+
+impl EnvCommand {
+    pub fn new() -> Self {
+        Self {
+            cli_args: OnceCell::new(),
+        }
+    }
+
+    fn cli_args(&self) -> &EnvCommandArgs {
+        self.cli_args.get_or_init(|| {
+            omni_error!("command arguments not initialized");
+            exit(1);
+        })
+    }
+
+    pub fn name(&self) -> Vec<String> {
+        vec!["env".to_string()]
+    }
+
+    pub fn aliases(&self) -> Vec<Vec<String>> {
+        vec![]
+    }
+
+    pub fn help(&self) -> Option<String> {
+        Some(
+            concat!(
+                "Explain the dynamic environment of the current directory\n",
+                "\n",
+                "This shows each environment variable that the dynamic environment sets, ",
+                "unsets, or adds to or removes from a list for the current directory, the ",
+                "value it will be restored to when leaving the directory, and the source of ",
+                "the change (tool version, homebrew, bundler, configuration \x1B[3menv\x1B[0m, ",
+                "etc.). The shell aliases and functions defined for the directory are also ",
+                "listed."
+            )
+            .to_string(),
+        )
+    }
+
+    pub fn syntax(&self) -> Option<CommandSyntax> {
+        Some(CommandSyntax {
+            usage: None,
+            parameters: vec![SyntaxOptArg {
+                name: "--json".to_string(),
+                desc: Some("Output the dynamic environment changes as JSON".to_string()),
+                required: false,
+                ..Default::default()
+            }],
+        })
+    }
+
+    pub fn category(&self) -> Option<Vec<String>> {
+        Some(vec!["General".to_string()])
+    }
+
+    pub fn exec(&self, argv: Vec<String>) {
+        if self.cli_args.set(EnvCommandArgs::parse(argv)).is_err() {
+            unreachable!();
+        }
+
+        let explanation = explain_dynamic_env();
+
+        if self.cli_args().json {
+            match serde_json::to_string_pretty(&explanation) {
+                Ok(json) => println!("{}", json),
+                Err(err) => {
+                    omni_error!(format!("failed to serialize dynamic environment: {}", err));
+                    exit(1);
+                }
+            }
+            exit(0);
+        }
+
+        if explanation.changes.is_empty() {
+            omni_info!("no dynamic environment for the current directory");
+            exit(0);
+        }
+
+        println!("{}", "Loaded features".bold());
+        if explanation.features.is_empty() {
+            println!("  {}", "none".light_black());
+        } else {
+            println!("  {}", explanation.features.join(", "));
+        }
+
+        println!("\n{}", "Environment changes".bold());
+        for change in explanation.changes.iter() {
+            self.print_change(change);
+        }
+
+        exit(0);
+    }
+
+    fn print_change(&self, change: &DynamicEnvChange) {
+        let operation = match change.operation {
+            DynamicEnvChangeOperation::Set => change.operation.as_str().light_green(),
+            DynamicEnvChangeOperation::Unset | DynamicEnvChangeOperation::Remove => {
+                change.operation.as_str().light_red()
+            }
+            DynamicEnvChangeOperation::Prepend | DynamicEnvChangeOperation::Append => {
+                change.operation.as_str().light_blue()
+            }
+            DynamicEnvChangeOperation::Alias | DynamicEnvChangeOperation::Function => {
+                change.operation.as_str().light_magenta()
+            }
+        };
+
+        let value = match &change.value {
+            Some(value)
+                if matches!(
+                    change.operation,
+                    DynamicEnvChangeOperation::Set | DynamicEnvChangeOperation::Alias
+                ) =>
+            {
+                format!("={}", value)
+            }
+            // Function bodies can span multiple lines, so they are not shown
+            Some(_) if change.operation == DynamicEnvChangeOperation::Function => "".to_string(),
+            Some(value) => format!(" {}", value),
+            None => "".to_string(),
+        };
+
+        println!(
+            "  {} {}{} {}",
+            operation,
+            change.name.bold(),
+            value,
+            format!("({})", change.source).light_black(),
+        );
+
+        // Lists are restored by reverting the operation on the value that
+        // the variable has when leaving, so the previous value of the whole
+        // list is only relevant for values that are replaced
+        if matches!(
+            change.operation,
+            DynamicEnvChangeOperation::Set | DynamicEnvChangeOperation::Unset
+        ) {
+            let previous = match &change.previous {
+                Some(previous) => previous.to_string(),
+                None => "unset".italic(),
+            };
+            println!("    {} {}", "restores to:".light_black(), previous);
+        }
+    }
+
+    pub fn autocompletion(&self) -> bool {
+        true
+    }
+
+    pub fn autocomplete(&self, _comp_cword: usize, _argv: Vec<String>) {
+        println!("--json");
+        exit(0);
+    }
+}
@@ -0,0 +1,351 @@
+use std::io::Write;
+use std::process::exit;
+
+use once_cell::sync::OnceCell;
+use uuid::Uuid;
+
+use crate::internal::commands::builtin::HelpCommand;
+use crate::internal::config::CommandSyntax;
+use crate::internal::config::SyntaxOptArg;
+use crate::internal::dynenv::dynamic_env_variables;
+use crate::internal::dynenv::DynamicEnvVariables;
+use crate::internal::user_interface::StringColor;
+use crate::omni_error;
+use crate::omni_info;
+use crate::omni_warning;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EnvExportFormat {
+    Dotenv,
+    Json,
+    GithubEnv,
+    Systemd,
+}
+
+impl EnvExportFormat {
+    fn from_str(format: &str) -> Option<Self> {
+        match format {
+            "dotenv" => Some(Self::Dotenv),
+            "json" => Some(Self::Json),
+            "github-env" => Some(Self::GithubEnv),
+            "systemd" => Some(Self::Systemd),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EnvExportCommandArgs {
+    format: EnvExportFormat,
+}
+
+impl EnvExportCommandArgs {
+    fn parse(argv: Vec<String>) -> Self {
+        let mut parse_argv = vec!["".to_string()];
+        parse_argv.extend(argv);
+
+        let matches = clap::Command::new("")
+            .disable_help_subcommand(true)
+            .disable_version_flag(true)
+            .arg(
+                clap::Arg::new("format")
+                    .long("format")
+                    .required(true)
+                    .action(clap::ArgAction::Set)
+                    .value_parser(clap::builder::PossibleValuesParser::new([
+                        "dotenv",
+                        "json",
+                        "github-env",
+                        "systemd",
+                    ])),
+            )
+            .try_get_matches_from(&parse_argv);
+
+        if let Err(err) = matches {
+            match err.kind() {
+                clap::error::ErrorKind::DisplayHelp
+                | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+                    HelpCommand::new().exec(vec!["env".to_string(), "export".to_string()]);
+                }
+                clap::error::ErrorKind::DisplayVersion => {
+                    unreachable!("version flag is disabled");
+                }
+                _ => {
+                    let err_str = format!("{}", err);
+                    let err_str = err_str
+                        .split('\n')
+                        .take_while(|line| !line.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let err_str = err_str.trim_start_matches("error: ");
+                    omni_error!(err_str);
+                }
+            }
+            exit(1);
+        }
+
+        let matches = matches.unwrap();
+
+        // We can consider those unwraps safe, as the format is
+        // required and its values are validated by clap
+        let format = EnvExportFormat::from_str(matches.get_one::<String>("format").unwrap());
+
+        Self {
+            format: format.unwrap(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnvExportCommand {
+    cli_args: OnceCell<EnvExportCommandArgs>,
+}
+
+impl EnvExportCommand {
+    pub fn new() -> Self {
+        Self {
+            cli_args: OnceCell::new(),
+        }
+    }
+
+    fn cli_args(&self) -> &EnvExportCommandArgs {
+        self.cli_args.get_or_init(|| {
+            omni_error!("command arguments not initialized");
+            exit(1);
+        })
+    }
+
+    pub fn name(&self) -> Vec<String> {
+        vec!["env".to_string(), "export".to_string()]
+    }
+
+    pub fn aliases(&self) -> Vec<Vec<String>> {
+        vec![]
+    }
+
+    pub fn help(&self) -> Option<String> {
+        Some(
+            concat!(
+                "Export the dynamic environment of the current directory\n",
+                "\n",
+                "The variables set by the dynamic environment are written in a format that ",
+                "can be used by other tools, such as a dotenv file for IDE run configurations ",
+                "or \x1B[3mdocker run --env-file\x1B[0m, a JSON object, the files of GitHub ",
+                "Actions (\x1B[3m$GITHUB_ENV\x1B[0m and \x1B[3m$GITHUB_PATH\x1B[0m), or a systemd ",
+                "environment file.",
+            )
+            .to_string(),
+        )
+    }
+
+    pub fn syntax(&self) -> Option<CommandSyntax> {
+        Some(CommandSyntax {
+            usage: None,
+            parameters: vec![SyntaxOptArg {
+                name: "--format".to_string(),
+                desc: Some("The format to export to (dotenv/json/github-env/systemd)".to_string()),
+                required: true,
+                ..Default::default()
+            }],
+        })
+    }
+
+    pub fn category(&self) -> Option<Vec<String>> {
+        Some(vec!["General".to_string()])
+    }
+
+    pub fn exec(&self, argv: Vec<String>) {
+        if self
+            .cli_args
+            .set(EnvExportCommandArgs::parse(argv))
+            .is_err()
+        {
+            unreachable!();
+        }
+
+        let mut variables = dynamic_env_variables();
+
+        // Secrets would end up in plain text in files that are not meant
+        // to hold them, so they are not exported in those formats
+        if matches!(
+            self.cli_args().format,
+            EnvExportFormat::Dotenv | EnvExportFormat::Systemd
+        ) {
+            for key in std::mem::take(&mut variables.secrets) {
+                variables.env.remove(&key);
+                omni_warning!(format!(
+                    "secret environment variable {} is never exported to this format, skipping",
+                    key.light_yellow()
+                ));
+            }
+        }
+
+        match self.cli_args().format {
+            EnvExportFormat::Dotenv => print!("{}", render_dotenv(&variables)),
+            EnvExportFormat::Json => match render_json(&variables) {
+                Ok(json) => println!("{}", json),
+                Err(err) => {
+                    omni_error!(format!("failed to serialize dynamic environment: {}", err));
+                    exit(1);
+                }
+            },
+            EnvExportFormat::GithubEnv => export_github_env(&variables),
+            EnvExportFormat::Systemd => print!("{}", render_systemd(&variables)),
+        }
+
+        exit(0);
+    }
+
+    pub fn autocompletion(&self) -> bool {
+        true
+    }
+
+    pub fn autocomplete(&self, comp_cword: usize, argv: Vec<String>) {
+        if comp_cword > 0 && argv.get(comp_cword - 1).map(|arg| arg.as_str()) == Some("--format") {
+            println!("dotenv");
+            println!("json");
+            println!("github-env");
+            println!("systemd");
+            exit(0);
+        }
+
+        println!("--format");
+        exit(0);
+    }
+}
+
+/// Iterates over the variables that are set; the formats other than JSON
+/// do not have a way to unset a variable, so those are skipped.
+fn set_variables(variables: &DynamicEnvVariables) -> impl Iterator<Item = (&String, &String)> {
+    variables
+        .env
+        .iter()
+        .filter_map(|(key, value)| value.as_ref().map(|value| (key, value)))
+}
+
+fn render_dotenv(variables: &DynamicEnvVariables) -> String {
+    let mut content = String::new();
+    for (key, value) in set_variables(variables) {
+        let is_simple = !value.is_empty()
+            && !value
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | '#' | '$' | '`'));
+
+        if is_simple {
+            content.push_str(&format!("{}={}\n", key, value));
+        } else {
+            content.push_str(&format!("{}={}\n", key, double_quote(value)));
+        }
+    }
+    content
+}
+
+fn render_json(variables: &DynamicEnvVariables) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&variables.env)
+}
+
+fn render_systemd(variables: &DynamicEnvVariables) -> String {
+    let mut content = String::new();
+    for (key, value) in set_variables(variables) {
+        content.push_str(&format!("{}={}\n", key, double_quote(value)));
+    }
+    content
+}
+
+fn double_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+fn render_github_env(variables: &DynamicEnvVariables, include_path: bool) -> String {
+    let mut content = String::new();
+    for (key, value) in set_variables(variables) {
+        if key == "PATH" && !include_path {
+            continue;
+        }
+
+        if value.contains('\n') {
+            let delimiter = format!("ghadelimiter_{}", Uuid::new_v4());
+            content.push_str(&format!(
+                "{}<<{}\n{}\n{}\n",
+                key, delimiter, value, delimiter
+            ));
+        } else {
+            content.push_str(&format!("{}={}\n", key, value));
+        }
+    }
+    content
+}
+
+fn render_github_masks(variables: &DynamicEnvVariables) -> String {
+    let mut content = String::new();
+    for (key, value) in set_variables(variables) {
+        if !variables.secrets.contains(key) {
+            continue;
+        }
+
+        // Masks only apply to single lines
+        for line in value.lines().filter(|line| !line.trim().is_empty()) {
+            content.push_str(&format!("::add-mask::{}\n", line));
+        }
+    }
+    content
+}
+
+fn render_github_path(variables: &DynamicEnvVariables) -> String {
+    // Each line of $GITHUB_PATH is prepended to the PATH, so writing the
+    // paths in the order they were added results in the same PATH
+    let mut content = String::new();
+    for path in variables.paths.iter() {
+        content.push_str(&format!("{}\n", path));
+    }
+    content
+}
+
+/// When running in GitHub Actions, the variables are directly appended to
+/// the $GITHUB_ENV and $GITHUB_PATH files; otherwise, they are printed, with
+/// the PATH as a regular variable.
+fn export_github_env(variables: &DynamicEnvVariables) {
+    let github_env = std::env::var("GITHUB_ENV").ok().filter(|f| !f.is_empty());
+    let github_path = std::env::var("GITHUB_PATH").ok().filter(|f| !f.is_empty());
+
+    if github_env.is_none() {
+        print!("{}", render_github_env(variables, github_path.is_none()));
+    } else {
+        // Ask GitHub Actions to mask the secrets in the logs before they
+        // are made available to the next steps
+        print!("{}", render_github_masks(variables));
+    }
+
+    for (file, content) in [
+        (github_env, render_github_env(variables, false)),
+        (github_path, render_github_path(variables)),
+    ] {
+        let file = match file {
+            Some(file) => file,
+            None => continue,
+        };
+
+        let result = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file)
+            .and_then(|mut file| file.write_all(content.as_bytes()));
+
+        match result {
+            Ok(()) => {
+                omni_info!(format!(
+                    "exported dynamic environment to {}",
+                    file.light_blue()
+                ));
+            }
+            Err(err) => {
+                omni_error!(format!("failed to write {}: {}", file, err));
+                exit(1);
+            }
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub(crate) mod base;
+pub(crate) use base::EnvCommand;
+
+pub(crate) mod export;
+pub(crate) use export::EnvExportCommand;
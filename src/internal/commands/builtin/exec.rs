@@ -0,0 +1,217 @@
+use std::os::unix::process::CommandExt;
+use std::process::exit;
+
+use once_cell::sync::OnceCell;
+
+use crate::internal::commands::builtin::HelpCommand;
+use crate::internal::commands::utils::abs_path;
+use crate::internal::config::CommandSyntax;
+use crate::internal::config::SyntaxOptArg;
+use crate::internal::dynenv::update_dynamic_env_for_command;
+use crate::internal::user_interface::StringColor;
+use crate::omni_error;
+
+#[derive(Debug, Clone)]
+struct ExecCommandArgs {
+    dir: Option<String>,
+    command: Vec<String>,
+}
+
+impl ExecCommandArgs {
+    fn parse(argv: Vec<String>) -> Self {
+        let mut parse_argv = vec!["".to_string()];
+        parse_argv.extend(argv);
+
+        let matches = clap::Command::new("")
+            .disable_help_subcommand(true)
+            .disable_version_flag(true)
+            .arg(
+                clap::Arg::new("dir")
+                    .long("dir")
+                    .short('C')
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                clap::Arg::new("command")
+                    .action(clap::ArgAction::Append)
+                    .required(true)
+                    .last(true),
+            )
+            .try_get_matches_from(&parse_argv);
+
+        if let Err(err) = matches {
+            match err.kind() {
+                clap::error::ErrorKind::DisplayHelp
+                | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+                    HelpCommand::new().exec(vec!["exec".to_string()]);
+                }
+                clap::error::ErrorKind::DisplayVersion => {
+                    unreachable!("version flag is disabled");
+                }
+                _ => {
+                    let err_str = format!("{}", err);
+                    let err_str = err_str
+                        .split('\n')
+                        .take_while(|line| !line.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let err_str = err_str.trim_start_matches("error: ");
+                    omni_error!(err_str);
+                }
+            }
+            exit(1);
+        }
+
+        let matches = matches.unwrap();
+
+        let command = matches
+            .get_many::<String>("command")
+            .map(|command| command.map(|arg| arg.to_string()).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        Self {
+            dir: matches.get_one::<String>("dir").cloned(),
+            command,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecCommand {
+    cli_args: OnceCell<ExecCommandArgs>,
+}
+
+impl ExecCommand {
+    pub fn new() -> Self {
+        Self {
+            cli_args: OnceCell::new(),
+        }
+    }
+
+    fn cli_args(&self) -> &ExecCommandArgs {
+        self.cli_args.get_or_init(|| {
+            omni_error!("command arguments not initialized");
+            exit(1);
+        })
+    }
+
+    pub fn name(&self) -> Vec<String> {
+        vec!["exec".to_string()]
+    }
+
+    pub fn aliases(&self) -> Vec<Vec<String>> {
+        vec![]
+    }
+
+    pub fn help(&self) -> Option<String> {
+        Some(
+            concat!(
+                "Run a command inside the dynamic environment of a work directory\n",
+                "\n",
+                "This loads the dynamic environment that \x1B[3momni up\x1B[0m prepared for the ",
+                "directory, and runs the command with it, without requiring the shell ",
+                "integration. This is useful for CI jobs or editor tasks, so that they use the ",
+                "same tool versions as your shell would.",
+            )
+            .to_string(),
+        )
+    }
+
+    pub fn syntax(&self) -> Option<CommandSyntax> {
+        Some(CommandSyntax {
+            usage: Some("[--dir <path>] -- <command> [args...]".to_string()),
+            parameters: vec![
+                SyntaxOptArg {
+                    name: "--dir".to_string(),
+                    desc: Some(
+                        concat!(
+                            "The directory to load the dynamic environment for, and to run the ",
+                            "command from; defaults to the current directory",
+                        )
+                        .to_string(),
+                    ),
+                    required: false,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "command".to_string(),
+                    desc: Some("The command to run, followed by its arguments".to_string()),
+                    required: true,
+                    ..Default::default()
+                },
+            ],
+        })
+    }
+
+    pub fn category(&self) -> Option<Vec<String>> {
+        Some(vec!["General".to_string()])
+    }
+
+    pub fn exec(&self, argv: Vec<String>) {
+        if self.cli_args.set(ExecCommandArgs::parse(argv)).is_err() {
+            unreachable!();
+        }
+
+        let dir = match &self.cli_args().dir {
+            Some(dir) => {
+                let dir = abs_path(dir);
+                if !dir.is_dir() {
+                    omni_error!(format!(
+                        "{} is not a directory",
+                        dir.display().to_string().light_blue()
+                    ));
+                    exit(1);
+                }
+                dir
+            }
+            None => abs_path("."),
+        };
+
+        // The dynamic environment of the current directory is loaded before
+        // any builtin command is run, so this replaces it by the one of the
+        // requested directory, in the environment of this process only
+        update_dynamic_env_for_command(&dir.to_string_lossy());
+
+        // The command being run is not an omni subcommand
+        std::env::remove_var("OMNI_SUBCOMMAND");
+
+        let command = &self.cli_args().command;
+        let err = std::process::Command::new(&command[0])
+            .args(&command[1..])
+            .current_dir(&dir)
+            .exec();
+
+        omni_error!(format!(
+            "failed to run {}: {}",
+            command[0].light_blue(),
+            err
+        ));
+        exit(if err.kind() == std::io::ErrorKind::NotFound {
+            127
+        } else {
+            126
+        });
+    }
+
+    pub fn autocompletion(&self) -> bool {
+        true
+    }
+
+    pub fn autocomplete(&self, comp_cword: usize, argv: Vec<String>) {
+        // Do not complete the arguments of the command to run, nor the
+        // value of the directory
+        if argv.iter().take(comp_cword).any(|arg| arg == "--")
+            || (comp_cword > 0
+                && matches!(
+                    argv.get(comp_cword - 1).map(|arg| arg.as_str()),
+                    Some("--dir") | Some("-C")
+                ))
+        {
+            exit(0);
+        }
+
+        println!("--dir");
+        println!("--");
+        exit(0);
+    }
+}
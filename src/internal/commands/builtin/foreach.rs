@@ -0,0 +1,595 @@
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::exit;
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+
+use globset::Glob;
+use globset::GlobMatcher;
+use once_cell::sync::OnceCell;
+use walkdir::WalkDir;
+
+use crate::internal::commands::builtin::HelpCommand;
+use crate::internal::commands::utils::abs_path;
+use crate::internal::config::config;
+use crate::internal::config::CommandSyntax;
+use crate::internal::config::OrgConfig;
+use crate::internal::config::SyntaxOptArg;
+use crate::internal::env::current_exe;
+use crate::internal::git::Org;
+use crate::internal::git::ORG_LOADER;
+use crate::internal::git_env;
+use crate::internal::user_interface::StringColor;
+use crate::omni_error;
+use crate::omni_info;
+
+#[derive(Debug, Clone)]
+struct ForeachCommandArgs {
+    orgs: Vec<String>,
+    matches: Vec<String>,
+    sets: Vec<String>,
+    jobs: usize,
+    command: Vec<String>,
+}
+
+impl ForeachCommandArgs {
+    fn parse(argv: Vec<String>) -> Self {
+        let mut parse_argv = vec!["".to_string()];
+        parse_argv.extend(argv);
+
+        let matches = clap::Command::new("")
+            .disable_help_subcommand(true)
+            .disable_version_flag(true)
+            .arg(
+                clap::Arg::new("org")
+                    .long("org")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("match")
+                    .long("match")
+                    .short('m')
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("set")
+                    .long("set")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                clap::Arg::new("jobs")
+                    .long("jobs")
+                    .short('j')
+                    .value_parser(clap::value_parser!(usize))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                clap::Arg::new("command")
+                    .action(clap::ArgAction::Append)
+                    .required(true)
+                    .last(true),
+            )
+            .try_get_matches_from(&parse_argv);
+
+        if let Err(err) = matches {
+            match err.kind() {
+                clap::error::ErrorKind::DisplayHelp
+                | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+                    HelpCommand::new().exec(vec!["foreach".to_string()]);
+                }
+                clap::error::ErrorKind::DisplayVersion => {
+                    unreachable!("version flag is disabled");
+                }
+                _ => {
+                    let err_str = format!("{}", err);
+                    let err_str = err_str
+                        .split('\n')
+                        .take_while(|line| !line.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let err_str = err_str.trim_start_matches("error: ");
+                    omni_error!(err_str);
+                }
+            }
+            exit(1);
+        }
+
+        let matches = matches.unwrap();
+
+        let get_many = |name: &str| {
+            matches
+                .get_many::<String>(name)
+                .map(|values| values.map(|value| value.to_string()).collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+
+        let jobs = match matches.get_one::<usize>("jobs") {
+            Some(0) => {
+                omni_error!("the number of jobs must be greater than 0");
+                exit(1);
+            }
+            Some(jobs) => *jobs,
+            None => thread::available_parallelism()
+                .map(|jobs| jobs.get())
+                .unwrap_or(4),
+        };
+
+        Self {
+            orgs: get_many("org"),
+            matches: get_many("match"),
+            sets: get_many("set"),
+            jobs,
+            command: get_many("command"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ForeachRepo {
+    /// The path of the repository relative to the worktree it was found in,
+    /// used to identify the repository in the output; this is the full path
+    /// of the repository if the same relative path exists in another worktree
+    name: String,
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeachCommand {
+    cli_args: OnceCell<ForeachCommandArgs>,
+}
+
+impl ForeachCommand {
+    pub fn new() -> Self {
+        Self {
+            cli_args: OnceCell::new(),
+        }
+    }
+
+    fn cli_args(&self) -> &ForeachCommandArgs {
+        self.cli_args.get_or_init(|| {
+            omni_error!("command arguments not initialized");
+            exit(1);
+        })
+    }
+
+    pub fn name(&self) -> Vec<String> {
+        vec!["foreach".to_string()]
+    }
+
+    pub fn aliases(&self) -> Vec<Vec<String>> {
+        vec![]
+    }
+
+    pub fn help(&self) -> Option<String> {
+        Some(
+            concat!(
+                "Runs a command in each of the repositories of the worktrees, in parallel\n",
+                "\n",
+                "The command is run from the root of each repository, with the dynamic ",
+                "environment of that repository. If the command starts with ",
+                "\x1B[3momni\x1B[0m, it is run as an omni command, as if using ",
+                "\x1B[3momni scope\x1B[0m, otherwise it is run as any other command, as if ",
+                "using \x1B[3momni exec\x1B[0m.\n",
+                "\n",
+                "The output of each command is prefixed by the repository it comes from, ",
+                "and a summary is printed once all the commands are done. The exit code is ",
+                "non-zero if the command failed in any of the repositories.",
+            )
+            .to_string(),
+        )
+    }
+
+    pub fn syntax(&self) -> Option<CommandSyntax> {
+        Some(CommandSyntax {
+            usage: Some(
+                "[--org <org>] [--match <glob>] [--set <name>] [--jobs <n>] -- <command> [args...]"
+                    .to_string(),
+            ),
+            parameters: vec![
+                SyntaxOptArg {
+                    name: "--org".to_string(),
+                    desc: Some(
+                        concat!(
+                            "Only run the command in the repositories whose origin belongs to ",
+                            "that organization handle (e.g. \x1B[3mgithub.com/xaf\x1B[0m); can ",
+                            "be repeated",
+                        )
+                        .to_string(),
+                    ),
+                    required: false,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--match".to_string(),
+                    desc: Some(
+                        concat!(
+                            "Only run the command in the repositories whose path relative to ",
+                            "their worktree matches the glob pattern (e.g. ",
+                            "\x1B[3mgithub.com/xaf/*\x1B[0m); can be repeated",
+                        )
+                        .to_string(),
+                    ),
+                    required: false,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--set".to_string(),
+                    desc: Some(
+                        concat!(
+                            "Only run the command in the repositories matching the patterns of ",
+                            "that repository set, as defined in the \x1B[3mrepo_sets\x1B[0m ",
+                            "configuration; can be repeated, and combined with ",
+                            "\x1B[3m--match\x1B[0m",
+                        )
+                        .to_string(),
+                    ),
+                    required: false,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--jobs".to_string(),
+                    desc: Some(
+                        concat!(
+                            "The number of repositories to run the command in at the same ",
+                            "time; defaults to the number of CPUs",
+                        )
+                        .to_string(),
+                    ),
+                    required: false,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "command".to_string(),
+                    desc: Some(
+                        "The command to run, followed by its arguments, after \x1B[3m--\x1B[0m"
+                            .to_string(),
+                    ),
+                    required: true,
+                    ..Default::default()
+                },
+            ],
+        })
+    }
+
+    pub fn category(&self) -> Option<Vec<String>> {
+        Some(vec!["Git commands".to_string()])
+    }
+
+    pub fn exec(&self, argv: Vec<String>) {
+        if self.cli_args.set(ForeachCommandArgs::parse(argv)).is_err() {
+            unreachable!();
+        }
+
+        let repos = self.list_repositories();
+        if repos.is_empty() {
+            omni_error!("no repository found");
+            exit(1);
+        }
+
+        let command = &self.cli_args().command;
+        let jobs = self.cli_args().jobs.min(repos.len());
+
+        // The commands are run by omni itself, which takes care of loading
+        // the dynamic environment of the repository they are run from
+        let omni_args = match command[0].as_str() {
+            "omni" => command[1..].to_vec(),
+            _ => vec!["exec".to_string(), "--".to_string()]
+                .into_iter()
+                .chain(command.iter().cloned())
+                .collect(),
+        };
+        if omni_args.is_empty() {
+            omni_error!("no omni command specified");
+            exit(1);
+        }
+
+        omni_info!(format!(
+            "running {} in {} {}",
+            command.join(" ").light_yellow(),
+            repos.len().to_string().light_blue(),
+            if repos.len() == 1 {
+                "repository"
+            } else {
+                "repositories"
+            },
+        ));
+
+        let prefix_width = repos.iter().map(|repo| repo.name.len()).max().unwrap_or(0);
+
+        let queue = Arc::new(Mutex::new(repos.into_iter().collect::<VecDeque<_>>()));
+        let (sender, receiver) = mpsc::channel();
+
+        let mut threads = Vec::new();
+        for _ in 0..jobs {
+            let queue = queue.clone();
+            let sender = sender.clone();
+            let omni_args = omni_args.clone();
+            threads.push(thread::spawn(move || loop {
+                let repo = match queue.lock().unwrap().pop_front() {
+                    Some(repo) => repo,
+                    None => break,
+                };
+
+                let result = run_in_repo(&repo, &omni_args, prefix_width);
+                sender.send((repo, result)).unwrap();
+            }));
+        }
+        drop(sender);
+
+        for thread in threads {
+            let _ = thread.join();
+        }
+
+        // Each repository is only run once, but its name is not enough to
+        // identify it, so results are kept in a list ordered like the output
+        let mut results = receiver.iter().collect::<Vec<_>>();
+        results.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+        let failed = results
+            .iter()
+            .filter(|(_, result)| result.is_err())
+            .collect::<Vec<_>>();
+
+        for (repo, result) in failed.iter() {
+            if let Err(err) = result {
+                omni_error!(format!("{} {}", repo.name.light_cyan(), err));
+            }
+        }
+
+        omni_info!(format!(
+            "{} succeeded, {} failed",
+            (results.len() - failed.len()).to_string().green(),
+            if failed.is_empty() {
+                failed.len().to_string()
+            } else {
+                failed.len().to_string().red()
+            },
+        ));
+
+        exit(if failed.is_empty() { 0 } else { 1 });
+    }
+
+    pub fn autocompletion(&self) -> bool {
+        true
+    }
+
+    pub fn autocomplete(&self, comp_cword: usize, argv: Vec<String>) {
+        // Do not complete the arguments of the command to run, nor the
+        // values of the options
+        if argv.iter().take(comp_cword).any(|arg| arg == "--")
+            || (comp_cword > 0
+                && matches!(
+                    argv.get(comp_cword - 1).map(|arg| arg.as_str()),
+                    Some("--org")
+                        | Some("--match")
+                        | Some("-m")
+                        | Some("--set")
+                        | Some("--jobs")
+                        | Some("-j")
+                ))
+        {
+            if comp_cword > 0 && argv.get(comp_cword - 1).map(|arg| arg.as_str()) == Some("--set") {
+                let mut sets = config(".").repo_sets.keys().cloned().collect::<Vec<_>>();
+                sets.sort();
+                for set in sets {
+                    println!("{}", set);
+                }
+            }
+            exit(0);
+        }
+
+        println!("--org");
+        println!("--match");
+        println!("--set");
+        println!("--jobs");
+        println!("--");
+        exit(0);
+    }
+
+    fn list_repositories(&self) -> Vec<ForeachRepo> {
+        let mut worktrees = HashSet::new();
+        worktrees.insert(abs_path(config(".").worktree()));
+        for org in ORG_LOADER.orgs.iter() {
+            let path = abs_path(org.worktree());
+            if path.is_dir() {
+                worktrees.insert(path);
+            }
+        }
+
+        // Only keep the worktrees that are not inside another one
+        let mut worktrees = worktrees.into_iter().collect::<Vec<_>>();
+        worktrees.sort();
+        let worktrees = worktrees
+            .iter()
+            .filter(|path| {
+                !worktrees
+                    .iter()
+                    .any(|other| path != &other && path.starts_with(other))
+            })
+            .collect::<Vec<_>>();
+
+        let orgs = self
+            .cli_args()
+            .orgs
+            .iter()
+            .map(|handle| {
+                if let Some(org) = ORG_LOADER
+                    .orgs
+                    .iter()
+                    .find(|org| org.config.handle == *handle)
+                {
+                    return org.clone();
+                }
+
+                match Org::new(OrgConfig::from_str(handle)) {
+                    Ok(org) => org,
+                    Err(_) => {
+                        omni_error!(format!("invalid org handle: {}", handle.light_blue()));
+                        exit(1);
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut patterns = self.cli_args().matches.clone();
+        if !self.cli_args().sets.is_empty() {
+            let repo_sets = &config(".").repo_sets;
+            for set in self.cli_args().sets.iter() {
+                match repo_sets.get(set) {
+                    Some(set_patterns) => patterns.extend(set_patterns.iter().cloned()),
+                    None => {
+                        omni_error!(format!("unknown repository set: {}", set.light_blue()));
+                        exit(1);
+                    }
+                }
+            }
+        }
+
+        let globs = patterns
+            .iter()
+            .map(|pattern| match Glob::new(pattern) {
+                Ok(glob) => glob.compile_matcher(),
+                Err(err) => {
+                    omni_error!(format!(
+                        "invalid pattern {}: {}",
+                        pattern.light_blue(),
+                        err.kind()
+                    ));
+                    exit(1);
+                }
+            })
+            .collect::<Vec<GlobMatcher>>();
+
+        let mut seen = HashSet::new();
+        let mut repos = Vec::new();
+        for worktree in worktrees {
+            let mut walker = WalkDir::new(worktree)
+                .min_depth(1)
+                .follow_links(true)
+                .into_iter();
+
+            while let Some(entry) = walker.next() {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(_) => continue,
+                };
+
+                if !entry.file_type().is_dir() || !entry.path().join(".git").exists() {
+                    continue;
+                }
+
+                // Repositories nested in other repositories are not considered,
+                // so there is no need to go further down
+                walker.skip_current_dir();
+
+                let path = entry.path().to_path_buf();
+                let name = path
+                    .strip_prefix(worktree)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .to_string();
+
+                if !globs.is_empty() && !globs.iter().any(|glob| glob.is_match(&name)) {
+                    continue;
+                }
+
+                if !orgs.is_empty() {
+                    let git = git_env(path.to_string_lossy());
+                    let hosted = git
+                        .origin()
+                        .is_some_and(|origin| orgs.iter().any(|org| org.hosts_repo(origin)));
+                    if !hosted {
+                        continue;
+                    }
+                }
+
+                if seen.insert(path.clone()) {
+                    repos.push(ForeachRepo { name, path });
+                }
+            }
+        }
+
+        // The same relative path can exist in different worktrees, in which
+        // case the full path is used to tell the repositories apart
+        let mut names = HashSet::new();
+        let duplicates = repos
+            .iter()
+            .filter(|repo| !names.insert(repo.name.clone()))
+            .map(|repo| repo.name.clone())
+            .collect::<HashSet<_>>();
+        for repo in repos.iter_mut() {
+            if duplicates.contains(&repo.name) {
+                repo.name = repo.path.to_string_lossy().to_string();
+            }
+        }
+
+        repos.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+        repos
+    }
+}
+
+/// Runs omni with the given arguments from the repository, printing each line
+/// of its output prefixed by the name of the repository
+fn run_in_repo(
+    repo: &ForeachRepo,
+    omni_args: &[String],
+    prefix_width: usize,
+) -> Result<(), String> {
+    let mut child = match std::process::Command::new(current_exe())
+        .args(omni_args)
+        .current_dir(&repo.path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => return Err(format!("failed to run: {}", err)),
+    };
+
+    let prefix = format!("{:width$} |", repo.name, width = prefix_width).light_cyan();
+
+    let stderr = child.stderr.take().unwrap();
+    let stderr_prefix = prefix.clone();
+    let stderr_thread = thread::spawn(move || {
+        print_prefixed(stderr, &stderr_prefix, true);
+    });
+
+    let stdout = child.stdout.take().unwrap();
+    print_prefixed(stdout, &prefix, false);
+    let _ = stderr_thread.join();
+
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => match status.code() {
+            Some(code) => Err(format!("exited with code {}", code)),
+            None => Err("was terminated by a signal".to_string()),
+        },
+        Err(err) => Err(format!("failed to wait for the command: {}", err)),
+    }
+}
+
+fn print_prefixed<R: Read>(output: R, prefix: &str, to_stderr: bool) {
+    let mut reader = BufReader::new(output);
+    let mut line = Vec::new();
+    while let Ok(read) = reader.read_until(b'\n', &mut line) {
+        if read == 0 {
+            break;
+        }
+
+        let content = String::from_utf8_lossy(&line);
+        let content = content.trim_end_matches(['\n', '\r']);
+        if to_stderr {
+            eprintln!("{} {}", prefix, content);
+        } else {
+            println!("{} {}", prefix, content);
+        }
+        line.clear();
+    }
+}
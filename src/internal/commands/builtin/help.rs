@@ -9,6 +9,7 @@ use crate::internal::commands::void::VoidCommand;
 use crate::internal::commands::Command;
 use crate::internal::config::CommandSyntax;
 use crate::internal::config::SyntaxOptArg;
+use crate::internal::config::SyntaxOptArgType;
 use crate::internal::user_interface::term_width;
 use crate::internal::user_interface::wrap_blocks;
 use crate::internal::user_interface::wrap_text;
@@ -131,11 +132,13 @@ impl HelpCommand {
                     name: "unfold".to_string(),
                     desc: Some("Show all subcommands".to_string()),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "command".to_string(),
                     desc: Some("The command to get help for".to_string()),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
@@ -228,10 +231,17 @@ impl HelpCommand {
                 for arg in syntax.parameters.iter() {
                     let missing_just = ljust - arg.name.len();
                     let str_name = format!("  {}{}", arg.name.cyan(), " ".repeat(missing_just));
-                    let help = if let Some(desc) = &arg.desc {
-                        wrap_text(desc, max_width - ljust).join(join_str.as_str())
-                    } else {
+                    let mut desc = arg.desc.clone().unwrap_or_default();
+                    if let Some(SyntaxOptArgType::Enum(values)) = &arg.arg_type {
+                        desc += &format!(" \x1B[90m(values: {})\x1B[0m", values.join(", "));
+                    }
+                    if let Some(default) = &arg.default {
+                        desc += &format!(" \x1B[90m(default: {})\x1B[0m", default);
+                    }
+                    let help = if desc.is_empty() {
                         "".to_string()
+                    } else {
+                        wrap_text(desc.trim(), max_width - ljust).join(join_str.as_str())
                     };
                     eprintln!("\n{}{}", str_name, help);
                 }
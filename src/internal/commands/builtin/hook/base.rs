@@ -31,11 +31,13 @@ impl HookCommand {
                     name: "hook".to_string(),
                     desc: Some("Which hook to call".to_string()),
                     required: true,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "options...".to_string(),
                     desc: Some("Any options to pass to the hook.".to_string()),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
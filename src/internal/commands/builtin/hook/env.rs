@@ -1,11 +1,74 @@
+use std::path::PathBuf;
 use std::process::exit;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
 
+use crate::internal::cache::loaders::set_up_environments_cache;
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::RepositoriesCache;
+use crate::internal::cache::UpEnvironmentsCache;
+use crate::internal::config::config;
+use crate::internal::config::global_config;
 use crate::internal::config::CommandSyntax;
+use crate::internal::config::ConfigLoader;
+use crate::internal::config::SyntaxOptArg;
+use crate::internal::dynenv::current_dynamic_env_id;
 use crate::internal::dynenv::update_dynamic_env;
+use crate::internal::dynenv::DYNENV_VAR;
+use crate::internal::env::cache_home;
+use crate::internal::env::current_exe;
 use crate::internal::env::Shell;
 use crate::internal::git::report_update_error;
+use crate::internal::workdir;
+use crate::internal::workdir::is_trusted;
+use crate::internal::workdir::up_fingerprint;
 use crate::internal::StringColor;
 
+const HOOK_ENV_SNAPSHOT_VERSION: &str = "1";
+const HOOK_ENV_SNAPSHOT_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const HOOK_ENV_BENCHMARK_ITERATIONS: usize = 20;
+
+#[derive(Debug, Clone)]
+struct HookEnvCommandArgs {
+    shell: Shell,
+    snapshot: bool,
+    benchmark: Option<usize>,
+}
+
+impl HookEnvCommandArgs {
+    /// The arguments are parsed by hand, as this is called on every prompt
+    /// and needs to stay as fast as possible
+    fn parse(argv: Vec<String>) -> Self {
+        let mut shell = None;
+        let mut snapshot = true;
+        let mut benchmark = None;
+
+        let mut args = argv.iter().peekable();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--no-snapshot" => snapshot = false,
+                "--benchmark" => {
+                    let iterations = args
+                        .next_if(|arg| arg.parse::<usize>().is_ok())
+                        .and_then(|arg| arg.parse::<usize>().ok())
+                        .unwrap_or(HOOK_ENV_BENCHMARK_ITERATIONS);
+                    benchmark = Some(iterations.max(1));
+                }
+                _ if shell.is_none() => shell = Some(Shell::from_str(arg)),
+                _ => {}
+            }
+        }
+
+        Self {
+            shell: shell.unwrap_or_else(Shell::from_env),
+            snapshot,
+            benchmark,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HookEnvCommand {}
 
@@ -28,7 +91,15 @@ impl HookEnvCommand {
                 "Hook used to update the dynamic environment\n",
                 "\n",
                 "The \x1B[1m\x1B[4menv\x1B[0m hook is called during your shell prompt to set the ",
-                "dynamic environment required for \x1B[3momni up\x1B[0m-ed repositories.",
+                "dynamic environment required for \x1B[3momni up\x1B[0m-ed repositories.\n",
+                "\n",
+                "It also lets you know, once, when the configuration of the repository or ",
+                "one of its lockfiles changed since \x1B[3momni up\x1B[0m was last run, or runs ",
+                "\x1B[3momni up\x1B[0m automatically if configured to do so.\n",
+                "\n",
+                "A snapshot of the state the dynamic environment depends on is kept for each ",
+                "shell, so that the hook returns right away when nothing changed since the ",
+                "previous prompt.",
             )
             .to_string(),
         )
@@ -37,7 +108,34 @@ impl HookEnvCommand {
     pub fn syntax(&self) -> Option<CommandSyntax> {
         Some(CommandSyntax {
             usage: None,
-            parameters: vec![],
+            parameters: vec![
+                SyntaxOptArg {
+                    name: "shell".to_string(),
+                    desc: Some(
+                        "The shell to export the dynamic environment for; defaults to the current shell"
+                            .to_string(),
+                    ),
+                    required: false,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--no-snapshot".to_string(),
+                    desc: Some(
+                        "Always compute the dynamic environment, even if nothing changed".to_string(),
+                    ),
+                    required: false,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--benchmark [iterations]".to_string(),
+                    desc: Some(format!(
+                        "Report the latency of the hook, with and without snapshot \x1B[90m(default: {} iterations)\x1B[0m",
+                        HOOK_ENV_BENCHMARK_ITERATIONS,
+                    )),
+                    required: false,
+                    ..Default::default()
+                },
+            ],
         })
     }
 
@@ -46,16 +144,44 @@ impl HookEnvCommand {
     }
 
     pub fn exec(&self, argv: Vec<String>) {
-        let shell_type = if argv.len() > 2 {
-            Shell::from_str(&argv[2])
-        } else {
-            Shell::from_env()
+        let args = HookEnvCommandArgs::parse(argv);
+        let shell_type = args.shell.clone();
+
+        if let Some(iterations) = args.benchmark {
+            self.benchmark(iterations);
+            exit(0);
+        }
+
+        // The snapshot is kept per shell, identified by the PPID that the
+        // shell integration provides
+        let snapshot = match std::env::var("OMNI_SHELL_PPID") {
+            Ok(shell_ppid) if args.snapshot && !shell_ppid.is_empty() => {
+                Some(HookEnvSnapshot::new(&shell_ppid, &shell_type))
+            }
+            _ => None,
         };
 
+        if let Some(snapshot) = &snapshot {
+            if snapshot.is_current() {
+                exit(0);
+            }
+        }
+
         match shell_type.dynenv_export_mode() {
             Some(export_mode) => {
+                // Check if up is outdated first, as running it automatically
+                // would change the dynamic environment to load
+                self.check_up_outdated();
                 update_dynamic_env(export_mode);
                 report_update_error();
+
+                // The dynamic environment has been updated for the current
+                // process too, so this records the state of the shell once
+                // it evaluated the output of the hook
+                if let Some(snapshot) = &snapshot {
+                    snapshot.write();
+                }
+
                 exit(0);
             }
             None => {
@@ -70,6 +196,172 @@ impl HookEnvCommand {
         }
     }
 
+    fn check_up_outdated(&self) {
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) if wd.in_workdir() => wd_id,
+            _ => return,
+        };
+
+        // Only consider the repositories that have already been set up
+        let repos = RepositoriesCache::get();
+        if repos.check_fingerprint(&wd_id, "up", 0) && !UpEnvironmentsCache::get().contains(&wd_id)
+        {
+            return;
+        }
+
+        // Record the current fingerprint, which the prompt hook reads to
+        // avoid computing it again
+        let fingerprint = up_fingerprint(".");
+        if !repos.check_fingerprint(&wd_id, "up_current", fingerprint) {
+            if let Err(err) = RepositoriesCache::exclusive(|repos| {
+                repos.update_fingerprint(&wd_id, "up_current", fingerprint)
+            }) {
+                eprintln!(
+                    "{} {} {}",
+                    "omni:".light_cyan(),
+                    "failed to update cache:".red(),
+                    err,
+                );
+            }
+        }
+
+        let (notify_outdated, auto_up) = config(".").up_command.outdated_config(&wd_id);
+        if !notify_outdated && !auto_up {
+            return;
+        }
+
+        if repos.check_fingerprint(&wd_id, "up", fingerprint)
+            || repos.check_fingerprint(&wd_id, "up_outdated", fingerprint)
+        {
+            return;
+        }
+
+        // Keep track of the change, so we only act on it once
+        if let Err(err) = RepositoriesCache::exclusive(|repos| {
+            repos.update_fingerprint(&wd_id, "up_outdated", fingerprint)
+        }) {
+            eprintln!(
+                "{} {} {}",
+                "omni:".light_cyan(),
+                "failed to update cache:".red(),
+                err,
+            );
+            return;
+        }
+
+        if auto_up && is_trusted(".") {
+            eprintln!(
+                "{} {} {}",
+                "omni:".light_cyan(),
+                "configuration changed, running".light_black(),
+                "omni up".light_yellow(),
+            );
+
+            // This runs synchronously, before the prompt is shown, so that
+            // the dynamic environment loaded right after reflects the changes;
+            // omni up cannot read from the terminal here, so steps requiring
+            // input fail and the user is notified as if auto_up was disabled.
+            // The output of this hook is evaluated by the shell, so the
+            // output of omni up needs to go to stderr
+            let status = std::process::Command::new(current_exe())
+                .arg("up")
+                .env("OMNI_SKIP_UPDATE", "1")
+                .stdin(Stdio::null())
+                .stdout(std::io::stderr())
+                .status();
+            if status.map(|status| status.success()).unwrap_or(false) {
+                // Reload the up environments that omni up just updated
+                if let Ok(cache) = UpEnvironmentsCache::shared() {
+                    set_up_environments_cache(cache);
+                }
+                return;
+            }
+        }
+
+        if notify_outdated {
+            eprintln!(
+                "{} {} {} {}",
+                "omni:".light_cyan(),
+                "configuration changed since the last".light_black(),
+                "omni up".light_yellow(),
+                "for this repository".light_black(),
+            );
+        }
+    }
+
+    /// Runs the hook as the shell integration would, with and without the
+    /// snapshot, and reports the latency of each
+    fn benchmark(&self, iterations: usize) {
+        let bench_ppid = format!("benchmark-{}", std::process::id());
+        let mut dynenv = std::env::var(DYNENV_VAR).ok();
+
+        let mut run = |snapshot: bool| -> Option<Duration> {
+            let mut command = std::process::Command::new(current_exe());
+            command
+                .args(["hook", "env", "posix"])
+                .env("OMNI_SHELL_PPID", &bench_ppid)
+                .stdin(Stdio::null())
+                .stderr(Stdio::null());
+            if !snapshot {
+                command.arg("--no-snapshot");
+            }
+            match &dynenv {
+                Some(value) => command.env(DYNENV_VAR, value),
+                None => command.env_remove(DYNENV_VAR),
+            };
+
+            let start = Instant::now();
+            let output = command.output().ok()?;
+            let elapsed = start.elapsed();
+
+            // Keep track of the dynamic environment as the shell would, so
+            // the next run starts from the state this one left
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            for line in stdout.lines() {
+                if let Some(value) = line.strip_prefix(&format!("export {}=", DYNENV_VAR)) {
+                    dynenv = Some(posix_unquote(value));
+                } else if line == format!("unset {}", DYNENV_VAR) {
+                    dynenv = None;
+                }
+            }
+
+            Some(elapsed)
+        };
+
+        // The first run loads the dynamic environment and writes the
+        // snapshot, so it is not part of the measures
+        if run(true).is_none() {
+            eprintln!(
+                "{} {}",
+                "omni:".light_cyan(),
+                "failed to run the hook".red(),
+            );
+            exit(1);
+        }
+
+        let with_snapshot = (0..iterations)
+            .filter_map(|_| run(true))
+            .collect::<Vec<_>>();
+        let without_snapshot = (0..iterations)
+            .filter_map(|_| run(false))
+            .collect::<Vec<_>>();
+
+        let _ = std::fs::remove_file(HookEnvSnapshot::path(&bench_ppid));
+
+        println!(
+            "{} {}",
+            "omni hook env".bold(),
+            format!("latency over {} runs", iterations).light_black(),
+        );
+        for (name, durations) in [
+            ("with snapshot", with_snapshot),
+            ("without snapshot", without_snapshot),
+        ] {
+            println!("  {:<18}{}", name, format_durations(durations));
+        }
+    }
+
     pub fn autocompletion(&self) -> bool {
         false
     }
@@ -78,3 +370,191 @@ impl HookEnvCommand {
         exit(0);
     }
 }
+
+/// The state that the output of the hook depends on, recorded the last
+/// time the hook computed the dynamic environment for a shell; as long as
+/// it stays the same, the hook has nothing to do.
+struct HookEnvSnapshot {
+    path: PathBuf,
+    header: Option<Vec<String>>,
+}
+
+impl HookEnvSnapshot {
+    fn new(shell_ppid: &str, shell_type: &Shell) -> Self {
+        Self {
+            path: Self::path(shell_ppid),
+            header: Self::header(shell_type),
+        }
+    }
+
+    fn path(shell_ppid: &str) -> PathBuf {
+        PathBuf::from(cache_home())
+            .join("hook_env")
+            .join(shell_ppid.replace('/', "_"))
+    }
+
+    fn header(shell_type: &Shell) -> Option<Vec<String>> {
+        let cwd = std::env::current_dir().ok()?.to_string_lossy().to_string();
+        let omni_config = std::env::var("OMNI_CONFIG").unwrap_or_default();
+        if cwd.contains('\n') || omni_config.contains('\n') {
+            return None;
+        }
+
+        Some(vec![
+            HOOK_ENV_SNAPSHOT_VERSION.to_string(),
+            shell_type.to_str().to_string(),
+            cwd,
+            format!("{:016x}", current_dynamic_env_id()),
+            omni_config,
+        ])
+    }
+
+    /// Checks that the snapshot exists and matches the current state; this
+    /// only reads the snapshot file and the modification times of the files
+    /// it lists, so that it can be done on every prompt.
+    fn is_current(&self) -> bool {
+        let header = match &self.header {
+            Some(header) => header,
+            None => return false,
+        };
+
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(_) => return false,
+        };
+
+        let mut lines = content.lines();
+        for expected in header.iter() {
+            if lines.next() != Some(expected.as_str()) {
+                return false;
+            }
+        }
+
+        lines.all(|line| match line.split_once('\t') {
+            Some((mtime, path)) => file_mtime(path) == mtime,
+            None => false,
+        })
+    }
+
+    fn write(&self) {
+        // The header is computed again, as the dynamic environment id
+        // changed if the hook updated it
+        let header = match Self::header(&Shell::from_str(
+            self.header
+                .as_ref()
+                .and_then(|header| header.get(1))
+                .map(|shell| shell.as_str())
+                .unwrap_or(""),
+        )) {
+            Some(header) => header,
+            None => return,
+        };
+
+        let mut content = header.join("\n");
+        for file in Self::watched_files() {
+            let file = file.to_string_lossy().to_string();
+            if file.contains('\n') {
+                continue;
+            }
+            content.push_str(&format!("\n{}\t{}", file_mtime(&file), file));
+        }
+        content.push('\n');
+
+        if let Some(parent) = self.path.parent() {
+            if std::fs::create_dir_all(parent).is_err() {
+                return;
+            }
+            Self::cleanup(parent);
+        }
+
+        let _ = std::fs::write(&self.path, content);
+    }
+
+    /// The files whose changes can lead to a different dynamic environment,
+    /// or to something else for the hook to report
+    fn watched_files() -> Vec<PathBuf> {
+        let mut files = ConfigLoader::user_config_files()
+            .into_iter()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+
+        let cache_path = PathBuf::from(global_config().cache.path.clone());
+        for cache_name in ["up_environments", "repositories", "omnipath"] {
+            files.push(cache_path.join(format!("{}.json", cache_name)));
+        }
+
+        let wd = workdir(".");
+        let root = match wd.root() {
+            Some(root) => PathBuf::from(root),
+            None => match std::env::current_dir() {
+                Ok(cwd) => cwd,
+                Err(_) => return files,
+            },
+        };
+        files.push(root.join(".omni.yaml"));
+        files.push(root.join(".omni").join("config.yaml"));
+
+        if wd.in_workdir() {
+            for file in config(".").up_command.watch_files.iter() {
+                files.push(root.join(file));
+            }
+        }
+
+        files
+    }
+
+    /// Removes the snapshots of shells that have not been used for a while
+    fn cleanup(dir: &std::path::Path) {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        for entry in entries.flatten() {
+            let expired = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > HOOK_ENV_SNAPSHOT_MAX_AGE);
+            if expired {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+fn file_mtime(path: &str) -> String {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos().to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn posix_unquote(value: &str) -> String {
+    match value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        Some(value) => value.replace("'\\''", "'").replace("'\\!'", "!"),
+        None => value.to_string(),
+    }
+}
+
+fn format_durations(durations: Vec<Duration>) -> String {
+    let mut durations = durations;
+    if durations.is_empty() {
+        return "no successful run".light_black();
+    }
+    durations.sort();
+
+    let ms = |duration: &Duration| format!("{:.1}ms", duration.as_secs_f64() * 1000.0);
+    format!(
+        "min {}  median {}  max {}",
+        ms(&durations[0]),
+        ms(&durations[durations.len() / 2]),
+        ms(&durations[durations.len() - 1]),
+    )
+}
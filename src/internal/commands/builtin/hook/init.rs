@@ -131,6 +131,7 @@ impl HookInitCommandArgs {
 struct InitHookAlias {
     alias: String,
     command: String,
+    command_args: Vec<String>,
     command_size: usize,
     full_command: String,
 }
@@ -156,6 +157,7 @@ impl InitHookAlias {
             alias,
             command: shell_words::quote(&command).to_string(),
             command_size: command_vec.len(),
+            command_args: command_vec,
             full_command: shell_words::quote(&full_command).to_string(),
         }
     }
@@ -184,11 +186,15 @@ impl HookInitCommand {
             "\n",
             "The \x1B[1m\x1B[4minit\x1B[0m hook will provide you with the command to run to ",
             "initialize omni in your shell. You can specify which shell you wish to load it ",
-            "for by specifying either one of \x1B[1mzsh\x1B[0m, \x1B[1mbash\x1B[0m, or ",
-            "\x1B[1mfish\x1B[0m as optional parameter. If no argument is specified, the login ",
-            "shell, as provided by the \x1B[3mSHELL\x1B[0m environment variable, will be used. ",
-            "You can load omni in your shell by using \x1B[1meval \"$(omni hook init YOURSHELL)",
-            "\"\x1B[0m for bash or zsh, or \x1B[1momni hook init fish | source\x1B[0m for fish.\n",
+            "for by specifying either one of \x1B[1mzsh\x1B[0m, \x1B[1mbash\x1B[0m, ",
+            "\x1B[1mfish\x1B[0m, \x1B[1mnu\x1B[0m or \x1B[1mpwsh\x1B[0m as optional ",
+            "parameter. If no argument is specified, the login shell, as provided by the ",
+            "\x1B[3mSHELL\x1B[0m environment variable, will be used. You can load omni in your ",
+            "shell by using \x1B[1meval \"$(omni hook init YOURSHELL)\"\x1B[0m for bash or zsh, ",
+            "\x1B[1momni hook init fish | source\x1B[0m for fish, or ",
+            "\x1B[1mInvoke-Expression (& omni hook init pwsh | Out-String)\x1B[0m for pwsh. ",
+            "For nu, the output needs to be saved in a file that is sourced at startup, such as ",
+            "a file in the \x1B[3mvendor/autoload\x1B[0m directory of nushell.\n",
             "\n",
             "The \x1B[1minit\x1B[0m hook supports the \x1B[1m--alias <alias>\x1B[0m ",
             "option, which adds an alias to the omni command with autocompletion support. It ",
@@ -210,6 +216,7 @@ impl HookInitCommand {
                             .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "--command-alias <alias> <subcommand>".to_string(),
@@ -222,14 +229,16 @@ impl HookInitCommand {
                         .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "shell".to_string(),
                     desc: Some(
-                        "Which shell to initialize omni for. Can be one of bash, zsh or fish."
+                        "Which shell to initialize omni for. Can be one of bash, zsh, fish, nu or pwsh."
                             .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
@@ -255,10 +264,18 @@ impl HookInitCommand {
                 args,
                 include_bytes!("../../../../../shell_integration/omni.fish.tmpl"),
             ),
+            "nu" | "nushell" => dump_integration(
+                args,
+                include_bytes!("../../../../../shell_integration/omni.nu.tmpl"),
+            ),
+            "pwsh" | "powershell" => dump_integration(
+                args,
+                include_bytes!("../../../../../shell_integration/omni.pwsh.tmpl"),
+            ),
             _ => {
                 omni_error!(
                     format!(
-                        "invalid shell '{}', omni only supports bash, zsh and fish",
+                        "invalid shell '{}', omni only supports bash, zsh, fish, nu and pwsh",
                         args.shell
                     ),
                     "hook init"
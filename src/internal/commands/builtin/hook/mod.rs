@@ -7,5 +7,8 @@ pub(crate) use env::HookEnvCommand;
 pub(crate) mod init;
 pub(crate) use init::HookInitCommand;
 
+pub(crate) mod prompt;
+pub(crate) use prompt::HookPromptCommand;
+
 pub(crate) mod uuid;
 pub(crate) use uuid::HookUuidCommand;
@@ -0,0 +1,320 @@
+use std::process::exit;
+
+use once_cell::sync::OnceCell;
+
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::RepositoriesCache;
+use crate::internal::cache::UpEnvironmentsCache;
+use crate::internal::commands::builtin::HelpCommand;
+use crate::internal::config::CommandSyntax;
+use crate::internal::config::SyntaxOptArg;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+use crate::internal::workdir::is_trusted;
+use crate::omni_error;
+
+const DEFAULT_PROMPT_FORMAT: &str = "%{handle} %{tools} %{up} %{trust}";
+
+#[derive(Debug, Clone)]
+struct HookPromptCommandArgs {
+    format: String,
+}
+
+impl HookPromptCommandArgs {
+    fn parse(argv: Vec<String>) -> Self {
+        let mut parse_argv = vec!["".to_string()];
+        parse_argv.extend(argv);
+
+        let matches = clap::Command::new("")
+            .disable_help_subcommand(true)
+            .disable_version_flag(true)
+            .arg(
+                clap::Arg::new("format")
+                    .long("format")
+                    .action(clap::ArgAction::Set),
+            )
+            .try_get_matches_from(&parse_argv);
+
+        if let Err(err) = matches {
+            match err.kind() {
+                clap::error::ErrorKind::DisplayHelp
+                | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+                    HelpCommand::new().exec(vec!["hook".to_string(), "prompt".to_string()]);
+                }
+                clap::error::ErrorKind::DisplayVersion => {
+                    unreachable!("version flag is disabled");
+                }
+                _ => {
+                    let err_str = format!("{}", err);
+                    let err_str = err_str
+                        .split('\n')
+                        .take_while(|line| !line.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let err_str = err_str.trim_start_matches("error: ");
+                    omni_error!(err_str);
+                }
+            }
+            exit(1);
+        }
+
+        let matches = matches.unwrap();
+
+        Self {
+            format: matches
+                .get_one::<String>("format")
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_PROMPT_FORMAT.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HookPromptCommand {
+    cli_args: OnceCell<HookPromptCommandArgs>,
+}
+
+impl HookPromptCommand {
+    pub fn new() -> Self {
+        Self {
+            cli_args: OnceCell::new(),
+        }
+    }
+
+    fn cli_args(&self) -> &HookPromptCommandArgs {
+        self.cli_args.get_or_init(|| {
+            omni_error!("command arguments not initialized");
+            exit(1);
+        })
+    }
+
+    pub fn name(&self) -> Vec<String> {
+        vec!["hook".to_string(), "prompt".to_string()]
+    }
+
+    pub fn aliases(&self) -> Vec<Vec<String>> {
+        vec![]
+    }
+
+    pub fn help(&self) -> Option<String> {
+        Some(
+            concat!(
+                "Hook used to show the status of the current directory in a prompt\n",
+                "\n",
+                "The \x1B[1m\x1B[4mprompt\x1B[0m hook prints a compact summary of the work ",
+                "directory, which can be used in a custom prompt or as a starship custom ",
+                "module. It prints nothing outside of a work directory.\n",
+                "\n",
+                "The format can use the following placeholders, which are removed when they ",
+                "do not apply, along with the extra spaces around them:\n",
+                "  \x1B[1m%{handle}\x1B[0m  the identifier of the work directory\n",
+                "  \x1B[1m%{tools}\x1B[0m   the tool versions active for the current directory\n",
+                "  \x1B[1m%{up}\x1B[0m      \x1B[3moutdated\x1B[0m if the configuration changed since ",
+                "the last \x1B[3momni up\x1B[0m, as last checked by the shell integration\n",
+                "  \x1B[1m%{trust}\x1B[0m   \x1B[3muntrusted\x1B[0m if the work directory is not trusted",
+            )
+            .to_string(),
+        )
+    }
+
+    pub fn syntax(&self) -> Option<CommandSyntax> {
+        Some(CommandSyntax {
+            usage: None,
+            parameters: vec![SyntaxOptArg {
+                name: "--format".to_string(),
+                desc: Some(format!(
+                    "The format of the summary \x1B[90m(default: \"{}\")\x1B[0m",
+                    DEFAULT_PROMPT_FORMAT,
+                )),
+                required: false,
+                ..Default::default()
+            }],
+        })
+    }
+
+    pub fn category(&self) -> Option<Vec<String>> {
+        Some(vec!["General".to_string()])
+    }
+
+    pub fn exec(&self, argv: Vec<String>) {
+        if self
+            .cli_args
+            .set(HookPromptCommandArgs::parse(argv))
+            .is_err()
+        {
+            unreachable!();
+        }
+
+        let wd = workdir(".");
+        let wd_id = match wd.id() {
+            Some(wd_id) if wd.in_workdir() => wd_id,
+            _ => exit(0),
+        };
+
+        let tools = match UpEnvironmentsCache::get().get_env(&wd_id) {
+            Some(up_env) => {
+                let dir = wd.reldir(".").unwrap_or("".to_string());
+                up_env
+                    .versions_for_dir(&dir)
+                    .iter()
+                    .map(|version| format!("{}:{}", version.tool, version.version))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+            None => "".to_string(),
+        };
+
+        let format = &self.cli_args().format;
+
+        // Only load what the format requires, as this is run on every prompt;
+        // the current fingerprint is the one recorded by the env hook, so the
+        // configuration and watched files are not read again here
+        let up = if format.contains("%{up}") {
+            let repos = RepositoriesCache::get();
+            let current = repos.get_fingerprint(&wd_id, "up_current");
+            if current == 0 || repos.check_fingerprint(&wd_id, "up", current) {
+                ""
+            } else {
+                "outdated"
+            }
+        } else {
+            ""
+        };
+
+        let trust = if format.contains("%{trust}") && !is_trusted(".") {
+            "untrusted"
+        } else {
+            ""
+        };
+
+        let prompt = render_prompt(
+            format,
+            &[
+                ("handle", &wd_id),
+                ("tools", &tools),
+                ("up", up),
+                ("trust", trust),
+            ],
+        );
+        if !prompt.trim().is_empty() {
+            println!("{}", prompt);
+        }
+        exit(0);
+    }
+
+    pub fn autocompletion(&self) -> bool {
+        true
+    }
+
+    pub fn autocomplete(&self, _comp_cword: usize, _argv: Vec<String>) {
+        println!("--format");
+        exit(0);
+    }
+}
+
+/// Replaces the placeholders of the format with their values; placeholders
+/// that are empty are removed along with the whitespace that separated them
+/// from the rest of the prompt, while any other whitespace is kept as is.
+fn render_prompt(format: &str, values: &[(&str, &str)]) -> String {
+    let mut prompt = String::new();
+    let mut rest = format;
+    let mut after_empty = false;
+
+    let push_literal = |prompt: &mut String, literal: &str, after_empty: bool| {
+        if after_empty && (prompt.is_empty() || prompt.ends_with(char::is_whitespace)) {
+            prompt.push_str(literal.trim_start());
+        } else {
+            prompt.push_str(literal);
+        }
+    };
+
+    while let Some(start) = rest.find("%{") {
+        let value = rest[start + 2..].find('}').and_then(|end| {
+            let name = &rest[start + 2..start + 2 + end];
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value, start + 2 + end + 1))
+        });
+
+        match value {
+            Some((value, end)) => {
+                push_literal(&mut prompt, &rest[..start], after_empty);
+                prompt.push_str(value);
+                after_empty = value.is_empty();
+                rest = &rest[end..];
+            }
+            None => {
+                // Unknown placeholders are kept as they are
+                push_literal(&mut prompt, &rest[..start + 2], after_empty);
+                after_empty = false;
+                rest = &rest[start + 2..];
+            }
+        }
+    }
+
+    if rest.is_empty() {
+        if after_empty {
+            prompt.truncate(prompt.trim_end().len());
+        }
+    } else {
+        push_literal(&mut prompt, rest, after_empty);
+    }
+
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(format: &str, tools: &str, up: &str) -> String {
+        render_prompt(
+            format,
+            &[
+                ("handle", "github.com/org/repo"),
+                ("tools", tools),
+                ("up", up),
+                ("trust", ""),
+            ],
+        )
+    }
+
+    #[test]
+    fn render_prompt_all_values() {
+        assert_eq!(
+            render(DEFAULT_PROMPT_FORMAT, "node:20", "outdated"),
+            "github.com/org/repo node:20 outdated"
+        );
+    }
+
+    #[test]
+    fn render_prompt_removes_gaps_of_empty_placeholders() {
+        assert_eq!(render(DEFAULT_PROMPT_FORMAT, "", ""), "github.com/org/repo");
+        assert_eq!(render("%{up} %{handle}", "", ""), "github.com/org/repo");
+        assert_eq!(
+            render("%{handle} %{tools} %{up}", "", "outdated"),
+            "github.com/org/repo outdated"
+        );
+    }
+
+    #[test]
+    fn render_prompt_keeps_user_whitespace() {
+        assert_eq!(
+            render("  %{handle}  |  %{tools}\t", "node:20", ""),
+            "  github.com/org/repo  |  node:20\t"
+        );
+        assert_eq!(
+            render("%{handle}  %{up}  [%{tools}] ", "", ""),
+            "github.com/org/repo  [] "
+        );
+    }
+
+    #[test]
+    fn render_prompt_keeps_unknown_placeholders() {
+        assert_eq!(
+            render("%{handle} %{unknown} %{tools", "", ""),
+            "github.com/org/repo %{unknown} %{tools"
+        );
+    }
+}
@@ -4,6 +4,16 @@ pub(crate) use cd::CdCommand;
 pub(crate) mod clone;
 pub(crate) use clone::CloneCommand;
 
+pub(crate) mod env;
+pub(crate) use env::EnvCommand;
+pub(crate) use env::EnvExportCommand;
+
+pub(crate) mod exec;
+pub(crate) use exec::ExecCommand;
+
+pub(crate) mod foreach;
+pub(crate) use foreach::ForeachCommand;
+
 pub(crate) mod help;
 pub(crate) use help::HelpCommand;
 
@@ -11,6 +21,7 @@ pub(crate) mod hook;
 pub(crate) use hook::HookCommand;
 pub(crate) use hook::HookEnvCommand;
 pub(crate) use hook::HookInitCommand;
+pub(crate) use hook::HookPromptCommand;
 pub(crate) use hook::HookUuidCommand;
 
 pub(crate) mod config;
@@ -30,3 +41,4 @@ pub(crate) use tidy::TidyGitRepo;
 
 pub(crate) mod up;
 pub(crate) use up::UpCommand;
+pub(crate) use up::UpExportCommand;
@@ -162,6 +162,7 @@ impl ScopeCommand {
                         .to_string(),
                     ),
                     required: true,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "command".to_string(),
@@ -170,11 +171,13 @@ impl ScopeCommand {
                             .to_string(),
                     ),
                     required: true,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "options...".to_string(),
                     desc: Some("Any options to pass to the omni command.".to_string()),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
@@ -177,6 +177,7 @@ impl TidyCommand {
                         "Do not ask for confirmation before organizing repositories".to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "--search-path".to_string(),
@@ -188,6 +189,7 @@ impl TidyCommand {
                         .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "--up-all".to_string(),
@@ -202,6 +204,7 @@ impl TidyCommand {
                         .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
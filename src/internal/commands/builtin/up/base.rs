@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 use std::hash::Hash;
 use std::hash::Hasher;
+use std::path::Path;
 use std::path::PathBuf;
 use std::process::exit;
 use std::str::FromStr;
@@ -23,6 +24,8 @@ use crate::internal::config::config;
 use crate::internal::config::config_loader;
 use crate::internal::config::flush_config;
 use crate::internal::config::global_config;
+use crate::internal::config::up::import::import_tool_versions;
+use crate::internal::config::up::import::patch_up_config;
 use crate::internal::config::up::run_progress;
 use crate::internal::config::up::utils::PrintProgressHandler;
 use crate::internal::config::up::utils::RunConfig;
@@ -46,6 +49,7 @@ use crate::internal::user_interface::StringColor;
 use crate::internal::workdir;
 use crate::internal::workdir::add_trust;
 use crate::internal::workdir::is_trusted_or_ask;
+use crate::internal::workdir::up_fingerprint;
 use crate::internal::workdir_or_init;
 use crate::omni_error;
 use crate::omni_info;
@@ -55,6 +59,7 @@ use crate::omni_warning;
 struct UpCommandArgs {
     cache_enabled: bool,
     clone_suggested: UpCommandArgsCloneSuggestedOptions,
+    import: UpCommandArgsImportOptions,
     trust: UpCommandArgsTrustOptions,
     update_repository: bool,
     update_user_config: UpCommandArgsUpdateUserConfigOptions,
@@ -88,6 +93,14 @@ impl UpCommandArgs {
                         "yes", "ask", "no",
                     ])),
             )
+            .arg(
+                clap::Arg::new("import")
+                    .long("import")
+                    .num_args(0..=1)
+                    .action(clap::ArgAction::Set)
+                    .default_missing_value("ask")
+                    .value_parser(clap::builder::PossibleValuesParser::new(["yes", "ask"])),
+            )
             .arg(
                 clap::Arg::new("trust")
                     .long("trust")
@@ -154,6 +167,15 @@ impl UpCommandArgs {
                 UpCommandArgsCloneSuggestedOptions::No
             };
 
+        let import = if let Some(import) = matches.get_one::<String>("import") {
+            import
+                .to_lowercase()
+                .parse::<UpCommandArgsImportOptions>()
+                .unwrap()
+        } else {
+            UpCommandArgsImportOptions::No
+        };
+
         let trust = if let Some(trust) = matches.get_one::<String>("trust") {
             trust
                 .to_lowercase()
@@ -178,6 +200,7 @@ impl UpCommandArgs {
         Self {
             cache_enabled: !*matches.get_one::<bool>("no-cache").unwrap_or(&false),
             clone_suggested,
+            import,
             trust,
             update_repository: *matches
                 .get_one::<bool>("update-repository")
@@ -207,6 +230,26 @@ impl FromStr for UpCommandArgsCloneSuggestedOptions {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+enum UpCommandArgsImportOptions {
+    Yes,
+    Ask,
+    No,
+}
+
+impl FromStr for UpCommandArgsImportOptions {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yes" => Ok(Self::Yes),
+            "ask" => Ok(Self::Ask),
+            "no" => Ok(Self::No),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum UpCommandArgsTrustOptions {
     Always,
@@ -304,6 +347,7 @@ impl UpCommand {
                         .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "--bootstrap".to_string(),
@@ -316,6 +360,7 @@ impl UpCommand {
                         .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "--clone-suggested".to_string(),
@@ -328,6 +373,21 @@ impl UpCommand {
                         .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--import".to_string(),
+                    desc: Some(
+                        concat!(
+                            "Whether we should import the tool versions from the ",
+                            "\x1B[3m.tool-versions\x1B[0m and \x1B[3mmise.toml\x1B[0m files of the ",
+                            "repository into its \x1B[3mup\x1B[0m configuration before running the ",
+                            "command (yes/ask) \x1B[90m(default: no)\x1B[0m",
+                        )
+                        .to_string(),
+                    ),
+                    required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "--trust".to_string(),
@@ -336,6 +396,7 @@ impl UpCommand {
                             .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "--update-repository".to_string(),
@@ -348,6 +409,7 @@ impl UpCommand {
                         .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "--update-user-config".to_string(),
@@ -362,6 +424,7 @@ impl UpCommand {
                         .to_string(),
                     ),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
@@ -397,6 +460,11 @@ impl UpCommand {
             exit(0);
         }
 
+        if self.is_up() && self.cli_args().import != UpCommandArgsImportOptions::No {
+            self.import_config();
+            flush_config(".");
+        }
+
         let config = config(".");
         let up_config = config.up.clone();
         if let Some(up_config) = up_config.clone() {
@@ -460,8 +528,13 @@ impl UpCommand {
         }
 
         let mut env_vars = None;
-        if self.is_up() && !config.env.is_empty() {
-            env_vars = Some(config.env.clone());
+        if self.is_up() && (!config.env.is_empty() || !config.env_secrets.is_empty()) {
+            env_vars = Some((config.env.clone(), config.env_secrets.clone()));
+        }
+
+        let mut shell_aliases = None;
+        if self.is_up() && !config.repo_shell_aliases.is_empty() {
+            shell_aliases = Some(config.repo_shell_aliases.clone());
         }
 
         if self.is_down() && (!wd.in_workdir() || !wd.has_id()) {
@@ -475,12 +548,14 @@ impl UpCommand {
             && suggest_config.is_none()
             && (!has_clone_suggested || !suggest_clone)
             && env_vars.is_none()
+            && shell_aliases.is_none()
         {
             omni_info!(format!(
                 "No {} configuration found, nothing to do.",
                 "up".italic(),
             ));
             UpConfig::clear_cache();
+            self.update_up_fingerprint(0);
             exit(0);
         }
 
@@ -505,11 +580,16 @@ impl UpCommand {
         UpConfig::clear_cache();
 
         // If there are environment variables to set, do it
-        if let Some(env_vars) = env_vars.clone() {
+        if let Some((env_vars, env_secrets)) = env_vars.clone() {
             if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
                 let wd = workdir(".");
                 if let Some(workdir_id) = wd.id() {
-                    up_env.set_env_vars(&workdir_id, env_vars.clone())
+                    up_env.set_env_vars(
+                        &workdir_id,
+                        env_vars.clone(),
+                        env_secrets.clone(),
+                        "config env",
+                    )
                 } else {
                     false
                 }
@@ -520,6 +600,22 @@ impl UpCommand {
             }
         }
 
+        // If there are shell aliases to define, do it
+        if let Some(shell_aliases) = shell_aliases {
+            if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
+                let wd = workdir(".");
+                if let Some(workdir_id) = wd.id() {
+                    up_env.set_shell_aliases(&workdir_id, shell_aliases.clone())
+                } else {
+                    false
+                }
+            }) {
+                omni_warning!(format!("failed to update cache: {}", err));
+            } else {
+                omni_info!(format!("Repository shell aliases configured"));
+            }
+        }
+
         // If it has an up configuration, handle it
         if has_up_config {
             let up_config = up_config.unwrap();
@@ -535,6 +631,8 @@ impl UpCommand {
             }
         }
 
+        self.update_up_fingerprint(if self.is_up() { up_fingerprint(".") } else { 0 });
+
         if let Some(suggested) = suggest_config {
             self.suggest_config(suggested);
         }
@@ -566,6 +664,7 @@ impl UpCommand {
     pub fn autocomplete(&self, _comp_cword: usize, _argv: Vec<String>) {
         println!("--bootstrap");
         println!("--clone-suggested");
+        println!("--import");
         println!("--trust");
         println!("--update-repository");
         println!("--update-user-config");
@@ -576,6 +675,21 @@ impl UpCommand {
         std::env::var("OMNI_SUBCOMMAND").unwrap_or("up".to_string())
     }
 
+    /// Records the fingerprint of the configuration applied by `omni up`,
+    /// so we can tell when the repository needs to be set up again; it is
+    /// also the current fingerprint of the repository, as used by the prompt
+    fn update_up_fingerprint(&self, fingerprint: u64) {
+        if let Some(wd_id) = workdir(".").id() {
+            if let Err(err) = RepositoriesCache::exclusive(|repos| {
+                let updated_up = repos.update_fingerprint(&wd_id, "up", fingerprint);
+                let updated_current = repos.update_fingerprint(&wd_id, "up_current", fingerprint);
+                updated_up || updated_current
+            }) {
+                omni_warning!(format!("failed to update cache: {}", err));
+            }
+        }
+    }
+
     fn is_up(&self) -> bool {
         self.subcommand() == "up"
     }
@@ -738,6 +852,129 @@ impl UpCommand {
         }
     }
 
+    fn import_config(&self) {
+        let wd = workdir(".");
+        let wd_root = match wd.root() {
+            Some(wd_root) => wd_root,
+            None => {
+                omni_error!("can only import tool versions from inside a work directory");
+                exit(1);
+            }
+        };
+
+        let imported = match import_tool_versions(Path::new(wd_root)) {
+            Ok(imported) => imported,
+            Err(err) => {
+                omni_error!(format!("unable to import tool versions: {}", err));
+                exit(1);
+            }
+        };
+
+        for skipped in imported.skipped.iter() {
+            omni_warning!(format!("skipping {}", skipped));
+        }
+
+        if imported.is_empty() {
+            omni_info!("No tool versions to import.");
+            return;
+        }
+
+        let config_file = [".omni.yaml", ".omni/config.yaml"]
+            .iter()
+            .find(|file| Path::new(wd_root).join(file).is_file())
+            .unwrap_or(&".omni.yaml")
+            .to_string();
+        let config_path = format!("{}/{}", wd_root, config_file);
+        let config_existed = Path::new(&config_path).exists();
+
+        let mut any_change_to_apply = false;
+        let mut any_change_applied = false;
+
+        let result = ConfigLoader::edit_user_config_file(config_path.clone(), |config_value| {
+            let before = config_value.clone();
+            let after = patch_up_config(&before, &imported.tools);
+
+            // Get the yaml representation of the before and after config
+            let before_yaml = before.as_yaml();
+            let after_yaml = after.as_yaml();
+
+            // Prepare the unified diff
+            let input = InternedInput::new(before_yaml.as_str(), after_yaml.as_str());
+            let diff = diff(
+                Algorithm::Histogram,
+                &input,
+                UnifiedDiffBuilder::new(&input),
+            );
+
+            if diff.is_empty() {
+                // No diff, nothing to do!
+                return false;
+            }
+            any_change_to_apply = true;
+
+            // If we got there, there is a diff, so color the lines
+            let diff = color_diff(&diff);
+
+            omni_info!(format!(
+                "Importing tool versions from {}",
+                imported.sources.join(", ").light_blue(),
+            ));
+            omni_info!(format!(
+                "The following is going to be changed in {}:",
+                config_file.underline()
+            ));
+            eprintln!("  {}", diff.replace('\n', "\n  "));
+
+            if self.cli_args().import == UpCommandArgsImportOptions::Yes {
+                *config_value = after;
+                any_change_applied = true;
+                return true;
+            }
+
+            if !shell_is_interactive() {
+                return false;
+            }
+
+            let question = requestty::Question::confirm("apply_import")
+                .ask_if_answered(true)
+                .on_esc(requestty::OnEsc::Terminate)
+                .message("Do you want to apply the changes?")
+                .default(true)
+                .build();
+
+            match requestty::prompt_one(question) {
+                Ok(requestty::Answer::Bool(true)) => {
+                    *config_value = after;
+                    any_change_applied = true;
+                    true
+                }
+                Ok(_) => false,
+                Err(err) => {
+                    println!("{}", format!("[✘] {:?}", err).red());
+                    false
+                }
+            }
+        });
+
+        if let Err(err) = result {
+            omni_error!(format!("Unable to update {}: {}", config_file, err));
+            exit(1);
+        }
+
+        // Do not leave an empty configuration file behind us
+        if !config_existed && !any_change_applied {
+            let _ = std::fs::remove_file(&config_path);
+        }
+
+        if !any_change_to_apply {
+            omni_info!(format!("{} is already up to date", config_file));
+        } else if any_change_applied {
+            omni_info!(format!("Updated {}", config_file));
+        } else {
+            omni_info!(format!("Skipped updating {}", config_file));
+        }
+    }
+
     fn suggest_config_split(
         &self,
         before: ConfigValue,
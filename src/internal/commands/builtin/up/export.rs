@@ -0,0 +1,279 @@
+use std::path::PathBuf;
+use std::process::exit;
+
+use itertools::Itertools;
+use once_cell::sync::OnceCell;
+
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::UpEnvironmentsCache;
+use crate::internal::commands::builtin::HelpCommand;
+use crate::internal::commands::utils::abs_path;
+use crate::internal::config::config;
+use crate::internal::config::up::UpConfig;
+use crate::internal::config::up::UpExport;
+use crate::internal::config::up::UpExportFormat;
+use crate::internal::config::CommandSyntax;
+use crate::internal::config::SyntaxOptArg;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+use crate::omni_error;
+use crate::omni_info;
+use crate::omni_warning;
+
+#[derive(Debug, Clone)]
+struct UpExportCommandArgs {
+    format: UpExportFormat,
+    output: Option<String>,
+}
+
+impl UpExportCommandArgs {
+    fn parse(argv: Vec<String>) -> Self {
+        let mut parse_argv = vec!["".to_string()];
+        parse_argv.extend(argv);
+
+        let matches = clap::Command::new("")
+            .disable_help_subcommand(true)
+            .disable_version_flag(true)
+            .arg(
+                clap::Arg::new("format")
+                    .long("format")
+                    .required(true)
+                    .action(clap::ArgAction::Set)
+                    .value_parser(clap::builder::PossibleValuesParser::new([
+                        "dockerfile",
+                        "devcontainer",
+                        "github-actions",
+                    ])),
+            )
+            .arg(
+                clap::Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .action(clap::ArgAction::Set),
+            )
+            .try_get_matches_from(&parse_argv);
+
+        if let Err(err) = matches {
+            match err.kind() {
+                clap::error::ErrorKind::DisplayHelp
+                | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+                    HelpCommand::new().exec(vec!["up".to_string(), "export".to_string()]);
+                }
+                clap::error::ErrorKind::DisplayVersion => {
+                    unreachable!("version flag is disabled");
+                }
+                _ => {
+                    let err_str = format!("{}", err);
+                    let err_str = err_str
+                        .split('\n')
+                        .take_while(|line| !line.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let err_str = err_str.trim_start_matches("error: ");
+                    omni_error!(err_str);
+                }
+            }
+            exit(1);
+        }
+
+        let matches = matches.unwrap();
+
+        // We can consider those unwraps safe, as the format is
+        // required and its values are validated by clap
+        let format = matches
+            .get_one::<String>("format")
+            .unwrap()
+            .parse::<UpExportFormat>()
+            .unwrap();
+
+        Self {
+            format,
+            output: matches.get_one::<String>("output").cloned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UpExportCommand {
+    cli_args: OnceCell<UpExportCommandArgs>,
+}
+
+impl UpExportCommand {
+    pub fn new() -> Self {
+        Self {
+            cli_args: OnceCell::new(),
+        }
+    }
+
+    fn cli_args(&self) -> &UpExportCommandArgs {
+        self.cli_args.get_or_init(|| {
+            omni_error!("command arguments not initialized");
+            exit(1);
+        })
+    }
+
+    pub fn name(&self) -> Vec<String> {
+        vec!["up".to_string(), "export".to_string()]
+    }
+
+    pub fn aliases(&self) -> Vec<Vec<String>> {
+        vec![]
+    }
+
+    pub fn help(&self) -> Option<String> {
+        Some(
+            concat!(
+                "Exports the \x1B[3mup\x1B[0m configuration of the repository to other formats\n",
+                "\n",
+                "The tool versions, homebrew dependencies, environment variables and custom ",
+                "commands of the \x1B[3mup\x1B[0m configuration are written as a Dockerfile, a ",
+                "devcontainer configuration or a GitHub Actions workflow, so that those can stay ",
+                "in sync with \x1B[3momni up\x1B[0m. The versions resolved during the last ",
+                "\x1B[3momni up\x1B[0m are used when available.",
+            )
+            .to_string(),
+        )
+    }
+
+    pub fn syntax(&self) -> Option<CommandSyntax> {
+        Some(CommandSyntax {
+            usage: None,
+            parameters: vec![
+                SyntaxOptArg {
+                    name: "--format".to_string(),
+                    desc: Some(
+                        "The format to export to (dockerfile/devcontainer/github-actions)"
+                            .to_string(),
+                    ),
+                    required: true,
+                    ..Default::default()
+                },
+                SyntaxOptArg {
+                    name: "--output".to_string(),
+                    desc: Some(
+                        concat!(
+                            "The file to write the export to; if not provided, the export will ",
+                            "be written to the standard output",
+                        )
+                        .to_string(),
+                    ),
+                    required: false,
+                    ..Default::default()
+                },
+            ],
+        })
+    }
+
+    pub fn category(&self) -> Option<Vec<String>> {
+        Some(vec!["Git commands".to_string()])
+    }
+
+    pub fn exec(&self, argv: Vec<String>) {
+        if self.cli_args.set(UpExportCommandArgs::parse(argv)).is_err() {
+            unreachable!();
+        }
+
+        let wd = workdir(".");
+        let wd_root = match wd.root() {
+            Some(wd_root) => wd_root,
+            None => {
+                omni_error!("can only export from inside a work directory");
+                exit(1);
+            }
+        };
+
+        let config = config(wd_root);
+        let up_config = match &config.up {
+            Some(up_config) if up_config.has_steps() => up_config.clone(),
+            _ if !config.env.is_empty() => UpConfig {
+                steps: vec![],
+                errors: vec![],
+            },
+            _ => {
+                omni_error!(format!("no {} configuration found", "up".italic()));
+                exit(1);
+            }
+        };
+
+        let resolved_versions = match wd.id() {
+            Some(wd_id) => match UpEnvironmentsCache::get().get_env(&wd_id) {
+                Some(up_env) => up_env.versions.clone(),
+                None => vec![],
+            },
+            None => vec![],
+        };
+
+        let name = wd.id().unwrap_or_else(|| {
+            PathBuf::from(wd_root)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("omni")
+                .to_string()
+        });
+
+        let export = UpExport::new(&up_config, &config.env, &resolved_versions);
+        let content = export.render(self.cli_args().format, &name);
+
+        for skipped in export.skipped(self.cli_args().format) {
+            omni_warning!(format!(
+                "{} is not supported by the {} format, skipping",
+                skipped,
+                self.cli_args().format.name(),
+            ));
+        }
+
+        for key in config.env_secrets.keys().sorted() {
+            omni_warning!(format!(
+                "secret environment variable {} is never exported, skipping",
+                key.light_yellow(),
+            ));
+        }
+
+        match &self.cli_args().output {
+            Some(output) => {
+                let output = abs_path(output);
+                if let Some(parent) = output.parent() {
+                    if let Err(err) = std::fs::create_dir_all(parent) {
+                        omni_error!(format!("failed to create {}: {}", parent.display(), err));
+                        exit(1);
+                    }
+                }
+                if let Err(err) = std::fs::write(&output, content) {
+                    omni_error!(format!("failed to write {}: {}", output.display(), err));
+                    exit(1);
+                }
+                omni_info!(format!(
+                    "exported {} configuration to {}",
+                    "up".italic(),
+                    output.display().to_string().light_blue(),
+                ));
+            }
+            None => print!("{}", content),
+        }
+
+        exit(0);
+    }
+
+    pub fn autocompletion(&self) -> bool {
+        true
+    }
+
+    pub fn autocomplete(&self, comp_cword: usize, argv: Vec<String>) {
+        if comp_cword > 0 {
+            match argv.get(comp_cword - 1).map(|arg| arg.as_str()) {
+                Some("--format") => {
+                    println!("dockerfile");
+                    println!("devcontainer");
+                    println!("github-actions");
+                    exit(0);
+                }
+                Some("--output") | Some("-o") => exit(0),
+                _ => {}
+            }
+        }
+
+        println!("--format");
+        println!("--output");
+        exit(0);
+    }
+}
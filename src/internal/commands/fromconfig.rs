@@ -1,19 +1,37 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::os::unix::process::CommandExt;
 use std::path::Path;
+use std::path::PathBuf;
+use std::process::exit;
 use std::process::Command as ProcessCommand;
+use std::time::SystemTime;
 
+use globset::Glob;
+use walkdir::WalkDir;
+
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::RepositoriesCache;
+use crate::internal::commands::arguments::inherited_arguments_env;
+use crate::internal::commands::arguments::syntax_arguments_env;
 use crate::internal::commands::utils::abs_or_rel_path;
+use crate::internal::commands::utils::glob_base;
 use crate::internal::commands::utils::split_name;
 use crate::internal::config::config;
 use crate::internal::config::CommandDefinition;
 use crate::internal::config::CommandSyntax;
 use crate::internal::config::ConfigSource;
+use crate::internal::env::shell_is_interactive;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+use crate::internal::workdir::up_fingerprint;
+use crate::omni_error;
+use crate::omni_info;
 
 #[derive(Debug, Clone)]
 pub struct ConfigCommand {
     name: Vec<String>,
-    details: CommandDefinition,
+    details: Box<CommandDefinition>,
 }
 
 impl ConfigCommand {
@@ -65,9 +83,14 @@ impl ConfigCommand {
     }
 
     pub fn new(name: String, details: CommandDefinition) -> Self {
-        let mut name = vec![name];
+        ConfigCommand {
+            name: Self::split_command_name(&name),
+            details: Box::new(details),
+        }
+    }
 
-        name = name.into_iter().flat_map(|n| split_name(&n, " ")).collect();
+    fn split_command_name(name: &str) -> Vec<String> {
+        let mut name = split_name(name, " ");
 
         if config(".").config_commands.split_on_dash {
             name = name.into_iter().flat_map(|n| split_name(&n, "-")).collect();
@@ -76,7 +99,7 @@ impl ConfigCommand {
             name = name.into_iter().flat_map(|n| split_name(&n, "/")).collect();
         }
 
-        ConfigCommand { name, details }
+        name
     }
 
     pub fn name(&self) -> Vec<String> {
@@ -148,21 +171,582 @@ impl ConfigCommand {
         let current_dir = std::env::current_dir().expect("Failed to get current directory");
         std::env::set_var("OMNI_CWD", current_dir.display().to_string());
 
-        let config_file = self.source();
-        let config_dir = Path::new(&config_file)
+        // The confirmation and the up check come first, so that nothing is
+        // run if the command is not going to be
+        self.prepare();
+
+        if !self.details.depends_on.is_empty() {
+            self.run_dependencies();
+        }
+
+        // Arguments can change what the command produces, so it is only
+        // skipped when called without any
+        if argv.is_empty() && self.is_up_to_date() {
+            omni_info!(format!(
+                "{} is up to date",
+                format!("omni {}", self.name.join(" ")).light_yellow(),
+            ));
+            exit(0);
+        }
+
+        let shell = self.shell();
+        let err = self.process_command(argv).exec();
+
+        omni_error!(format!("failed to run {}: {}", shell.light_blue(), err));
+        exit(1);
+    }
+
+    fn shell(&self) -> String {
+        self.details.shell.clone().unwrap_or("bash".to_string())
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        Path::new(&self.source())
             .parent()
-            .expect("Failed to get config directory");
-        if std::env::set_current_dir(config_dir).is_err() {
-            println!("Failed to change directory to {}", config_dir.display());
+            .expect("Failed to get config directory")
+            .to_path_buf()
+    }
+
+    /// Checks what needs to be before running the command, exiting if the
+    /// command cannot be run
+    fn prepare(&self) {
+        if self.details.requires_up {
+            self.check_up_current(&self.config_dir());
         }
 
-        ProcessCommand::new("bash")
-            .arg("-c")
-            .arg(self.details.run.clone())
-            .arg(self.source())
+        if let Some(confirm) = &self.details.confirm {
+            self.confirm(confirm);
+        }
+    }
+
+    fn process_command(&self, argv: Vec<String>) -> ProcessCommand {
+        let run_dir = self.run_dir();
+        if !run_dir.is_dir() {
+            omni_error!(format!(
+                "directory {} does not exist",
+                run_dir.display().to_string().light_blue()
+            ));
+            exit(1);
+        }
+
+        let shell = self.shell();
+        let (inline_flag, takes_arg0) = interpreter_args(&shell);
+
+        let mut command = ProcessCommand::new(&shell);
+        command.arg(inline_flag).arg(self.details.run.clone());
+        if takes_arg0 {
+            command.arg(self.source());
+        }
+        command
             .args(argv)
-            .exec();
+            .envs(self.details.env.iter())
+            .current_dir(run_dir);
+
+        command
+    }
+
+    /// The directory to run the command from: by default, the directory of
+    /// the configuration file; `dir` is relative to the root of the work
+    /// directory, or to the directory the command was called from if it
+    /// starts with `$OMNI_CWD`
+    fn run_dir(&self) -> PathBuf {
+        let config_dir = self.config_dir();
+        let dir = match &self.details.dir {
+            Some(dir) => dir,
+            None => return config_dir,
+        };
+
+        for prefix in ["${OMNI_CWD}", "$OMNI_CWD"] {
+            if let Some(rel_dir) = dir.strip_prefix(prefix) {
+                let current_dir = std::env::current_dir().expect("Failed to get current directory");
+                return current_dir.join(rel_dir.trim_start_matches('/'));
+            }
+        }
+
+        let wd = workdir(config_dir.to_string_lossy());
+        let root = match wd.root() {
+            Some(root) => PathBuf::from(root),
+            None => config_dir,
+        };
+
+        root.join(dir)
+    }
+
+    /// Runs the commands this command depends on, and their own dependencies,
+    /// each only once; dependencies that do not depend on each other are run
+    /// at the same time if the command is `parallel`
+    fn run_dependencies(&self) {
+        let tasks = match self.dependency_order(&ConfigCommand::all()) {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                omni_error!(err);
+                exit(1);
+            }
+        };
+
+        let mut done: HashSet<Vec<String>> = HashSet::new();
+        let mut pending = tasks;
+        while !pending.is_empty() {
+            // As tasks are in dependency order, the first pending task can
+            // always be run
+            let (ready, rest): (Vec<_>, Vec<_>) = pending.into_iter().partition(|(_, deps)| {
+                deps.iter()
+                    .all(|dep: &ConfigCommand| done.contains(&dep.name))
+            });
+            let (ready, rest) = if self.details.parallel {
+                (ready, rest)
+            } else {
+                let mut ready = ready.into_iter();
+                let first = ready.next().into_iter().collect::<Vec<_>>();
+                let mut rest = rest;
+                rest.extend(ready);
+                (first, rest)
+            };
+            pending = rest;
+
+            let mut children = vec![];
+            for (task, _) in ready.iter() {
+                let task_name = format!("omni {}", task.name.join(" "));
+                if task.is_up_to_date() {
+                    omni_info!(format!("{} is up to date", task_name.light_yellow()));
+                    continue;
+                }
+
+                task.prepare();
+                omni_info!(format!("running {}", task_name.light_yellow()));
+                match task.dependency_command().spawn() {
+                    Ok(child) => children.push((task_name, child)),
+                    Err(err) => {
+                        omni_error!(format!(
+                            "failed to run {}: {}",
+                            task_name.light_yellow(),
+                            err
+                        ));
+                        exit(1);
+                    }
+                }
+            }
+
+            let mut failed = None;
+            for (task_name, mut child) in children {
+                match child.wait() {
+                    Ok(status) if status.success() => {}
+                    Ok(status) => {
+                        omni_error!(format!("{} failed ({})", task_name.light_yellow(), status));
+                        failed = failed.or(status.code()).or(Some(1));
+                    }
+                    Err(err) => {
+                        omni_error!(format!("{} failed: {}", task_name.light_yellow(), err));
+                        failed = failed.or(Some(1));
+                    }
+                }
+            }
+            if let Some(exit_code) = failed {
+                exit(exit_code);
+            }
+
+            done.extend(ready.into_iter().map(|(task, _)| task.name));
+        }
+    }
+
+    /// The process to run this command as a dependency of another one; it
+    /// does not receive any argument, and so does not inherit the parsed
+    /// arguments of the command that depends on it
+    fn dependency_command(&self) -> ProcessCommand {
+        let vars = match self.dependency_arguments_env() {
+            Ok(vars) => vars,
+            Err(err) => {
+                omni_error!(err);
+                exit(1);
+            }
+        };
+
+        let mut command = self.process_command(vec![]);
+        for key in inherited_arguments_env() {
+            command.env_remove(key);
+        }
+        command
+            .env("OMNI_SUBCOMMAND", self.name.join(" "))
+            .envs(vars);
+
+        command
+    }
+
+    /// Returns the `OMNI_ARG_*` environment variables of this command when run
+    /// without arguments, or an error if its syntax requires arguments
+    fn dependency_arguments_env(&self) -> Result<Vec<(String, String)>, String> {
+        let syntax = match &self.details.syntax {
+            Some(syntax) => syntax,
+            None => return Ok(vec![]),
+        };
+
+        let task_name = format!("omni {}", self.name.join(" ")).light_yellow();
+        if syntax
+            .parameters
+            .iter()
+            .any(|param| param.required && param.default.is_none())
+        {
+            return Err(format!(
+                "{} requires arguments, and cannot be used as a dependency",
+                task_name
+            ));
+        }
+
+        if !syntax.parses_args() {
+            return Ok(vec![]);
+        }
+
+        syntax_arguments_env(syntax, &[]).map_err(|err| {
+            let err_str = err.to_string();
+            let err_str = err_str.lines().next().unwrap_or_default();
+            format!(
+                "{} cannot be used as a dependency: {}",
+                task_name,
+                err_str.trim_start_matches("error: ")
+            )
+        })
+    }
+
+    /// Returns the dependencies of this command, recursively, with their own
+    /// direct dependencies, ordered so that each comes after what it depends on
+    fn dependency_order(
+        &self,
+        all_commands: &[ConfigCommand],
+    ) -> Result<Vec<(ConfigCommand, Vec<ConfigCommand>)>, String> {
+        fn visit(
+            command: &ConfigCommand,
+            all_commands: &[ConfigCommand],
+            visiting: &mut Vec<Vec<String>>,
+            order: &mut Vec<(ConfigCommand, Vec<ConfigCommand>)>,
+        ) -> Result<(), String> {
+            if order.iter().any(|(task, _)| task.name == command.name) {
+                return Ok(());
+            }
+            if visiting.contains(&command.name) {
+                return Err(format!(
+                    "circular dependency for {}",
+                    format!("omni {}", command.name.join(" ")).light_yellow()
+                ));
+            }
+            visiting.push(command.name.clone());
+
+            let mut deps = vec![];
+            for dep_name in command.details.depends_on.iter() {
+                let dep_parts = ConfigCommand::split_command_name(dep_name);
+                let dep = all_commands
+                    .iter()
+                    .find(|cmd| cmd.name == dep_parts || cmd.aliases().contains(&dep_parts))
+                    .ok_or_else(|| {
+                        format!(
+                            "unknown dependency {} for {}",
+                            dep_name.light_yellow(),
+                            format!("omni {}", command.name.join(" ")).light_yellow()
+                        )
+                    })?;
+
+                dep.dependency_arguments_env()?;
+                visit(dep, all_commands, visiting, order)?;
+                deps.push(dep.clone());
+            }
+
+            visiting.pop();
+            order.push((command.clone(), deps));
+            Ok(())
+        }
+
+        let mut order = vec![];
+        visit(self, all_commands, &mut vec![], &mut order)?;
+
+        // The last task is the command itself
+        order.pop();
+        Ok(order)
+    }
+
+    /// A command with outputs is up to date if all of its outputs exist, and
+    /// none of its inputs were modified after them
+    fn is_up_to_date(&self) -> bool {
+        if self.details.outputs.is_empty() {
+            return false;
+        }
+
+        let run_dir = self.run_dir();
+        let outputs = match glob_mtimes(&run_dir, &self.details.outputs) {
+            Some(outputs) => outputs,
+            None => return false,
+        };
+        let inputs = glob_mtimes(&run_dir, &self.details.inputs).unwrap_or_default();
+
+        match (outputs.iter().min(), inputs.iter().max()) {
+            (Some(oldest_output), Some(newest_input)) => newest_input <= oldest_output,
+            (Some(_), None) => self.details.inputs.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn check_up_current(&self, config_dir: &Path) {
+        let config_dir = config_dir.to_string_lossy().to_string();
+        let wd = workdir(&config_dir);
+        let (wd_id, wd_root) = match (wd.id(), wd.root()) {
+            (Some(wd_id), Some(wd_root)) => (wd_id, wd_root.to_string()),
+            _ => return,
+        };
+
+        if !RepositoriesCache::get().check_fingerprint(&wd_id, "up", up_fingerprint(&wd_root)) {
+            omni_error!(format!(
+                "the environment is not up to date, run {} first",
+                "omni up".light_yellow(),
+            ));
+            exit(1);
+        }
+    }
+
+    fn confirm(&self, message: &str) {
+        if !shell_is_interactive() {
+            omni_error!("this command requires confirmation, but the shell is not interactive");
+            exit(1);
+        }
+
+        let message = if message.is_empty() {
+            format!(
+                "Do you want to run {}?",
+                format!("omni {}", self.name.join(" ")).light_yellow(),
+            )
+        } else {
+            message.to_string()
+        };
+
+        let question = requestty::Question::confirm("confirm_command")
+            .ask_if_answered(true)
+            .on_esc(requestty::OnEsc::Terminate)
+            .message(format!("{} {}", "omni:".light_cyan(), message))
+            .default(false)
+            .build();
+
+        match requestty::prompt_one(question) {
+            Ok(requestty::Answer::Bool(true)) => {}
+            Ok(_) => exit(1),
+            Err(err) => {
+                println!("{}", format!("[✘] {:?}", err).red());
+                exit(1);
+            }
+        }
+    }
+}
+
+/// The flag used to pass the code to run to the interpreter, and whether the
+/// interpreter takes the name of the script as first argument after the code,
+/// as shells do for `$0`
+fn interpreter_args(shell: &str) -> (&'static str, bool) {
+    let name = Path::new(shell)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match name.as_str() {
+        "node" | "ruby" | "perl" => ("-e", false),
+        name if name.starts_with("python") => ("-c", false),
+        _ => ("-c", true),
+    }
+}
+
+/// Returns the modification times of the files matching the globs, relative
+/// to the given directory, or `None` if any of the globs matches no file;
+/// only the literal base directory of each glob is walked
+fn glob_mtimes(dir: &Path, globs: &[String]) -> Option<Vec<SystemTime>> {
+    let mut mtimes = vec![];
+    for glob in globs {
+        let matcher = Glob::new(glob).ok()?.compile_matcher();
+
+        let mut matched = false;
+        for entry in WalkDir::new(dir.join(glob_base(glob)))
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .flatten()
+        {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let rel_path = match entry.path().strip_prefix(dir) {
+                Ok(rel_path) => rel_path,
+                Err(_) => continue,
+            };
+
+            if matcher.is_match(rel_path) {
+                matched = true;
+                mtimes.push(entry.metadata().ok()?.modified().ok()?);
+            }
+        }
+
+        if !matched {
+            return None;
+        }
+    }
+
+    Some(mtimes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::config::ConfigExtendOptions;
+    use crate::internal::config::ConfigValue;
+    use crate::internal::config::OmniConfig;
+
+    /// Returns the commands defined by the YAML, as if it was the content of
+    /// the `commands` parameter of the configuration
+    fn commands(yaml: &str) -> Vec<ConfigCommand> {
+        let yaml = yaml
+            .lines()
+            .map(|line| format!("  {}\n", line))
+            .collect::<String>();
+        let mut config_value = ConfigValue::default();
+        config_value.extend(
+            ConfigValue::from_str(&format!("commands:\n{}", yaml)),
+            ConfigExtendOptions::new(),
+            vec![],
+        );
+        ConfigCommand::all_commands(
+            OmniConfig::from_config_value(&config_value).commands,
+            vec![],
+        )
+    }
+
+    fn dependency_order(commands: &[ConfigCommand], name: &str) -> Result<Vec<String>, String> {
+        let command = commands
+            .iter()
+            .find(|command| command.name.join(" ") == name)
+            .unwrap();
+        command.dependency_order(commands).map(|order| {
+            order
+                .into_iter()
+                .map(|(task, deps)| {
+                    let deps = deps
+                        .iter()
+                        .map(|dep| dep.name.join(" "))
+                        .collect::<Vec<_>>();
+                    format!("{} <- [{}]", task.name.join(" "), deps.join(", "))
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn dependency_order_runs_each_dependency_once() {
+        let commands = commands(concat!(
+            "ci:\n",
+            "  depends_on: [check, gen, lint]\n",
+            "check:\n",
+            "  depends_on: [build, lint]\n",
+            "build:\n",
+            "  run: make\n",
+            "lint:\n",
+            "  depends_on: gen\n",
+            "gen:\n",
+            "  run: generate\n",
+        ));
+
+        assert_eq!(
+            dependency_order(&commands, "ci").unwrap(),
+            vec![
+                "build <- []",
+                "gen <- []",
+                "lint <- [gen]",
+                "check <- [build, lint]",
+            ]
+        );
+        assert_eq!(
+            dependency_order(&commands, "gen").unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn dependency_order_resolves_aliases_and_subcommands() {
+        let commands = commands(concat!(
+            "deploy:\n",
+            "  depends_on: [b, \"assets compile\"]\n",
+            "build:\n",
+            "  aliases: [b]\n",
+            "assets:\n",
+            "  subcommands:\n",
+            "    compile:\n",
+            "      depends_on: b\n",
+        ));
+
+        assert_eq!(
+            dependency_order(&commands, "deploy").unwrap(),
+            vec!["build <- []", "assets compile <- [build]"]
+        );
+    }
+
+    #[test]
+    fn dependency_order_detects_cycles() {
+        let commands = commands(concat!(
+            "first:\n",
+            "  depends_on: second\n",
+            "second:\n",
+            "  depends_on: third\n",
+            "third:\n",
+            "  depends_on: first\n",
+            "self:\n",
+            "  depends_on: self\n",
+        ));
+
+        let err = dependency_order(&commands, "first").unwrap_err();
+        assert!(err.contains("circular dependency for"), "{}", err);
+        assert!(err.contains("omni first"), "{}", err);
+
+        let err = dependency_order(&commands, "self").unwrap_err();
+        assert!(err.contains("circular dependency for"), "{}", err);
+    }
+
+    #[test]
+    fn dependency_order_rejects_invalid_dependencies() {
+        let commands = commands(concat!(
+            "missing:\n",
+            "  depends_on: [build, nope]\n",
+            "build:\n",
+            "  run: make\n",
+            "greet:\n",
+            "  syntax:\n",
+            "    arguments:\n",
+            "      - who:\n",
+            "          required: true\n",
+            "welcome:\n",
+            "  depends_on: greet\n",
+        ));
+
+        let err = dependency_order(&commands, "missing").unwrap_err();
+        assert!(err.contains("unknown dependency"), "{}", err);
+        assert!(err.contains("nope"), "{}", err);
+
+        let err = dependency_order(&commands, "welcome").unwrap_err();
+        assert!(err.contains("requires arguments"), "{}", err);
+    }
+
+    fn globs(globs: &[&str]) -> Vec<String> {
+        globs.iter().map(|glob| glob.to_string()).collect()
+    }
 
-        panic!("Something went wrong");
+    #[test]
+    fn glob_mtimes_matches_all_globs() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("src/nested")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("out")).unwrap();
+        std::fs::write(tmp.path().join("src/main.rs"), "").unwrap();
+        std::fs::write(tmp.path().join("src/nested/lib.rs"), "").unwrap();
+        std::fs::write(tmp.path().join("out/app"), "").unwrap();
+        std::fs::write(tmp.path().join("Cargo.lock"), "").unwrap();
+
+        let mtimes = glob_mtimes(tmp.path(), &globs(&["src/**/*.rs"])).unwrap();
+        assert_eq!(mtimes.len(), 2);
+
+        let mtimes = glob_mtimes(tmp.path(), &globs(&["out/app", "*.lock"])).unwrap();
+        assert_eq!(mtimes.len(), 2);
+
+        assert!(glob_mtimes(tmp.path(), &globs(&["out/app", "missing/*"])).is_none());
+        assert!(glob_mtimes(tmp.path(), &globs(&["src/*.py"])).is_none());
+        assert_eq!(glob_mtimes(tmp.path(), &[]), Some(vec![]));
     }
 }
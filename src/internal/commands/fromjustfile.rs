@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::exit;
+use std::process::Command as ProcessCommand;
+
+use regex::Regex;
+
+use crate::internal::commands::utils::abs_or_rel_path;
+use crate::internal::commands::utils::split_name;
+use crate::internal::config::config;
+use crate::internal::config::CommandSyntax;
+use crate::internal::config::SyntaxOptArg;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+use crate::omni_error;
+
+#[derive(Debug, Clone)]
+pub struct JustfileCommand {
+    name: Vec<String>,
+    aliases: Vec<Vec<String>>,
+    category: Option<String>,
+    desc: Option<String>,
+    recipe: String,
+    parameters: Vec<SyntaxOptArg>,
+    source: String,
+    lineno: usize,
+}
+
+impl JustfileCommand {
+    pub fn all_from_path(path: &str) -> Vec<Self> {
+        // Canonicalize the path
+        let abs_path = match fs::canonicalize(path) {
+            Ok(abs_path) => abs_path,
+            Err(_) => return vec![],
+        };
+
+        let mut path = abs_path.as_path();
+
+        // Get the git environment
+        let wd = workdir(path.to_str().unwrap());
+
+        let mut commands = vec![];
+        while let Some(parent) = path.parent() {
+            if let Ok(entries) = fs::read_dir(path) {
+                for entry in entries.flatten() {
+                    let filepath = entry.path();
+                    if !filepath.is_file() {
+                        continue;
+                    }
+
+                    let filename = entry.file_name().to_string_lossy().to_lowercase();
+                    if filename == "justfile" || filename == ".justfile" {
+                        commands.extend(Self::all_from_file(filepath.to_str().unwrap()));
+                    }
+                }
+            }
+
+            if wd.in_workdir() && wd.root().unwrap() == path.to_str().unwrap() {
+                break;
+            }
+
+            path = parent;
+        }
+
+        commands
+    }
+
+    pub fn all_from_file(filepath: &str) -> Vec<Self> {
+        let content = match fs::read_to_string(filepath) {
+            Ok(content) => content,
+            Err(_) => return vec![],
+        };
+
+        let recipe_regex = Regex::new(
+            r"^@?(?<recipe>[a-zA-Z_][a-zA-Z0-9_-]*)(?<params>(\s+[^:=\s][^:]*?)?)\s*:([^=]|$)",
+        )
+        .expect("Invalid regex pattern?!");
+        let alias_regex =
+            Regex::new(r"^alias\s+(?<alias>[a-zA-Z_][a-zA-Z0-9_-]*)\s*:=\s*(?<recipe>\S+)")
+                .expect("Invalid regex pattern?!");
+        let group_regex = Regex::new(r#"group\(\s*['"](?<group>[^'"]*)['"]\s*\)"#)
+            .expect("Invalid regex pattern?!");
+        let doc_regex =
+            Regex::new(r#"doc\(\s*['"](?<doc>[^'"]*)['"]\s*\)"#).expect("Invalid regex pattern?!");
+
+        let mut recipes = vec![];
+        let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+
+        // The comment and attributes right before a recipe apply to it
+        let mut comment: Option<String> = None;
+        let mut group = None;
+        let mut doc = None;
+        let mut private = false;
+
+        for (lineno, line) in content.lines().enumerate() {
+            // Recipe bodies, and the empty lines between recipes
+            if line.trim().is_empty() || line.starts_with([' ', '\t']) {
+                comment = None;
+                group = None;
+                doc = None;
+                private = false;
+                continue;
+            }
+
+            if let Some(line_comment) = line.strip_prefix('#') {
+                if !line_comment.starts_with('!') {
+                    comment = Some(line_comment.trim().to_string());
+                }
+                continue;
+            }
+
+            if let Some(attributes) = line
+                .strip_prefix('[')
+                .and_then(|line| line.trim_end().strip_suffix(']'))
+            {
+                if let Some(captures) = group_regex.captures(attributes) {
+                    group = Some(captures["group"].to_string());
+                }
+                if let Some(captures) = doc_regex.captures(attributes) {
+                    doc = Some(captures["doc"].to_string());
+                }
+                if attributes
+                    .split(',')
+                    .any(|attribute| attribute.trim() == "private")
+                {
+                    private = true;
+                }
+                continue;
+            }
+
+            if let Some(captures) = alias_regex.captures(line) {
+                aliases
+                    .entry(captures["recipe"].to_string())
+                    .or_default()
+                    .push(captures["alias"].to_string());
+            } else if let Some(captures) = recipe_regex.captures(line) {
+                let recipe = captures["recipe"].to_string();
+
+                // Private recipes are not meant to be called directly
+                if !private && !recipe.starts_with('_') {
+                    recipes.push((
+                        recipe,
+                        captures["params"].to_string(),
+                        group.clone(),
+                        doc.clone().or(comment.clone()),
+                        lineno + 1,
+                    ));
+                }
+            }
+
+            comment = None;
+            group = None;
+            doc = None;
+            private = false;
+        }
+
+        recipes
+            .into_iter()
+            .map(|(recipe, params, category, desc, lineno)| {
+                let recipe_aliases = aliases.remove(&recipe).unwrap_or_default();
+                JustfileCommand::new(
+                    recipe,
+                    recipe_aliases,
+                    category,
+                    desc,
+                    parse_parameters(&params),
+                    filepath.to_string(),
+                    lineno,
+                )
+            })
+            .collect()
+    }
+
+    pub fn new(
+        recipe: String,
+        aliases: Vec<String>,
+        category: Option<String>,
+        desc: Option<String>,
+        parameters: Vec<SyntaxOptArg>,
+        source: String,
+        lineno: usize,
+    ) -> Self {
+        JustfileCommand {
+            name: Self::split_recipe_name(&recipe),
+            aliases: aliases
+                .iter()
+                .map(|alias| Self::split_recipe_name(alias))
+                .collect(),
+            category,
+            desc,
+            recipe,
+            parameters,
+            source,
+            lineno,
+        }
+    }
+
+    fn split_recipe_name(recipe: &str) -> Vec<String> {
+        let mut name = vec![recipe.to_string()];
+        if config(".").justfile_commands.split_on_dash {
+            name = name.into_iter().flat_map(|n| split_name(&n, "-")).collect();
+        }
+        name
+    }
+
+    pub fn name(&self) -> Vec<String> {
+        self.name.clone()
+    }
+
+    pub fn aliases(&self) -> Vec<Vec<String>> {
+        self.aliases.clone()
+    }
+
+    pub fn source(&self) -> String {
+        self.source.clone()
+    }
+
+    pub fn lineno(&self) -> usize {
+        self.lineno
+    }
+
+    pub fn help(&self) -> Option<String> {
+        self.desc.clone()
+    }
+
+    pub fn syntax(&self) -> Option<CommandSyntax> {
+        if self.parameters.is_empty() {
+            return None;
+        }
+
+        Some(CommandSyntax {
+            usage: None,
+            parameters: self.parameters.clone(),
+        })
+    }
+
+    pub fn category(&self) -> Option<Vec<String>> {
+        let source = abs_or_rel_path(&self.source);
+        let mut category = vec![source];
+
+        if let Some(cat) = &self.category {
+            category.push(cat.clone());
+        }
+
+        Some(category)
+    }
+
+    pub fn exec(&self, argv: Vec<String>) {
+        // Get the current directory so we can store it in a variable
+        let current_dir = std::env::current_dir().expect("Failed to get current directory");
+        std::env::set_var("OMNI_CWD", current_dir.display().to_string());
+
+        let justfile_dir = Path::new(&self.source).parent().unwrap();
+        if std::env::set_current_dir(justfile_dir).is_err() {
+            println!("Failed to change directory to {}", justfile_dir.display());
+        }
+
+        let err = ProcessCommand::new("just")
+            .arg("--justfile")
+            .arg(self.source())
+            .arg(self.recipe.clone())
+            .args(argv)
+            .exec();
+
+        omni_error!(format!("failed to run {}: {}", "just".light_blue(), err));
+        exit(1);
+    }
+}
+
+/// Parses the parameters of a recipe, such as `target env='dev' +files`,
+/// where `+` and `*` mark variadic parameters taking one or more, or zero or
+/// more values, and `$` marks parameters exported as environment variables
+fn parse_parameters(params: &str) -> Vec<SyntaxOptArg> {
+    let mut parameters = vec![];
+
+    for param in split_parameters(params) {
+        let (variadic, param) = match param.strip_prefix('+') {
+            Some(param) => (Some(true), param),
+            None => match param.strip_prefix('*') {
+                Some(param) => (Some(false), param),
+                None => (None, param.as_str()),
+            },
+        };
+        let param = param.trim_start_matches('$');
+
+        let (name, default) = match param.split_once('=') {
+            Some((name, default)) => (name, Some(unquote(default))),
+            None => (param, None),
+        };
+
+        let mut parameter = SyntaxOptArg::new(
+            match variadic {
+                Some(_) => format!("{}...", name),
+                None => name.to_string(),
+            },
+            None,
+            default.is_none() && variadic != Some(false),
+        );
+        parameter.default = default;
+        parameter.multiple = variadic.is_some();
+        parameters.push(parameter);
+    }
+
+    parameters
+}
+
+/// Splits the parameters on whitespace, except within quotes, parentheses
+/// or backticks, which can be used in default values
+fn split_parameters(params: &str) -> Vec<String> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut quote = None;
+    let mut depth = 0;
+
+    for c in params.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                c if c.is_whitespace() && depth <= 0 => {
+                    if !current.is_empty() {
+                        parts.push(std::mem::take(&mut current));
+                    }
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        current.push(c);
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    parts
+}
+
+fn unquote(value: &str) -> String {
+    for quote in ['\'', '"'] {
+        if let Some(value) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return value.to_string();
+        }
+    }
+    value.to_string()
+}
@@ -1,11 +1,14 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fs;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
 use std::os::unix::process::CommandExt;
+use std::path::Component;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command as ProcessCommand;
 
+use globset::GlobBuilder;
+use lazy_static::lazy_static;
 use regex::Regex;
 
 use crate::internal::commands::utils::abs_or_rel_path;
@@ -14,12 +17,39 @@ use crate::internal::config::config;
 use crate::internal::config::CommandSyntax;
 use crate::internal::workdir;
 
+/// The file names looked up by `make` in a directory, by order of priority
+const MAKEFILE_NAMES: [&str; 3] = ["GNUmakefile", "makefile", "Makefile"];
+
+/// Makefiles can include each other, and variables can reference each
+/// other; this protects against infinite recursion
+const MAX_DEPTH: usize = 10;
+
+lazy_static! {
+    static ref TARGET_REGEX: Regex =
+        Regex::new(r"^[a-zA-Z_0-9\-/]+$").expect("Invalid regex pattern?!");
+    static ref INCLUDE_REGEX: Regex =
+        Regex::new(r"^\s*(?:-include|sinclude|include)\s+(?<files>.+)$")
+            .expect("Invalid regex pattern?!");
+    static ref ASSIGNMENT_REGEX: Regex = Regex::new(
+        r"^\s*(?:(?:override|export)\s+)*(?<name>[a-zA-Z0-9_.\-]+)\s*(?<op>:::=|::=|:=|\?=|\+=|!=|=)\s*(?<value>.*)$"
+    )
+    .expect("Invalid regex pattern?!");
+    static ref CONDITIONAL_REGEX: Regex =
+        Regex::new(r"^\s*(?:ifeq|ifneq|ifdef|ifndef|else|endif)(?:\s|$)")
+            .expect("Invalid regex pattern?!");
+    static ref SUBMAKE_REGEX: Regex = Regex::new(
+        r"(?:^|[\s;&|(@+\-])make\s(?:[^;&|]*?\s)?(?:-C\s*|--directory[=\s]+)(?<dir>[^\s;&|)]+)"
+    )
+    .expect("Invalid regex pattern?!");
+}
+
 #[derive(Debug, Clone)]
 pub struct MakefileCommand {
     name: Vec<String>,
     category: Option<String>,
     desc: Option<String>,
     target: String,
+    makefile: String,
     source: String,
     lineno: usize,
 }
@@ -76,49 +106,88 @@ impl MakefileCommand {
     }
 
     pub fn all_from_file(filepath: &str) -> Vec<Self> {
-        let mut commands = vec![];
+        let mut visited = HashSet::new();
+        Self::all_from_file_with_prefix(Path::new(filepath), &[], &mut visited, 0)
+    }
 
-        // Open the file and read it line by line
-        let file = File::open(filepath);
-        if file.is_err() {
-            return commands;
+    /// Loads the targets of a Makefile and of the files it includes, as well
+    /// as the targets of the Makefiles it calls through `make -C <dir>`,
+    /// which are prefixed by the path of their directory
+    fn all_from_file_with_prefix(
+        filepath: &Path,
+        prefix: &[String],
+        visited: &mut HashSet<PathBuf>,
+        depth: usize,
+    ) -> Vec<Self> {
+        if depth > MAX_DEPTH {
+            return vec![];
         }
 
-        let file = file.unwrap();
-        let reader = BufReader::new(file);
-
-        // Prepare the target regex
-        let target = Regex::new(r"^(?<target>[a-zA-Z_0-9\-\/\/]+):(.*?##\s*(?<desc>.*))?$")
-            .expect("Invalid regex pattern?!");
+        match fs::canonicalize(filepath) {
+            Ok(canonical) if visited.insert(canonical.clone()) => {}
+            _ => return vec![],
+        }
 
-        let mut category = None;
-        for (lineno, line) in reader.lines().enumerate() {
-            if line.is_err() {
-                break;
+        let mut parser = MakefileParser::new(filepath);
+        parser.parse_file(filepath, 0);
+
+        let makefile = filepath.to_string_lossy().to_string();
+        let mut commands = parser
+            .targets
+            .iter()
+            .filter(|target| {
+                // When the Makefile declares its phony targets, the other
+                // targets are files to be built rather than commands, unless
+                // they have been documented
+                parser.phony.is_empty()
+                    || parser.phony.contains(&target.target)
+                    || target.desc.is_some()
+            })
+            .map(|target| {
+                MakefileCommand::new(
+                    target.target.clone(),
+                    prefix,
+                    target.category.clone(),
+                    target.desc.clone(),
+                    makefile.clone(),
+                    target.source.clone(),
+                    target.lineno,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for dir in parser.subdirs.iter() {
+            let rel_dir = Path::new(dir);
+
+            // Only consider directories within the tree of the Makefile
+            let mut subdir_prefix = prefix.to_vec();
+            let mut within_tree = true;
+            for component in rel_dir.components() {
+                match component {
+                    Component::Normal(part) => {
+                        subdir_prefix.push(part.to_string_lossy().to_string())
+                    }
+                    Component::CurDir => {}
+                    _ => within_tree = false,
+                }
             }
-            let line = line.unwrap();
-
-            if let Some(cat) = line.strip_prefix("##@") {
-                category = Some(cat.trim().to_string());
+            if !within_tree || subdir_prefix.len() == prefix.len() {
                 continue;
             }
 
-            match target.captures(&line) {
-                Some(captures) => {
-                    let target = captures.name("target").unwrap().as_str().to_string();
-
-                    let desc = captures.name("desc").map(|m| m.as_str().trim().to_string());
-
-                    commands.push(MakefileCommand::new(
-                        target,
-                        category.clone(),
-                        desc,
-                        filepath.to_string(),
-                        lineno + 1,
-                    ));
-                }
-                None => continue,
-            };
+            let subdir = parser.root_dir.join(rel_dir);
+            if let Some(sub_makefile) = MAKEFILE_NAMES
+                .iter()
+                .map(|name| subdir.join(name))
+                .find(|path| path.is_file())
+            {
+                commands.extend(Self::all_from_file_with_prefix(
+                    &sub_makefile,
+                    &subdir_prefix,
+                    visited,
+                    depth + 1,
+                ));
+            }
         }
 
         commands
@@ -126,8 +195,10 @@ impl MakefileCommand {
 
     pub fn new(
         target: String,
+        prefix: &[String],
         category: Option<String>,
         desc: Option<String>,
+        makefile: String,
         source: String,
         lineno: usize,
     ) -> Self {
@@ -138,12 +209,14 @@ impl MakefileCommand {
         if config(".").makefile_commands.split_on_slash {
             name = name.into_iter().flat_map(|n| split_name(&n, "/")).collect();
         }
+        name.splice(0..0, prefix.iter().cloned());
 
         MakefileCommand {
             name,
             category,
             desc,
             target,
+            makefile,
             source,
             lineno,
         }
@@ -174,8 +247,8 @@ impl MakefileCommand {
     }
 
     pub fn category(&self) -> Option<Vec<String>> {
-        let source = abs_or_rel_path(&self.source);
-        let mut category = vec![source];
+        let makefile = abs_or_rel_path(&self.makefile);
+        let mut category = vec![makefile];
 
         if let Some(cat) = &self.category {
             category.push(cat.clone());
@@ -189,14 +262,14 @@ impl MakefileCommand {
         let current_dir = std::env::current_dir().expect("Failed to get current directory");
         std::env::set_var("OMNI_CWD", current_dir.display().to_string());
 
-        let makefile_dir = Path::new(&self.source).parent().unwrap();
+        let makefile_dir = Path::new(&self.makefile).parent().unwrap();
         if std::env::set_current_dir(makefile_dir).is_err() {
             println!("Failed to change directory to {}", makefile_dir.display());
         }
 
         ProcessCommand::new("make")
             .arg("-f")
-            .arg(self.source())
+            .arg(&self.makefile)
             .arg(self.target.clone())
             .args(argv)
             .exec();
@@ -204,3 +277,563 @@ impl MakefileCommand {
         panic!("Something went wrong");
     }
 }
+
+#[derive(Debug, Clone)]
+struct MakefileTarget {
+    target: String,
+    category: Option<String>,
+    desc: Option<String>,
+    source: String,
+    lineno: usize,
+}
+
+/// A best-effort parser of Makefiles, which follows includes and expands
+/// the variables defined in the Makefiles, but does not evaluate
+/// conditionals nor functions
+struct MakefileParser {
+    root_dir: PathBuf,
+    variables: HashMap<String, String>,
+    phony: HashSet<String>,
+    targets: Vec<MakefileTarget>,
+    subdirs: Vec<String>,
+    included: HashSet<PathBuf>,
+}
+
+impl MakefileParser {
+    fn new(makefile: &Path) -> Self {
+        let root_dir = makefile
+            .parent()
+            .map(|dir| dir.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        Self {
+            root_dir,
+            variables: HashMap::new(),
+            phony: HashSet::new(),
+            targets: vec![],
+            subdirs: vec![],
+            included: HashSet::new(),
+        }
+    }
+
+    fn parse_file(&mut self, filepath: &Path, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+
+        match fs::canonicalize(filepath) {
+            Ok(canonical) if self.included.insert(canonical.clone()) => {}
+            _ => return,
+        }
+
+        let content = match fs::read_to_string(filepath) {
+            Ok(content) => content,
+            Err(_) => return,
+        };
+        let source = filepath.to_string_lossy().to_string();
+
+        let mut category = None;
+        let mut in_define = false;
+        for (lineno, line) in logical_lines(&content) {
+            if in_define {
+                in_define = line.trim() != "endef";
+                continue;
+            }
+
+            // Recipes can call make in a subdirectory
+            if line.starts_with('\t') {
+                self.parse_recipe(&line);
+                continue;
+            }
+
+            if let Some(cat) = line.strip_prefix("##@") {
+                category = Some(cat.trim().to_string());
+                continue;
+            }
+
+            if line.trim_start().starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+
+            let trimmed = line.trim();
+            if trimmed.starts_with("define ") || trimmed == "define" {
+                in_define = true;
+                continue;
+            }
+
+            if CONDITIONAL_REGEX.is_match(&line) {
+                continue;
+            }
+
+            if let Some(captures) = INCLUDE_REGEX.captures(&line) {
+                let files = self.expand(strip_comment(&captures["files"]), 0);
+                for file in self.resolve_includes(&files) {
+                    self.parse_file(&file, depth + 1);
+                }
+                continue;
+            }
+
+            if let Some(captures) = ASSIGNMENT_REGEX.captures(&line) {
+                let name = captures["name"].to_string();
+                let value = strip_comment(&captures["value"]).trim().to_string();
+                match &captures["op"] {
+                    ":=" | "::=" | ":::=" => {
+                        let value = self.expand(&value, 0);
+                        self.variables.insert(name, value);
+                    }
+                    "?=" => {
+                        self.variables.entry(name).or_insert(value);
+                    }
+                    "+=" => {
+                        let entry = self.variables.entry(name).or_default();
+                        if !entry.is_empty() {
+                            entry.push(' ');
+                        }
+                        entry.push_str(&value);
+                    }
+                    // The output of shell commands is not evaluated
+                    "!=" => {}
+                    _ => {
+                        self.variables.insert(name, value);
+                    }
+                }
+                continue;
+            }
+
+            self.parse_rule(&line, category.clone(), &source, lineno);
+        }
+    }
+
+    fn parse_rule(&mut self, line: &str, category: Option<String>, source: &str, lineno: usize) {
+        let (targets, rest) = match line.split_once(':') {
+            Some((targets, rest)) if !targets.contains('=') => (targets, rest),
+            _ => return,
+        };
+        let rest = rest.strip_prefix(':').unwrap_or(rest);
+
+        let (prerequisites, desc) = match rest.split_once("##") {
+            Some((prerequisites, desc)) => (prerequisites, Some(desc.trim().to_string())),
+            None => (strip_comment(rest), None),
+        };
+        let desc = desc.filter(|desc| !desc.is_empty());
+
+        // Target-specific variables do not define the target
+        if prerequisites.contains('=') {
+            return;
+        }
+
+        let targets = self.expand(targets, 0);
+        for target in targets.split_whitespace() {
+            if target == ".PHONY" {
+                let phony = self.expand(prerequisites, 0);
+                self.phony
+                    .extend(phony.split_whitespace().map(|t| t.to_string()));
+                continue;
+            }
+
+            // Special targets, pattern rules, files with extensions, and
+            // targets relying on variables that could not be expanded
+            if !TARGET_REGEX.is_match(target) {
+                continue;
+            }
+
+            match self.targets.iter_mut().find(|t| t.target == target) {
+                Some(existing) => {
+                    if existing.desc.is_none() && desc.is_some() {
+                        existing.desc = desc.clone();
+                    }
+                }
+                None => self.targets.push(MakefileTarget {
+                    target: target.to_string(),
+                    category: category.clone(),
+                    desc: desc.clone(),
+                    source: source.to_string(),
+                    lineno,
+                }),
+            }
+        }
+    }
+
+    fn parse_recipe(&mut self, line: &str) {
+        let line = self.expand(line, 0);
+        for captures in SUBMAKE_REGEX.captures_iter(&line) {
+            let dir = captures["dir"].trim_matches(|c| c == '"' || c == '\'');
+
+            // Directories computed when running the recipe, e.g. in loops
+            if dir.is_empty() || dir.contains('$') {
+                continue;
+            }
+
+            let dir = dir.trim_end_matches('/').to_string();
+            if !self.subdirs.contains(&dir) {
+                self.subdirs.push(dir);
+            }
+        }
+    }
+
+    /// Resolves the files of an include directive, relative to the directory
+    /// from which make is called, and supporting wildcards
+    fn resolve_includes(&self, files: &str) -> Vec<PathBuf> {
+        let mut resolved = vec![];
+
+        for file in files.split_whitespace() {
+            if file.contains('$') {
+                continue;
+            }
+
+            let path = self.root_dir.join(file);
+            if !file.contains(['*', '?', '[']) {
+                resolved.push(path);
+                continue;
+            }
+
+            let (dir, pattern) = match (path.parent(), path.file_name()) {
+                (Some(dir), Some(pattern)) => (dir, pattern.to_string_lossy().to_string()),
+                _ => continue,
+            };
+            let matcher = match GlobBuilder::new(&pattern).literal_separator(true).build() {
+                Ok(glob) => glob.compile_matcher(),
+                Err(_) => continue,
+            };
+
+            let mut matched = match fs::read_dir(dir) {
+                Ok(entries) => entries
+                    .flatten()
+                    .filter(|entry| matcher.is_match(entry.file_name()))
+                    .map(|entry| entry.path())
+                    .collect::<Vec<_>>(),
+                Err(_) => continue,
+            };
+            matched.sort();
+            resolved.extend(matched);
+        }
+
+        resolved
+    }
+
+    /// Expands the references to variables, `$(NAME)` or `${NAME}`; the
+    /// references that cannot be expanded are kept as they are
+    fn expand(&self, value: &str, depth: usize) -> String {
+        if depth > MAX_DEPTH || !value.contains('$') {
+            return value.to_string();
+        }
+
+        let mut expanded = String::new();
+        let mut chars = value.char_indices().peekable();
+        while let Some((idx, c)) = chars.next() {
+            let close = match (c, chars.peek()) {
+                ('$', Some((_, '('))) => ')',
+                ('$', Some((_, '{'))) => '}',
+                _ => {
+                    expanded.push(c);
+                    continue;
+                }
+            };
+            let open = chars.next().unwrap().1;
+
+            // Find the matching closing parenthesis or brace
+            let mut nesting = 1;
+            let mut end = None;
+            for (pos, c) in chars.by_ref() {
+                if c == open {
+                    nesting += 1;
+                } else if c == close {
+                    nesting -= 1;
+                    if nesting == 0 {
+                        end = Some(pos);
+                        break;
+                    }
+                }
+            }
+            let end = match end {
+                Some(end) => end,
+                None => {
+                    expanded.push_str(&value[idx..]);
+                    break;
+                }
+            };
+
+            let reference = &value[idx..=end];
+            let name = self.expand(&value[idx + 2..end], depth + 1);
+            match name.as_str() {
+                "MAKE" => expanded.push_str("make"),
+                "CURDIR" => expanded.push_str(&self.root_dir.to_string_lossy()),
+                name => match self.variables.get(name) {
+                    Some(variable) => expanded.push_str(&self.expand(variable, depth + 1)),
+                    None => expanded.push_str(reference),
+                },
+            }
+        }
+
+        expanded
+    }
+}
+
+/// Returns the lines of a Makefile with their line number, joining the
+/// lines ending with a backslash with the following ones
+fn logical_lines(content: &str) -> Vec<(usize, String)> {
+    let mut lines = vec![];
+    let mut current: Option<(usize, String)> = None;
+
+    for (idx, line) in content.lines().enumerate() {
+        let (continued, line) = match line.strip_suffix('\\') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+
+        let (lineno, mut text) = current.take().unwrap_or((idx + 1, String::new()));
+        if !text.is_empty() {
+            text.push(' ');
+            text.push_str(line.trim_start());
+        } else {
+            text.push_str(line);
+        }
+
+        if continued {
+            current = Some((lineno, text));
+        } else {
+            lines.push((lineno, text));
+        }
+    }
+
+    if let Some(line) = current {
+        lines.push(line);
+    }
+
+    lines
+}
+
+fn strip_comment(value: &str) -> &str {
+    match value.find('#') {
+        Some(idx) => &value[..idx],
+        None => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes the files of a fixture, given as paths relative to the root
+    /// of a temporary directory and their content
+    fn fixture(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = tmp.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        tmp
+    }
+
+    fn parse(tmp: &tempfile::TempDir) -> MakefileParser {
+        let makefile = tmp.path().join("Makefile");
+        let mut parser = MakefileParser::new(&makefile);
+        parser.parse_file(&makefile, 0);
+        parser
+    }
+
+    fn target<'a>(parser: &'a MakefileParser, name: &str) -> &'a MakefileTarget {
+        parser
+            .targets
+            .iter()
+            .find(|target| target.target == name)
+            .unwrap_or_else(|| panic!("target {} not found", name))
+    }
+
+    fn target_names(parser: &MakefileParser) -> Vec<String> {
+        parser
+            .targets
+            .iter()
+            .map(|target| target.target.clone())
+            .collect()
+    }
+
+    fn command_names(tmp: &tempfile::TempDir) -> Vec<String> {
+        let makefile = tmp.path().join("Makefile");
+        let mut names = MakefileCommand::all_from_file(makefile.to_str().unwrap())
+            .iter()
+            .map(|command| command.name().join(" "))
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn includes_with_globs() {
+        let tmp = fixture(&[
+            (
+                "Makefile",
+                "include mk/*.mk\n-include missing.mk optional/*.mk\nsinclude $(UNSET)/x.mk\nroot:\n",
+            ),
+            ("mk/b.mk", "beta: ## Beta target\n"),
+            ("mk/a.mk", "alpha:\n"),
+            ("mk/ignored.txt", "ignored:\n"),
+        ]);
+
+        let parser = parse(&tmp);
+        assert_eq!(target_names(&parser), vec!["alpha", "beta", "root"]);
+        assert_eq!(target(&parser, "beta").desc.as_deref(), Some("Beta target"));
+        assert!(target(&parser, "alpha")
+            .source
+            .ends_with(&format!("mk{}a.mk", std::path::MAIN_SEPARATOR)));
+    }
+
+    #[test]
+    fn includes_are_parsed_once() {
+        let tmp = fixture(&[
+            ("Makefile", "include common.mk\ninclude common.mk\nroot:\n"),
+            ("common.mk", "include Makefile\ncommon:\n"),
+        ]);
+
+        let parser = parse(&tmp);
+        assert_eq!(target_names(&parser), vec!["common", "root"]);
+    }
+
+    #[test]
+    fn descriptions_and_categories() {
+        let tmp = fixture(&[(
+            "Makefile",
+            concat!(
+                "build: deps ## Build the project\n",
+                "deps:\n",
+                "##@ Testing\n",
+                "test: build ##   Run the tests  \n",
+                "lint: # not a description\n",
+                "##@ Release\n",
+                "release: ##\n",
+                "test: ## Redefined description\n",
+            ),
+        )]);
+
+        let parser = parse(&tmp);
+        assert_eq!(
+            target_names(&parser),
+            vec!["build", "deps", "test", "lint", "release"]
+        );
+
+        let build = target(&parser, "build");
+        assert_eq!(build.desc.as_deref(), Some("Build the project"));
+        assert_eq!(build.category, None);
+        assert_eq!(build.lineno, 1);
+
+        let test = target(&parser, "test");
+        assert_eq!(test.desc.as_deref(), Some("Run the tests"));
+        assert_eq!(test.category.as_deref(), Some("Testing"));
+
+        assert_eq!(target(&parser, "lint").desc, None);
+        assert_eq!(target(&parser, "lint").category.as_deref(), Some("Testing"));
+        assert_eq!(target(&parser, "release").desc, None);
+        assert_eq!(
+            target(&parser, "release").category.as_deref(),
+            Some("Release")
+        );
+    }
+
+    #[test]
+    fn phony_filtering() {
+        let tmp = fixture(&[(
+            "Makefile",
+            concat!(
+                ".PHONY: build test\n",
+                "build: app\n",
+                "test:\n",
+                "app: main.c\n",
+                "docs: ## Generate the documentation\n",
+                "%.o: %.c\n",
+                "main.o: main.c\n",
+            ),
+        )]);
+        assert_eq!(command_names(&tmp), vec!["build", "docs", "test"]);
+
+        // Without phony targets, all the targets are considered commands
+        let tmp = fixture(&[("Makefile", "build: app\napp: main.c\n")]);
+        assert_eq!(command_names(&tmp), vec!["app", "build"]);
+    }
+
+    #[test]
+    fn variable_expansion() {
+        let tmp = fixture(&[(
+            "Makefile",
+            concat!(
+                "BIN := app\n",
+                "BIN ?= ignored\n",
+                "MODE ?= release\n",
+                "TARGETS = build\n",
+                "TARGETS += test # comment\n",
+                "override NESTED := $(BIN)$(MODE)\n",
+                "SHELLOUT != echo ignored\n",
+                "$(TARGETS):\n",
+                "$(NESTED): ## Nested\n",
+                "${BIN}: ## Braces\n",
+                "$(UNKNOWN):\n",
+                "run: BIN = other\n",
+            ),
+        )]);
+
+        let parser = parse(&tmp);
+        assert_eq!(parser.variables["BIN"], "app");
+        assert_eq!(parser.variables["MODE"], "release");
+        assert_eq!(parser.variables["TARGETS"], "build test");
+        assert_eq!(parser.variables["NESTED"], "apprelease");
+        assert!(!parser.variables.contains_key("SHELLOUT"));
+
+        assert_eq!(
+            target_names(&parser),
+            vec!["build", "test", "apprelease", "app"]
+        );
+        assert_eq!(target(&parser, "app").desc.as_deref(), Some("Braces"));
+    }
+
+    #[test]
+    fn submake_prefixes() {
+        let tmp = fixture(&[
+            (
+                "Makefile",
+                concat!(
+                    "SERVICES := services\n",
+                    "all:\n",
+                    "\t$(MAKE) -C $(SERVICES)/api build\n",
+                    "\tcd x && make --directory=docs html\n",
+                    "\t@make -C ./tools/\n",
+                    "\t$(MAKE) -C ../outside\n",
+                    "\tfor d in a b; do $(MAKE) -C $$d; done\n",
+                    "\t$(MAKE) -C .\n",
+                ),
+            ),
+            ("services/api/Makefile", "build:\ntest: ## Run the tests\n"),
+            ("docs/makefile", "html:\n\t$(MAKE) -C ..\n"),
+            ("tools/GNUmakefile", "lint:\n"),
+            ("outside/Makefile", "escaped:\n"),
+        ]);
+
+        let parser = parse(&tmp);
+        assert_eq!(
+            parser.subdirs,
+            vec!["services/api", "docs", "./tools", "../outside", "."]
+        );
+
+        assert_eq!(
+            command_names(&tmp),
+            vec![
+                "all",
+                "docs html",
+                "services api build",
+                "services api test",
+                "tools lint",
+            ]
+        );
+    }
+
+    #[test]
+    fn logical_lines_join_continuations() {
+        assert_eq!(
+            logical_lines("a: b \\\n   c\n\techo \\\n\t  done\nd:\n"),
+            vec![
+                (1, "a: b  c".to_string()),
+                (3, "\techo  done".to_string()),
+                (5, "d:".to_string()),
+            ]
+        );
+    }
+}
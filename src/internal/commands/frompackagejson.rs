@@ -0,0 +1,465 @@
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::fs;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::exit;
+use std::process::Command as ProcessCommand;
+
+use globset::GlobBuilder;
+use walkdir::WalkDir;
+
+use crate::internal::commands::utils::abs_or_rel_path;
+use crate::internal::commands::utils::glob_base;
+use crate::internal::commands::utils::split_name;
+use crate::internal::config::config;
+use crate::internal::config::CommandSyntax;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+use crate::omni_error;
+
+/// Scripts run by the package managers as part of their own lifecycle,
+/// which are not meant to be called directly
+const LIFECYCLE_SCRIPTS: [&str; 11] = [
+    "preinstall",
+    "install",
+    "postinstall",
+    "prepublish",
+    "preprepare",
+    "prepare",
+    "postprepare",
+    "prepublishOnly",
+    "prepack",
+    "postpack",
+    "dependencies",
+];
+
+#[derive(Debug, Clone)]
+pub struct PackageJsonCommand {
+    name: Vec<String>,
+    desc: Option<String>,
+    script: String,
+    package_manager: String,
+    source: String,
+}
+
+impl PackageJsonCommand {
+    pub fn all_from_path(path: &str) -> Vec<Self> {
+        // Canonicalize the path
+        let abs_path = match fs::canonicalize(path) {
+            Ok(abs_path) => abs_path,
+            Err(_) => return vec![],
+        };
+
+        let mut path = abs_path.as_path();
+
+        // Get the git environment
+        let wd = workdir(path.to_str().unwrap());
+        let root = wd.root().map(PathBuf::from);
+
+        let mut seen = HashSet::new();
+        let mut commands = vec![];
+        while let Some(parent) = path.parent() {
+            let package_json = path.join("package.json");
+            if package_json.is_file() && seen.insert(package_json.clone()) {
+                commands.extend(Self::all_from_file(&package_json, None, root.as_deref()));
+            }
+
+            if wd.in_workdir() && wd.root().unwrap() == path.to_str().unwrap() {
+                break;
+            }
+
+            path = parent;
+        }
+
+        // The scripts of the other packages of the workspace are available
+        // prefixed by the name of their package
+        if let Some(root) = root
+            .as_ref()
+            .filter(|_| config(".").package_json_commands.workspaces)
+        {
+            for package_json in workspace_packages(root) {
+                if !seen.insert(package_json.clone()) {
+                    continue;
+                }
+
+                let package = read_package_json(&package_json)
+                    .and_then(|content| content.get("name")?.as_str().map(package_short_name))
+                    .or_else(|| {
+                        package_json
+                            .parent()?
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                    });
+
+                if let Some(package) = package {
+                    commands.extend(Self::all_from_file(
+                        &package_json,
+                        Some(package),
+                        Some(root),
+                    ));
+                }
+            }
+        }
+
+        commands
+    }
+
+    fn all_from_file(filepath: &Path, package: Option<String>, root: Option<&Path>) -> Vec<Self> {
+        let content = match read_package_json(filepath) {
+            Some(content) => content,
+            None => return vec![],
+        };
+
+        let scripts = match content
+            .get("scripts")
+            .and_then(|scripts| scripts.as_object())
+        {
+            Some(scripts) => scripts,
+            None => return vec![],
+        };
+
+        let scripts_info = content
+            .get("scripts-info")
+            .and_then(|scripts_info| scripts_info.as_object());
+
+        let package_manager = detect_package_manager(filepath, &content, root);
+
+        scripts
+            .keys()
+            .filter(|script| !is_hook_script(script, scripts))
+            .map(|script| {
+                let desc = scripts_info
+                    .and_then(|scripts_info| scripts_info.get(script))
+                    .and_then(|desc| desc.as_str())
+                    .map(|desc| desc.to_string());
+
+                PackageJsonCommand::new(
+                    script.to_string(),
+                    package.clone(),
+                    desc,
+                    package_manager.clone(),
+                    filepath.to_string_lossy().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn new(
+        script: String,
+        package: Option<String>,
+        desc: Option<String>,
+        package_manager: String,
+        source: String,
+    ) -> Self {
+        let mut name = vec![script.clone()];
+        if config(".").package_json_commands.split_on_colon {
+            name = name.into_iter().flat_map(|n| split_name(&n, ":")).collect();
+        }
+        if config(".").package_json_commands.split_on_dash {
+            name = name.into_iter().flat_map(|n| split_name(&n, "-")).collect();
+        }
+        if let Some(package) = package {
+            name.insert(0, package);
+        }
+
+        PackageJsonCommand {
+            name,
+            desc,
+            script,
+            package_manager,
+            source,
+        }
+    }
+
+    pub fn name(&self) -> Vec<String> {
+        self.name.clone()
+    }
+
+    pub fn aliases(&self) -> Vec<Vec<String>> {
+        vec![]
+    }
+
+    pub fn source(&self) -> String {
+        self.source.clone()
+    }
+
+    pub fn help(&self) -> Option<String> {
+        self.desc.clone()
+    }
+
+    pub fn syntax(&self) -> Option<CommandSyntax> {
+        None
+    }
+
+    pub fn category(&self) -> Option<Vec<String>> {
+        Some(vec![abs_or_rel_path(&self.source)])
+    }
+
+    pub fn exec(&self, argv: Vec<String>) {
+        // Get the current directory so we can store it in a variable
+        let current_dir = std::env::current_dir().expect("Failed to get current directory");
+        std::env::set_var("OMNI_CWD", current_dir.display().to_string());
+
+        let package_dir = Path::new(&self.source).parent().unwrap();
+        if std::env::set_current_dir(package_dir).is_err() {
+            println!("Failed to change directory to {}", package_dir.display());
+        }
+
+        let mut command = ProcessCommand::new(&self.package_manager);
+        command.arg("run").arg(&self.script);
+        if !argv.is_empty() && self.package_manager == "npm" {
+            // npm would otherwise consider the arguments as its own
+            command.arg("--");
+        }
+        let err = command.args(argv).exec();
+
+        omni_error!(format!(
+            "failed to run {}: {}",
+            self.package_manager.light_blue(),
+            err
+        ));
+        exit(1);
+    }
+}
+
+fn read_package_json(path: &Path) -> Option<serde_json::Value> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// The name of a package without its scope, e.g. `web` for `@acme/web`
+fn package_short_name(name: &str) -> String {
+    name.rsplit('/').next().unwrap_or(name).to_string()
+}
+
+/// Whether the script is run by the package manager as a hook, either as
+/// part of its lifecycle, or before or after another script
+fn is_hook_script(script: &str, scripts: &serde_json::Map<String, serde_json::Value>) -> bool {
+    if LIFECYCLE_SCRIPTS.contains(&script) {
+        return true;
+    }
+
+    ["pre", "post"].iter().any(|prefix| {
+        script
+            .strip_prefix(prefix)
+            .is_some_and(|hooked| scripts.contains_key(hooked))
+    })
+}
+
+/// Detects the package manager from the `packageManager` field of the
+/// package, or otherwise from the lock file found in the directory of the
+/// package or its parents, up to the root of the work directory
+fn detect_package_manager(
+    package_json: &Path,
+    content: &serde_json::Value,
+    root: Option<&Path>,
+) -> String {
+    let from_field = |content: &serde_json::Value| {
+        content
+            .get("packageManager")
+            .and_then(|package_manager| package_manager.as_str())
+            .and_then(|package_manager| package_manager.split('@').next())
+            .filter(|package_manager| !package_manager.is_empty())
+            .map(|package_manager| package_manager.to_string())
+    };
+
+    if let Some(package_manager) = from_field(content) {
+        return package_manager;
+    }
+
+    let lock_files = [
+        ("pnpm-lock.yaml", "pnpm"),
+        ("yarn.lock", "yarn"),
+        ("bun.lockb", "bun"),
+        ("bun.lock", "bun"),
+        ("package-lock.json", "npm"),
+    ];
+
+    let mut dir = package_json.parent();
+    while let Some(path) = dir {
+        for (lock_file, package_manager) in lock_files.iter() {
+            if path.join(lock_file).is_file() {
+                return package_manager.to_string();
+            }
+        }
+
+        // The root of a workspace can also define the package manager
+        if path != package_json.parent().unwrap() {
+            if let Some(package_manager) =
+                read_package_json(&path.join("package.json")).and_then(|c| from_field(&c))
+            {
+                return package_manager;
+            }
+        }
+
+        if root.is_none_or(|root| path == root) {
+            break;
+        }
+        dir = path.parent();
+    }
+
+    "npm".to_string()
+}
+
+/// Returns the `package.json` files of the packages of the workspace defined
+/// at the root of the work directory, either through the `workspaces` field
+/// of its `package.json`, or through a `pnpm-workspace.yaml` file
+fn workspace_packages(root: &Path) -> Vec<PathBuf> {
+    let mut patterns = vec![];
+
+    if let Some(content) = read_package_json(&root.join("package.json")) {
+        let workspaces = content.get("workspaces").and_then(|workspaces| {
+            workspaces
+                .as_array()
+                .or_else(|| workspaces.get("packages")?.as_array())
+        });
+        if let Some(workspaces) = workspaces {
+            patterns.extend(
+                workspaces
+                    .iter()
+                    .filter_map(|pattern| pattern.as_str())
+                    .map(|pattern| pattern.to_string()),
+            );
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        if let Ok(content) = serde_yaml::from_str::<serde_yaml::Value>(&content) {
+            if let Some(packages) = content.get("packages").and_then(|p| p.as_sequence()) {
+                patterns.extend(
+                    packages
+                        .iter()
+                        .filter_map(|pattern| pattern.as_str())
+                        .map(|pattern| pattern.to_string()),
+                );
+            }
+        }
+    }
+
+    let (excludes, includes): (Vec<_>, Vec<_>) = patterns
+        .iter()
+        .map(|pattern| pattern.trim_start_matches("./").trim_end_matches('/'))
+        .partition(|pattern| pattern.starts_with('!'));
+
+    let build_matcher = |pattern: &str| {
+        GlobBuilder::new(pattern.trim_start_matches('!'))
+            .literal_separator(true)
+            .build()
+            .ok()
+            .map(|glob| glob.compile_matcher())
+    };
+    let excludes = excludes
+        .into_iter()
+        .filter_map(build_matcher)
+        .collect::<Vec<_>>();
+
+    // Each pattern is only expanded from its literal base directory, and no
+    // deeper than its number of components unless it uses `**`
+    let mut packages = BTreeSet::new();
+    for pattern in includes {
+        let matcher = match build_matcher(pattern) {
+            Some(matcher) => matcher,
+            None => continue,
+        };
+
+        let base = glob_base(pattern);
+        let mut walker = WalkDir::new(root.join(&base));
+        if !pattern.contains("**") {
+            let depth = Path::new(pattern).components().count() - base.components().count();
+            walker = walker.max_depth(depth);
+        }
+
+        for entry in walker
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.file_type().is_dir()
+                    && !matches!(
+                        entry.file_name().to_str(),
+                        Some("node_modules") | Some(".git")
+                    )
+            })
+            .flatten()
+        {
+            let rel_path = match entry.path().strip_prefix(root) {
+                Ok(rel_path) if !rel_path.as_os_str().is_empty() => rel_path,
+                _ => continue,
+            };
+
+            if matcher.is_match(rel_path)
+                && !excludes.iter().any(|matcher| matcher.is_match(rel_path))
+            {
+                let package_json = entry.path().join("package.json");
+                if package_json.is_file() {
+                    packages.insert(package_json);
+                }
+            }
+        }
+    }
+
+    packages.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_package(root: &Path, dir: &str) {
+        let dir = root.join(dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("package.json"), "{}").unwrap();
+    }
+
+    #[test]
+    fn workspace_packages_expands_patterns() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::write(
+            root.join("package.json"),
+            r#"{"workspaces": ["packages/*", "!packages/skip", "apps/**"]}"#,
+        )
+        .unwrap();
+        write_package(root, "packages/web");
+        write_package(root, "packages/skip");
+        write_package(root, "packages/web/nested");
+        write_package(root, "packages/web/node_modules/dep");
+        write_package(root, "apps/mobile/ios");
+        write_package(root, "other/tool");
+
+        let packages = workspace_packages(root)
+            .into_iter()
+            .map(|path| {
+                path.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            packages,
+            vec![
+                "apps/mobile/ios/package.json".to_string(),
+                "packages/web/package.json".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn workspace_packages_from_pnpm_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::write(
+            root.join("pnpm-workspace.yaml"),
+            "packages:\n  - 'libs/*'\n",
+        )
+        .unwrap();
+        write_package(root, "libs/core");
+
+        assert_eq!(
+            workspace_packages(root),
+            vec![root.join("libs/core/package.json")]
+        );
+    }
+}
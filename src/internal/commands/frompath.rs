@@ -1,22 +1,32 @@
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fs;
 use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
+use std::os::unix::fs::MetadataExt;
 use std::os::unix::fs::PermissionsExt;
 use std::os::unix::process::CommandExt;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command as ProcessCommand;
 
 use once_cell::sync::OnceCell;
 use serde::Deserialize;
 use serde::Serialize;
-use walkdir::WalkDir;
-
+use time::OffsetDateTime;
+
+use crate::internal::cache::command_index::index_mtime;
+use crate::internal::cache::command_index::CommandIndexDetails;
+use crate::internal::cache::command_index::CommandIndexDirectory;
+use crate::internal::cache::command_index::CommandIndexEntry;
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::CommandIndexCache;
 use crate::internal::commands::path::omnipath;
 use crate::internal::config::CommandSyntax;
 use crate::internal::config::SyntaxOptArg;
+use crate::internal::config::SyntaxOptArgType;
 
 #[derive(Debug, Clone)]
 pub struct PathCommand {
@@ -31,26 +41,20 @@ impl PathCommand {
         let mut all_commands: Vec<PathCommand> = Vec::new();
         let mut known_sources: HashMap<String, usize> = HashMap::new();
 
+        let mut walker = IndexedWalker::new();
+
         for path in &omnipath() {
-            // Aggregate all the files first, since WalkDir does not sort the list
+            // Aggregate all the files first, so they can be sorted
             let mut files_to_process = Vec::new();
-            for entry in WalkDir::new(path).follow_links(true).into_iter().flatten() {
-                let filetype = entry.file_type();
-                let filepath = entry.path();
-
-                if !filetype.is_file() || !Self::is_executable(filepath) {
-                    continue;
-                }
-
-                files_to_process.push(filepath.to_path_buf());
-            }
+            walker.walk(Path::new(path), &mut files_to_process);
 
             // Sort the files by path
-            files_to_process.sort();
+            files_to_process.sort_by(|a, b| a.path.cmp(&b.path));
 
             // Process the files
-            for filepath in files_to_process {
-                let mut partitions = filepath
+            for file in files_to_process {
+                let mut partitions = file
+                    .path
                     .strip_prefix(format!("{}/", path))
                     .unwrap()
                     .to_str()
@@ -77,29 +81,26 @@ impl PathCommand {
 
                 let new_command = PathCommand::new(
                     partitions.iter().map(|s| s.to_string()).collect(),
-                    filepath.to_str().unwrap().to_string(),
+                    file.path.to_str().unwrap().to_string(),
                 );
+                let _ = new_command.file_details.set(file.details);
 
                 // Check if the source is already known
-                if let Some(idx) = known_sources.get_mut(&new_command.real_source()) {
+                if let Some(idx) = known_sources.get_mut(&file.real_path) {
                     // Add this command's name to the command's aliases
                     let cmd: &mut _ = &mut all_commands[*idx];
                     cmd.add_alias(new_command.name(), Some(new_command.source()));
                 } else {
                     // Add the new command
                     all_commands.push(new_command.clone());
-                    known_sources.insert(new_command.real_source(), all_commands.len() - 1);
+                    known_sources.insert(file.real_path, all_commands.len() - 1);
                 }
             }
         }
 
-        all_commands
-    }
+        walker.save();
 
-    fn is_executable(path: &std::path::Path) -> bool {
-        fs::metadata(path)
-            .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
-            .unwrap_or(false)
+        all_commands
     }
 
     pub fn new(name: Vec<String>, source: String) -> Self {
@@ -136,14 +137,6 @@ impl PathCommand {
         self.source.clone()
     }
 
-    fn real_source(&self) -> String {
-        if let Ok(canon) = std::fs::canonicalize(&self.source) {
-            canon.to_str().unwrap().to_string()
-        } else {
-            self.source.clone()
-        }
-    }
-
     pub fn help(&self) -> Option<String> {
         self.file_details().and_then(|details| details.help.clone())
     }
@@ -198,6 +191,188 @@ impl PathCommand {
     }
 }
 
+struct IndexedFile {
+    path: PathBuf,
+    real_path: String,
+    details: Option<PathCommandFileDetails>,
+}
+
+/// Walks the directories of the omnipath with the help of the command index:
+/// the entries of a directory are only read again if its modification time
+/// changed, and the details of a command are only read again if the
+/// modification time of its file or of its metadata file changed
+struct IndexedWalker {
+    index: CommandIndexCache,
+    directories: BTreeMap<String, CommandIndexDirectory>,
+    commands: BTreeMap<String, CommandIndexEntry>,
+    visited: HashSet<(u64, u64)>,
+    updated: bool,
+}
+
+impl IndexedWalker {
+    fn new() -> Self {
+        Self::with_index(CommandIndexCache::get())
+    }
+
+    fn with_index(index: CommandIndexCache) -> Self {
+        Self {
+            index,
+            directories: BTreeMap::new(),
+            commands: BTreeMap::new(),
+            visited: HashSet::new(),
+            updated: false,
+        }
+    }
+
+    fn walk(&mut self, root: &Path, files: &mut Vec<IndexedFile>) {
+        // Symbolic links are followed, so we need to protect against loops,
+        // but the same directory can be part of different omnipath entries
+        self.visited.clear();
+        self.walk_dir(root, files);
+    }
+
+    fn walk_dir(&mut self, dir: &Path, files: &mut Vec<IndexedFile>) {
+        let metadata = match fs::metadata(dir) {
+            Ok(metadata) if metadata.is_dir() => metadata,
+            _ => return,
+        };
+        if !self.visited.insert((metadata.dev(), metadata.ino())) {
+            return;
+        }
+
+        let dir_path = dir.to_string_lossy().to_string();
+        let mtime = index_mtime(&metadata);
+        let entries = match self.index.directory(&dir_path, mtime) {
+            Some(entries) => entries.clone(),
+            None => {
+                self.updated = true;
+                Self::read_dir(dir, mtime)
+            }
+        };
+
+        // Changing the permissions or the content of a file does not change
+        // the modification time of its directory, so each file is checked
+        for filename in entries.files.iter() {
+            let filepath = dir.join(filename);
+            let file_metadata = match fs::metadata(&filepath) {
+                Ok(file_metadata) if file_metadata.is_file() => file_metadata,
+                _ => continue,
+            };
+            if file_metadata.permissions().mode() & 0o111 == 0 {
+                continue;
+            }
+
+            let source = filepath.to_string_lossy().to_string();
+            let file_mtime = index_mtime(&file_metadata);
+            let metadata_mtime = PathCommandFileDetails::metadata_files(&source)
+                .iter()
+                .find_map(|metadata_file| {
+                    // Metadata files are usually next to the command, in
+                    // which case the listing of the directory tells us if
+                    // they exist without having to check each of them
+                    let metadata_file = Path::new(metadata_file);
+                    if metadata_file.parent() == Some(dir) {
+                        let name = metadata_file.file_name()?.to_string_lossy();
+                        if !entries.files.iter().any(|file| *file == name) {
+                            return None;
+                        }
+                    }
+                    fs::metadata(metadata_file).ok()
+                })
+                .map(|metadata| index_mtime(&metadata));
+
+            let (real_path, details) = match self.index.command(&source, file_mtime, metadata_mtime)
+            {
+                Some(entry) => (
+                    entry.real_path.clone(),
+                    entry.details.clone().map(PathCommandFileDetails::from),
+                ),
+                None => {
+                    self.updated = true;
+                    let real_path = match fs::canonicalize(&filepath) {
+                        Ok(real_path) if real_path != filepath => {
+                            real_path.to_string_lossy().to_string()
+                        }
+                        _ => String::new(),
+                    };
+                    (real_path, PathCommandFileDetails::from_file(&source))
+                }
+            };
+
+            self.commands.insert(
+                source.clone(),
+                CommandIndexEntry {
+                    mtime: file_mtime,
+                    metadata_mtime,
+                    real_path: real_path.clone(),
+                    details: details.clone().map(CommandIndexDetails::from),
+                },
+            );
+            files.push(IndexedFile {
+                real_path: if real_path.is_empty() {
+                    source
+                } else {
+                    real_path
+                },
+                path: filepath,
+                details,
+            });
+        }
+
+        for subdir in entries.directories.iter() {
+            self.walk_dir(&dir.join(subdir), files);
+        }
+
+        self.directories.insert(dir_path, entries);
+    }
+
+    fn read_dir(dir: &Path, mtime: u64) -> CommandIndexDirectory {
+        let mut entries = CommandIndexDirectory {
+            mtime,
+            files: vec![],
+            directories: vec![],
+        };
+
+        if let Ok(dir_entries) = fs::read_dir(dir) {
+            for entry in dir_entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                match fs::metadata(entry.path()) {
+                    Ok(metadata) if metadata.is_dir() => entries.directories.push(name),
+                    Ok(metadata) if metadata.is_file() => entries.files.push(name),
+                    _ => {}
+                }
+            }
+        }
+
+        entries.files.sort();
+        entries.directories.sort();
+        entries
+    }
+
+    /// Writes the index if anything was read again since it was loaded
+    fn save(self) {
+        if !self.updated {
+            return;
+        }
+
+        let _ = CommandIndexCache::exclusive(|index| {
+            self.merge_into(index);
+            true
+        });
+    }
+
+    /// Adds the entries of this walk to the index; the omnipath can differ
+    /// between repositories, so the entries of the directories that were not
+    /// walked are kept, unless their path does not exist anymore
+    fn merge_into(self, index: &mut CommandIndexCache) {
+        index.directories.extend(self.directories);
+        index.commands.extend(self.commands);
+        index.directories.retain(|path, _| Path::new(path).is_dir());
+        index.commands.retain(|path, _| Path::new(path).is_file());
+        index.updated_at = OffsetDateTime::now_utc();
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PathCommandFileDetails {
     #[serde(default, deserialize_with = "deserialize_category")]
@@ -275,15 +450,18 @@ impl PathCommandFileDetails {
         None
     }
 
-    pub fn from_metadata_file(path: &str) -> Option<Self> {
-        // The metadata file for `file.ext` can be either
-        // `file.ext.metadata.yaml` or `file.metadata.yaml`
+    /// The metadata file for `file.ext` can be either
+    /// `file.ext.metadata.yaml` or `file.metadata.yaml`
+    fn metadata_files(path: &str) -> Vec<String> {
         let mut metadata_files = vec![format!("{}.metadata.yaml", path)];
         if let Some(dotpos) = path.rfind('.') {
             metadata_files.push(format!("{}.metadata.yaml", &path[0..dotpos]));
         }
+        metadata_files
+    }
 
-        for metadata_file in metadata_files {
+    pub fn from_metadata_file(path: &str) -> Option<Self> {
+        for metadata_file in Self::metadata_files(path) {
             let path = Path::new(&metadata_file);
 
             // Check if the metadata file exists
@@ -368,23 +546,36 @@ impl PathCommandFileDetails {
                 }
 
                 let param_name = param[0].clone();
-                let param_desc = param[1].clone();
-
-                if let Some(cur_param_desc) = parameters
-                    .iter_mut()
-                    .find(|p| p.name == param_name && p.required == param_required)
+                let (param_spec, param_desc) = match split_param_spec(&param[1]) {
+                    Some((spec, desc)) => (Some(spec), desc),
+                    None => (None, param[1].clone()),
+                };
+
+                let param_index = match parameters
+                    .iter()
+                    .position(|p| p.name == param_name && p.required == param_required)
                 {
-                    cur_param_desc.desc = Some(format!(
-                        "{}\n{}",
-                        cur_param_desc.desc.clone().unwrap_or(String::new()),
-                        param_desc
-                    ));
-                } else {
-                    parameters.push(SyntaxOptArg::new(
-                        param_name,
-                        Some(param_desc),
-                        param_required,
-                    ))
+                    Some(idx) => {
+                        let cur_param = &mut parameters[idx];
+                        cur_param.desc = Some(format!(
+                            "{}\n{}",
+                            cur_param.desc.clone().unwrap_or_default(),
+                            param_desc
+                        ));
+                        idx
+                    }
+                    None => {
+                        parameters.push(SyntaxOptArg::new(
+                            param_name,
+                            Some(param_desc),
+                            param_required,
+                        ));
+                        parameters.len() - 1
+                    }
+                };
+
+                if let Some(param_spec) = param_spec {
+                    apply_param_spec(&mut parameters[param_index], &param_spec);
                 }
             }
         }
@@ -413,9 +604,269 @@ impl PathCommandFileDetails {
     }
 }
 
+impl From<CommandIndexDetails> for PathCommandFileDetails {
+    fn from(details: CommandIndexDetails) -> Self {
+        Self {
+            category: details.category,
+            help: details.help,
+            autocompletion: details.autocompletion,
+            syntax: details.syntax,
+        }
+    }
+}
+
+impl From<PathCommandFileDetails> for CommandIndexDetails {
+    fn from(details: PathCommandFileDetails) -> Self {
+        Self {
+            category: details.category,
+            help: details.help,
+            autocompletion: details.autocompletion,
+            syntax: details.syntax,
+        }
+    }
+}
+
+const PARAM_SPEC_KEYS: [&str; 5] = ["type", "default", "values", "multiple", "complete"];
+
+/// Splits the typed specification of a parameter from its description, as
+/// in `# opt: --count: type=int default=3: Number of retries`; values can be
+/// quoted to contain spaces or colons, as in `complete="git branch"`. This
+/// returns `None` if the value does not start with a specification.
+fn split_param_spec(value: &str) -> Option<(Vec<(String, String)>, String)> {
+    let mut spec = vec![];
+    let mut rest = value.trim_start();
+
+    loop {
+        if let Some(desc) = rest.strip_prefix(':') {
+            if spec.is_empty() {
+                return None;
+            }
+            return Some((spec, desc.trim().to_string()));
+        }
+
+        let (key, after_key) = rest.split_once('=')?;
+        if !PARAM_SPEC_KEYS.contains(&key) {
+            return None;
+        }
+
+        let (value, after_value) = match after_key.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let end = after_key[1..].find(quote)? + 1;
+                (&after_key[1..end], &after_key[end + 1..])
+            }
+            _ => {
+                let end = after_key
+                    .find(|c: char| c == ':' || c.is_whitespace())
+                    .unwrap_or(after_key.len());
+                (&after_key[..end], &after_key[end..])
+            }
+        };
+
+        // Each pair needs to be followed by another one or by the description
+        if !after_value.is_empty() && !after_value.starts_with([':', ' ', '\t']) {
+            return None;
+        }
+
+        spec.push((key.to_string(), value.to_string()));
+        rest = after_value.trim_start();
+    }
+}
+
+fn apply_param_spec(param: &mut SyntaxOptArg, spec: &[(String, String)]) {
+    let values = spec
+        .iter()
+        .find(|(key, _)| key == "values")
+        .map(|(_, values)| values.split(',').map(|v| v.to_string()).collect());
+
+    for (key, value) in spec {
+        match key.as_str() {
+            "type" => param.arg_type = SyntaxOptArgType::from_str(value, values.clone()),
+            "default" => param.default = Some(value.clone()),
+            "multiple" => param.multiple = value == "true",
+            "complete" => param.complete = Some(value.clone()),
+            _ => {}
+        }
+    }
+
+    if param.arg_type.is_none() {
+        param.arg_type = values.map(SyntaxOptArgType::Enum);
+    }
+}
+
 fn handle_color_codes(string: String) -> String {
     string
         .replace("\\033[", "\x1B[")
         .replace("\\e[", "\x1B[")
         .replace("\\x1B[", "\x1B[")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_command(path: &Path, help: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, format!("#!/bin/sh\n# help: {}\necho ok\n", help)).unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    fn walk(index: &CommandIndexCache, root: &Path) -> (IndexedWalker, Vec<(String, String)>) {
+        let mut walker = IndexedWalker::with_index(index.clone());
+        let mut files = vec![];
+        walker.walk(root, &mut files);
+
+        let mut helps = files
+            .into_iter()
+            .map(|file| {
+                let help = file.details.and_then(|details| details.help);
+                (
+                    file.path.strip_prefix(root).unwrap().display().to_string(),
+                    help.unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        helps.sort();
+        (walker, helps)
+    }
+
+    #[test]
+    fn indexed_walker_reuses_index() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("cmds");
+        write_command(&root.join("build"), "Build");
+        write_command(&root.join("tools.d/lint"), "Lint");
+
+        let mut index = CommandIndexCache::new_empty();
+        let (walker, helps) = walk(&index, &root);
+        assert!(walker.updated);
+        assert_eq!(
+            helps,
+            vec![
+                ("build".to_string(), "Build".to_string()),
+                ("tools.d/lint".to_string(), "Lint".to_string()),
+            ]
+        );
+        walker.merge_into(&mut index);
+
+        // The details come from the index as long as the files do not change
+        let build = root.join("build").to_string_lossy().to_string();
+        index
+            .commands
+            .get_mut(&build)
+            .unwrap()
+            .details
+            .as_mut()
+            .unwrap()
+            .help = Some("Indexed".to_string());
+        let (walker, helps) = walk(&index, &root);
+        assert!(!walker.updated);
+        assert_eq!(helps[0], ("build".to_string(), "Indexed".to_string()));
+
+        // Changing the modification time of a file reads it again
+        write_command(&root.join("build"), "Build again");
+        let file = File::options().write(true).open(&build).unwrap();
+        file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(10))
+            .unwrap();
+        let (walker, helps) = walk(&index, &root);
+        assert!(walker.updated);
+        assert_eq!(helps[0], ("build".to_string(), "Build again".to_string()));
+        assert_eq!(helps[1], ("tools.d/lint".to_string(), "Lint".to_string()));
+    }
+
+    #[test]
+    fn indexed_walker_keeps_other_directories() {
+        let tmp = tempfile::tempdir().unwrap();
+        let first = tmp.path().join("first");
+        let second = tmp.path().join("second");
+        write_command(&first.join("one"), "One");
+        write_command(&second.join("two"), "Two");
+        write_command(&second.join("gone"), "Gone");
+
+        let mut index = CommandIndexCache::new_empty();
+        let (walker, _) = walk(&index, &first);
+        walker.merge_into(&mut index);
+        let (walker, _) = walk(&index, &second);
+        walker.merge_into(&mut index);
+
+        // Walking the first directory again does not drop the entries of
+        // the second one, but the entries of removed files are dropped
+        fs::remove_file(second.join("gone")).unwrap();
+        write_command(&first.join("three"), "Three");
+        let (walker, helps) = walk(&index, &first);
+        assert!(walker.updated);
+        assert_eq!(helps.len(), 2);
+        walker.merge_into(&mut index);
+
+        let path = |path: &Path| path.to_string_lossy().to_string();
+        assert!(index.directories.contains_key(&path(&first)));
+        assert!(index.directories.contains_key(&path(&second)));
+        assert!(index.commands.contains_key(&path(&first.join("three"))));
+        assert!(index.commands.contains_key(&path(&second.join("two"))));
+        assert!(!index.commands.contains_key(&path(&second.join("gone"))));
+
+        // The second directory changed since it was indexed
+        let (walker, helps) = walk(&index, &second);
+        assert!(walker.updated);
+        assert_eq!(helps, vec![("two".to_string(), "Two".to_string())]);
+        walker.merge_into(&mut index);
+
+        let (walker, _) = walk(&index, &second);
+        assert!(!walker.updated);
+        let (walker, _) = walk(&index, &first);
+        assert!(!walker.updated);
+    }
+
+    fn spec(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn split_param_spec_pairs() {
+        assert_eq!(
+            split_param_spec(" type=int default=3: Number of retries"),
+            Some((
+                spec(&[("type", "int"), ("default", "3")]),
+                "Number of retries".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn split_param_spec_quoted_complete() {
+        assert_eq!(
+            split_param_spec(
+                r#" complete="git branch --format='%(refname:short)'" multiple=true: Branches"#
+            ),
+            Some((
+                spec(&[
+                    ("complete", "git branch --format='%(refname:short)'"),
+                    ("multiple", "true"),
+                ]),
+                "Branches".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn split_param_spec_without_spec() {
+        assert_eq!(split_param_spec(" The description: with a colon"), None);
+        assert_eq!(split_param_spec(" unknown=key: Description"), None);
+        assert_eq!(
+            split_param_spec(r#" complete="unterminated: Description"#),
+            None
+        );
+        assert_eq!(split_param_spec(" type=int"), None);
+    }
+
+    #[test]
+    fn apply_param_spec_complete() {
+        let mut param = SyntaxOptArg::new("--branch".to_string(), None, false);
+        apply_param_spec(&mut param, &spec(&[("complete", "git branch")]));
+
+        assert_eq!(param.complete, Some("git branch".to_string()));
+        assert_eq!(param.arg_type, None);
+    }
+}
@@ -0,0 +1,313 @@
+use std::fs;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::exit;
+use std::process::Command as ProcessCommand;
+
+use crate::internal::commands::utils::abs_or_rel_path;
+use crate::internal::commands::utils::split_name;
+use crate::internal::config::config;
+use crate::internal::config::CommandSyntax;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+use crate::omni_error;
+
+/// The file names looked up by `task` in a directory, by order of priority
+const TASKFILE_NAMES: [&str; 8] = [
+    "Taskfile.yml",
+    "taskfile.yml",
+    "Taskfile.yaml",
+    "taskfile.yaml",
+    "Taskfile.dist.yml",
+    "taskfile.dist.yml",
+    "Taskfile.dist.yaml",
+    "taskfile.dist.yaml",
+];
+
+/// Includes can themselves include other Taskfiles; this protects against
+/// Taskfiles including each other
+const MAX_INCLUDE_DEPTH: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct TaskfileCommand {
+    name: Vec<String>,
+    aliases: Vec<Vec<String>>,
+    desc: Option<String>,
+    task: String,
+    taskfile: String,
+    source: String,
+}
+
+impl TaskfileCommand {
+    pub fn all_from_path(path: &str) -> Vec<Self> {
+        // Canonicalize the path
+        let abs_path = match fs::canonicalize(path) {
+            Ok(abs_path) => abs_path,
+            Err(_) => return vec![],
+        };
+
+        let mut path = abs_path.as_path();
+
+        // Get the git environment
+        let wd = workdir(path.to_str().unwrap());
+
+        let mut commands = vec![];
+        while let Some(parent) = path.parent() {
+            if let Some(taskfile) = find_taskfile(path) {
+                commands.extend(Self::all_from_file(&taskfile));
+            }
+
+            if wd.in_workdir() && wd.root().unwrap() == path.to_str().unwrap() {
+                break;
+            }
+
+            path = parent;
+        }
+
+        commands
+    }
+
+    pub fn all_from_file(taskfile: &Path) -> Vec<Self> {
+        let mut commands = vec![];
+        Self::collect_tasks(taskfile, taskfile, &[], false, 0, &mut commands);
+        commands
+    }
+
+    /// Collects the tasks of a Taskfile and of its includes; `namespace` is
+    /// the path of the include the file was loaded from, and `internal`
+    /// whether that include was marked as internal
+    fn collect_tasks(
+        taskfile: &Path,
+        filepath: &Path,
+        namespace: &[String],
+        internal: bool,
+        depth: usize,
+        commands: &mut Vec<Self>,
+    ) {
+        if depth > MAX_INCLUDE_DEPTH {
+            return;
+        }
+
+        let content: serde_yaml::Value = match fs::read_to_string(filepath)
+            .ok()
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+        {
+            Some(content) => content,
+            None => return,
+        };
+
+        if let Some(tasks) = content.get("tasks").and_then(|tasks| tasks.as_mapping()) {
+            for (name, task) in tasks.iter() {
+                let name = match name.as_str() {
+                    Some(name) => name,
+                    None => continue,
+                };
+
+                // Tasks with wildcards take their arguments from their name,
+                // which cannot be mapped to an omni command
+                if name.contains('*') {
+                    continue;
+                }
+
+                // Internal tasks can only be called from other tasks
+                if internal || get_bool(task, "internal") {
+                    continue;
+                }
+
+                let desc = get_str(task, "desc");
+                let summary = get_str(task, "summary");
+                let help = match (desc, summary) {
+                    (Some(desc), Some(summary)) => Some(format!("{}\n\n{}", desc, summary)),
+                    (desc, summary) => desc.or(summary),
+                };
+
+                let aliases = get_str_list(task, "aliases");
+
+                commands.push(TaskfileCommand::new(
+                    namespace,
+                    name,
+                    aliases,
+                    help,
+                    taskfile.to_string_lossy().to_string(),
+                    filepath.to_string_lossy().to_string(),
+                ));
+            }
+        }
+
+        let includes = match content.get("includes").and_then(|i| i.as_mapping()) {
+            Some(includes) => includes,
+            None => return,
+        };
+
+        let dir = filepath.parent().unwrap_or(Path::new("."));
+        for (include_namespace, include) in includes.iter() {
+            let include_namespace = match include_namespace.as_str() {
+                Some(include_namespace) => include_namespace,
+                None => continue,
+            };
+
+            let include_path = match include.as_str() {
+                Some(include_path) => Some(include_path.to_string()),
+                None => get_str(include, "taskfile"),
+            };
+            let include_path = match include_path {
+                Some(include_path) => dir.join(include_path),
+                None => continue,
+            };
+
+            let include_file = if include_path.is_dir() {
+                match find_taskfile(&include_path) {
+                    Some(include_file) => include_file,
+                    None => continue,
+                }
+            } else if include_path.is_file() {
+                include_path
+            } else {
+                // Optional includes or includes depending on variables
+                // that cannot be resolved here
+                continue;
+            };
+
+            // Flattened includes make their tasks available without namespace
+            let mut include_full_namespace = namespace.to_vec();
+            if !get_bool(include, "flatten") {
+                include_full_namespace.push(include_namespace.to_string());
+            }
+
+            Self::collect_tasks(
+                taskfile,
+                &include_file,
+                &include_full_namespace,
+                internal || get_bool(include, "internal"),
+                depth + 1,
+                commands,
+            );
+        }
+    }
+
+    pub fn new(
+        namespace: &[String],
+        task: &str,
+        aliases: Vec<String>,
+        desc: Option<String>,
+        taskfile: String,
+        source: String,
+    ) -> Self {
+        let full_name = |name: &str| {
+            namespace
+                .iter()
+                .map(|ns| ns.as_str())
+                .chain(std::iter::once(name))
+                .collect::<Vec<_>>()
+                .join(":")
+        };
+
+        TaskfileCommand {
+            name: Self::split_task_name(&full_name(task)),
+            aliases: aliases
+                .iter()
+                .map(|alias| Self::split_task_name(&full_name(alias)))
+                .collect(),
+            desc,
+            task: full_name(task),
+            taskfile,
+            source,
+        }
+    }
+
+    /// Namespaces are separated by colons in task names, and are mapped to
+    /// omni subcommands
+    fn split_task_name(task: &str) -> Vec<String> {
+        let mut name = split_name(task, ":");
+        if config(".").taskfile_commands.split_on_dash {
+            name = name.into_iter().flat_map(|n| split_name(&n, "-")).collect();
+        }
+        name
+    }
+
+    pub fn name(&self) -> Vec<String> {
+        self.name.clone()
+    }
+
+    pub fn aliases(&self) -> Vec<Vec<String>> {
+        self.aliases.clone()
+    }
+
+    pub fn source(&self) -> String {
+        self.source.clone()
+    }
+
+    pub fn help(&self) -> Option<String> {
+        self.desc.clone()
+    }
+
+    pub fn syntax(&self) -> Option<CommandSyntax> {
+        None
+    }
+
+    pub fn category(&self) -> Option<Vec<String>> {
+        Some(vec![abs_or_rel_path(&self.source)])
+    }
+
+    pub fn exec(&self, argv: Vec<String>) {
+        // Get the current directory so we can store it in a variable
+        let current_dir = std::env::current_dir().expect("Failed to get current directory");
+        std::env::set_var("OMNI_CWD", current_dir.display().to_string());
+
+        let taskfile_dir = Path::new(&self.taskfile).parent().unwrap();
+        if std::env::set_current_dir(taskfile_dir).is_err() {
+            println!("Failed to change directory to {}", taskfile_dir.display());
+        }
+
+        let mut command = ProcessCommand::new("task");
+        command
+            .arg("--taskfile")
+            .arg(&self.taskfile)
+            .arg(&self.task);
+        if !argv.is_empty() {
+            // Arguments are made available to the task as `{{.CLI_ARGS}}`
+            command.arg("--").args(argv);
+        }
+        let err = command.exec();
+
+        omni_error!(format!("failed to run {}: {}", "task".light_blue(), err));
+        exit(1);
+    }
+}
+
+fn find_taskfile(dir: &Path) -> Option<PathBuf> {
+    TASKFILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn get_str(value: &serde_yaml::Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|value| value.as_str())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn get_bool(value: &serde_yaml::Value, key: &str) -> bool {
+    value
+        .get(key)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+}
+
+fn get_str_list(value: &serde_yaml::Value, key: &str) -> Vec<String> {
+    value
+        .get(key)
+        .and_then(|value| value.as_sequence())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str())
+                .map(|value| value.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -12,18 +12,27 @@ use crate::internal::commands::builtin::CdCommand;
 use crate::internal::commands::builtin::CloneCommand;
 use crate::internal::commands::builtin::ConfigBootstrapCommand;
 use crate::internal::commands::builtin::ConfigPathSwitchCommand;
+use crate::internal::commands::builtin::EnvCommand;
+use crate::internal::commands::builtin::EnvExportCommand;
+use crate::internal::commands::builtin::ExecCommand;
+use crate::internal::commands::builtin::ForeachCommand;
 use crate::internal::commands::builtin::HelpCommand;
 use crate::internal::commands::builtin::HookCommand;
 use crate::internal::commands::builtin::HookEnvCommand;
 use crate::internal::commands::builtin::HookInitCommand;
+use crate::internal::commands::builtin::HookPromptCommand;
 use crate::internal::commands::builtin::HookUuidCommand;
 use crate::internal::commands::builtin::ScopeCommand;
 use crate::internal::commands::builtin::StatusCommand;
 use crate::internal::commands::builtin::TidyCommand;
 use crate::internal::commands::builtin::UpCommand;
+use crate::internal::commands::builtin::UpExportCommand;
 use crate::internal::commands::fromconfig::ConfigCommand;
+use crate::internal::commands::fromjustfile::JustfileCommand;
 use crate::internal::commands::frommakefile::MakefileCommand;
+use crate::internal::commands::frompackagejson::PackageJsonCommand;
 use crate::internal::commands::frompath::PathCommand;
+use crate::internal::commands::fromtaskfile::TaskfileCommand;
 use crate::internal::config;
 use crate::internal::env::shell_is_interactive;
 use crate::internal::user_interface::colors::StringColor;
@@ -88,15 +97,21 @@ impl CommandLoader {
         commands.push(Command::BuiltinConfigPathSwitch(
             ConfigPathSwitchCommand::new(),
         ));
+        commands.push(Command::BuiltinEnv(EnvCommand::new()));
+        commands.push(Command::BuiltinEnvExport(EnvExportCommand::new()));
+        commands.push(Command::BuiltinExec(ExecCommand::new()));
+        commands.push(Command::BuiltinForeach(ForeachCommand::new()));
         commands.push(Command::BuiltinHelp(HelpCommand::new()));
         commands.push(Command::BuiltinHook(HookCommand::new()));
         commands.push(Command::BuiltinHookEnv(HookEnvCommand::new()));
         commands.push(Command::BuiltinHookInit(HookInitCommand::new()));
+        commands.push(Command::BuiltinHookPrompt(HookPromptCommand::new()));
         commands.push(Command::BuiltinHookUuid(HookUuidCommand::new()));
         commands.push(Command::BuiltinScope(ScopeCommand::new()));
         commands.push(Command::BuiltinStatus(StatusCommand::new()));
         commands.push(Command::BuiltinTidy(TidyCommand::new()));
         commands.push(Command::BuiltinUp(UpCommand::new()));
+        commands.push(Command::BuiltinUpExport(UpExportCommand::new()));
 
         // Add all the builtin to seen commands
         for command in commands.iter() {
@@ -130,6 +145,24 @@ impl CommandLoader {
             add_fn(Command::FromMakefile(command));
         }
 
+        if config(path).justfile_commands.enabled {
+            for command in JustfileCommand::all_from_path(path) {
+                add_fn(Command::FromJustfile(command));
+            }
+        }
+
+        if config(path).package_json_commands.enabled {
+            for command in PackageJsonCommand::all_from_path(path) {
+                add_fn(Command::FromPackageJson(command));
+            }
+        }
+
+        if config(path).taskfile_commands.enabled {
+            for command in TaskfileCommand::all_from_path(path) {
+                add_fn(Command::FromTaskfile(command));
+            }
+        }
+
         Self { commands }
     }
 
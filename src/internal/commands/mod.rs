@@ -1,3 +1,5 @@
+pub(crate) mod arguments;
+
 pub(crate) mod base;
 pub(crate) use base::Command;
 
@@ -6,14 +8,21 @@ pub(crate) use builtin::config_bootstrap;
 pub(crate) use builtin::HelpCommand;
 pub(crate) use builtin::HookEnvCommand;
 pub(crate) use builtin::HookInitCommand;
+pub(crate) use builtin::HookPromptCommand;
 pub(crate) use builtin::HookUuidCommand;
 
 mod fromconfig;
 
+mod fromjustfile;
+
 mod frommakefile;
 
+mod frompackagejson;
+
 mod frompath;
 
+mod fromtaskfile;
+
 pub(crate) mod loader;
 pub(crate) use loader::command_loader;
 
@@ -60,6 +60,20 @@ pub fn abs_path(path: impl AsRef<Path>) -> PathBuf {
     absolute_path
 }
 
+/// Returns the leading components of the glob that do not contain any
+/// wildcard, which is where the files matching it can be found
+pub fn glob_base(glob: &str) -> PathBuf {
+    Path::new(glob)
+        .components()
+        .take_while(|component| {
+            !component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '[', '{'])
+        })
+        .collect()
+}
+
 pub fn omni_cmd(cmd: &str) -> Result<(), io::Error> {
     let cmd_file = omni_cmd_file().expect("shell integration not loaded");
 
@@ -154,3 +168,16 @@ pub fn file_auto_complete(p: String) -> Completions<String> {
 
     files
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_base_stops_at_wildcards() {
+        assert_eq!(glob_base("src/**/*.rs"), PathBuf::from("src"));
+        assert_eq!(glob_base("out/app"), PathBuf::from("out/app"));
+        assert_eq!(glob_base("assets/{css,js}/*"), PathBuf::from("assets"));
+        assert_eq!(glob_base("*.lock"), PathBuf::new());
+    }
+}
@@ -53,11 +53,13 @@ impl VoidCommand {
                     name: "subcommand".to_string(),
                     desc: Some("Subcommand to be called".to_string()),
                     required: true,
+                    ..Default::default()
                 },
                 SyntaxOptArg {
                     name: "options...".to_string(),
                     desc: Some("Options to pass to the subcommand".to_string()),
                     required: false,
+                    ..Default::default()
                 },
             ],
         })
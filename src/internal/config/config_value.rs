@@ -139,11 +139,21 @@ config_commands:
   split_on_dash: true
   split_on_slash: true
 env: {}
+github_release:
+  token_hosts: []
+justfile_commands:
+  enabled: true
+  split_on_dash: true
 makefile_commands:
   enabled: true
   split_on_dash: true
   split_on_slash: true
 org: []
+package_json_commands:
+  enabled: true
+  split_on_colon: true
+  split_on_dash: true
+  workspaces: false
 path:
   append: []
   prepend: []
@@ -159,8 +169,14 @@ path_repo_updates:
   ref_match: null # regex or null
   per_repo_config: {}
 repo_path_format: "%{host}/%{org}/%{repo}"
+taskfile_commands:
+  enabled: true
+  split_on_dash: true
 up_command:
   auto_bootstrap: true
+  notify_outdated: true
+  auto_up: false
+  per_repo_config: {}
 "#;
 
         // Convert yaml_str from String to &str
@@ -90,7 +90,7 @@ impl ConfigLoader {
         Self::new_global()
     }
 
-    fn user_config_files() -> Vec<String> {
+    pub fn user_config_files() -> Vec<String> {
         vec![
             format!("{}/.omni.yaml", user_home()),
             format!("{}/omni.yaml", xdg_config_home()),
@@ -16,9 +16,12 @@ pub(crate) use parser::flush_config;
 pub(crate) use parser::global_config;
 pub(crate) use parser::CommandDefinition;
 pub(crate) use parser::CommandSyntax;
+pub(crate) use parser::EnvSecretConfig;
 pub(crate) use parser::OmniConfig;
 pub(crate) use parser::OrgConfig;
+pub(crate) use parser::RepoShellAliasConfig;
 pub(crate) use parser::SyntaxOptArg;
+pub(crate) use parser::SyntaxOptArgType;
 
 pub(crate) mod up;
 
@@ -121,17 +121,24 @@ pub struct OmniConfig {
     pub command_match_skip_prompt_if: MatchSkipPromptIfConfig,
     pub config_commands: ConfigCommandsConfig,
     pub makefile_commands: MakefileCommandsConfig,
+    pub justfile_commands: JustfileCommandsConfig,
+    pub package_json_commands: PackageJsonCommandsConfig,
+    pub taskfile_commands: TaskfileCommandsConfig,
     pub org: Vec<OrgConfig>,
     pub path: PathConfig,
     pub path_repo_updates: PathRepoUpdatesConfig,
     pub repo_path_format: String,
     pub env: HashMap<String, String>,
+    pub env_secrets: HashMap<String, EnvSecretConfig>,
     pub cd: CdConfig,
+    pub github_release: GithubReleaseConfig,
     pub clone: CloneConfig,
     pub up: Option<UpConfig>,
     pub suggest_clone: SuggestCloneConfig,
     pub up_command: UpCommandConfig,
     pub shell_aliases: ShellAliasesConfig,
+    pub repo_shell_aliases: HashMap<String, RepoShellAliasConfig>,
+    pub repo_sets: HashMap<String, Vec<String>>,
 }
 
 impl OmniConfig {
@@ -154,9 +161,44 @@ impl OmniConfig {
         }
 
         let mut env_config = HashMap::new();
+        let mut env_secrets_config = HashMap::new();
         if let Some(value) = config_value.get("env") {
             for (key, value) in value.as_table().unwrap() {
-                env_config.insert(key.to_string(), value.as_str().unwrap().to_string());
+                if let Some(secret) = EnvSecretConfig::from_config_value(&value) {
+                    env_secrets_config.insert(key.to_string(), secret);
+                } else {
+                    env_config.insert(key.to_string(), value.as_str().unwrap().to_string());
+                }
+            }
+        }
+
+        let mut repo_shell_aliases_config = HashMap::new();
+        if let Some(value) = config_value.get("repo_shell_aliases") {
+            if let Some(table) = value.as_table() {
+                for (name, value) in table {
+                    if !RepoShellAliasConfig::is_valid_name(&name) {
+                        continue;
+                    }
+                    if let Some(alias) = RepoShellAliasConfig::from_config_value(&value) {
+                        repo_shell_aliases_config.insert(name, alias);
+                    }
+                }
+            }
+        }
+
+        let mut repo_sets_config = HashMap::new();
+        if let Some(value) = config_value.get("repo_sets") {
+            if let Some(table) = value.as_table() {
+                for (name, value) in table {
+                    let patterns = if let Some(pattern) = value.as_str() {
+                        vec![pattern]
+                    } else if let Some(array) = value.as_array() {
+                        array.iter().filter_map(|value| value.as_str()).collect()
+                    } else {
+                        continue;
+                    };
+                    repo_sets_config.insert(name, patterns);
+                }
             }
         }
 
@@ -178,6 +220,15 @@ impl OmniConfig {
             makefile_commands: MakefileCommandsConfig::from_config_value(
                 &config_value.get("makefile_commands").unwrap(),
             ),
+            justfile_commands: JustfileCommandsConfig::from_config_value(
+                config_value.get("justfile_commands"),
+            ),
+            package_json_commands: PackageJsonCommandsConfig::from_config_value(
+                config_value.get("package_json_commands"),
+            ),
+            taskfile_commands: TaskfileCommandsConfig::from_config_value(
+                config_value.get("taskfile_commands"),
+            ),
             org: org_config,
             path: PathConfig::from_config_value(&config_value.get("path").unwrap()),
             path_repo_updates: PathRepoUpdatesConfig::from_config_value(
@@ -188,15 +239,52 @@ impl OmniConfig {
                 .unwrap()
                 .to_string(),
             env: env_config,
+            env_secrets: env_secrets_config,
             cd: CdConfig::from_config_value(config_value.get("cd")),
             clone: CloneConfig::from_config_value(config_value.get("clone")),
+            github_release: GithubReleaseConfig::from_config_value(
+                config_value.get("github_release"),
+            ),
             up: UpConfig::from_config_value(config_value.get("up")),
             suggest_clone: SuggestCloneConfig::from_config_value(config_value.get("suggest_clone")),
             up_command: UpCommandConfig::from_config_value(config_value.get("up_command")),
             shell_aliases: ShellAliasesConfig::from_config_value(config_value.get("shell_aliases")),
+            repo_shell_aliases: repo_shell_aliases_config,
+            repo_sets: repo_sets_config,
         }
     }
 
+    /// Returns a fingerprint of the configuration that `omni up` applies to
+    /// the repository, so we can know if it changed since `omni up` was last
+    /// run; this is 0 if there is nothing for `omni up` to apply.
+    pub fn up_fingerprint(&self) -> u64 {
+        let has_up_config = self.up.as_ref().is_some_and(|up| up.has_steps());
+        if !has_up_config
+            && self.env.is_empty()
+            && self.env_secrets.is_empty()
+            && self.repo_shell_aliases.is_empty()
+        {
+            return 0;
+        }
+
+        // Going through a JSON value sorts the keys of the maps, so the
+        // fingerprint does not depend on their iteration order
+        let value = match serde_json::to_value((
+            &self.up,
+            &self.env,
+            &self.env_secrets,
+            &self.repo_shell_aliases,
+        )) {
+            Ok(value) => value,
+            Err(_) => return 0,
+        };
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(value.to_string().as_bytes());
+        let hash_bytes = hasher.finalize();
+        u64::from_le_bytes(hash_bytes.as_bytes()[..8].try_into().unwrap())
+    }
+
     pub fn worktree(&self) -> String {
         if let Some(omni_git) = omni_git_env() {
             return omni_git;
@@ -206,6 +294,57 @@ impl OmniConfig {
     }
 }
 
+/// An environment variable whose value is the output of a command, run
+/// when the dynamic environment is applied; the value is only kept in the
+/// environment, or in a private cache file for `ttl` seconds if provided.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EnvSecretConfig {
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+}
+
+impl EnvSecretConfig {
+    fn from_config_value(config_value: &ConfigValue) -> Option<Self> {
+        let command = config_value.get_as_str("secret")?;
+
+        Some(Self {
+            command,
+            ttl: config_value.get_as_unsigned_integer("ttl"),
+        })
+    }
+}
+
+/// A shell alias or function defined in the shell by the dynamic
+/// environment while inside the work directory.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RepoShellAliasConfig {
+    Alias(String),
+    Function(String),
+}
+
+impl RepoShellAliasConfig {
+    fn from_config_value(config_value: &ConfigValue) -> Option<Self> {
+        if let Some(value) = config_value.as_str() {
+            return Some(Self::Alias(value));
+        }
+
+        config_value.get_as_str("function").map(Self::Function)
+    }
+
+    /// Only accept names that can be used for both aliases and functions
+    /// in bash, zsh and fish, and that do not need quoting.
+    fn is_valid_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+            _ => return false,
+        }
+        chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CacheConfig {
     pub path: String,
@@ -230,6 +369,15 @@ pub struct CommandDefinition {
     pub syntax: Option<CommandSyntax>,
     pub category: Option<Vec<String>>,
     pub subcommands: Option<HashMap<String, CommandDefinition>>,
+    pub shell: Option<String>,
+    pub dir: Option<String>,
+    pub env: HashMap<String, String>,
+    pub confirm: Option<String>,
+    pub requires_up: bool,
+    pub depends_on: Vec<String>,
+    pub parallel: bool,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
     pub source: ConfigSource,
 }
 
@@ -277,6 +425,25 @@ impl CommandDefinition {
             None => vec![],
         };
 
+        let env = match config_value.get_as_table("env") {
+            Some(table) => table
+                .into_iter()
+                .filter_map(|(key, value)| value.as_str_forced().map(|value| (key, value)))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        // The confirmation can be enabled with a boolean, to use the default
+        // message, or directly be the message to show
+        let confirm = match config_value.get("confirm") {
+            Some(value) if value.is_bool() => match value.as_bool() {
+                Some(true) => Some("".to_string()),
+                _ => None,
+            },
+            Some(value) => value.as_str(),
+            None => None,
+        };
+
         Self {
             desc: config_value
                 .get("desc")
@@ -289,12 +456,36 @@ impl CommandDefinition {
             syntax,
             category,
             subcommands,
+            shell: config_value
+                .get_as_str("shell")
+                .or_else(|| config_value.get_as_str("interpreter")),
+            dir: config_value.get_as_str("dir"),
+            env,
+            confirm,
+            requires_up: config_value.get_as_bool("requires_up").unwrap_or(false),
+            depends_on: string_or_list(config_value.get("depends_on")),
+            parallel: config_value.get_as_bool("parallel").unwrap_or(false),
+            inputs: string_or_list(config_value.get("inputs")),
+            outputs: string_or_list(config_value.get("outputs")),
             source: config_value.get_source().clone(),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+fn string_or_list(config_value: Option<ConfigValue>) -> Vec<String> {
+    match config_value {
+        Some(value) if value.is_array() => value
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|value| value.as_str_forced())
+            .collect(),
+        Some(value) => value.as_str_forced().into_iter().collect(),
+        None => vec![],
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct CommandSyntax {
     pub usage: Option<String>,
     pub parameters: Vec<SyntaxOptArg>,
@@ -370,13 +561,30 @@ impl CommandSyntax {
 
         Some(Self { usage, parameters })
     }
+
+    /// Omni only parses the arguments of a command for which at least one
+    /// parameter has a type, so that commands that do their own parsing
+    /// keep receiving their arguments untouched
+    pub fn parses_args(&self) -> bool {
+        self.parameters
+            .iter()
+            .any(|parameter| parameter.arg_type.is_some())
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct SyntaxOptArg {
     pub name: String,
     pub desc: Option<String>,
     pub required: bool,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub arg_type: Option<SyntaxOptArgType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub multiple: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complete: Option<String>,
 }
 
 impl SyntaxOptArg {
@@ -385,13 +593,63 @@ impl SyntaxOptArg {
             name,
             desc,
             required,
+            ..Default::default()
         }
     }
 
+    /// The flags of the parameter, if it is an option; a name such as
+    /// `-f, --format` provides both a short and a long flag
+    pub fn flags(&self) -> Vec<String> {
+        self.name
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| part.starts_with('-') && part.len() > 1)
+            .map(|part| part.to_string())
+            .collect()
+    }
+
+    pub fn is_positional(&self) -> bool {
+        self.flags().is_empty()
+    }
+
+    /// Whether the parameter can receive more than one value, which is also
+    /// the case for positional parameters named with a trailing `...`
+    pub fn is_multiple(&self) -> bool {
+        self.multiple || (self.is_positional() && self.name.ends_with("..."))
+    }
+
+    /// The name of the parameter in the environment variables exported to
+    /// the command, based on its first long flag if any, e.g. `--dry-run`
+    /// becomes `DRY_RUN`
+    pub fn env_name(&self) -> String {
+        let flags = self.flags();
+        let name = flags
+            .iter()
+            .find(|flag| flag.starts_with("--"))
+            .or(flags.first())
+            .map(|flag| flag.as_str())
+            .unwrap_or(self.name.as_str());
+
+        name.trim_start_matches('-')
+            .trim_end_matches("...")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
     fn from_config_value(config_value: &ConfigValue, required: Option<bool>) -> Option<Self> {
         let name;
         let mut desc = None;
         let mut required = required;
+        let mut arg_type = None;
+        let mut default = None;
+        let mut multiple = false;
+        let mut complete = None;
 
         if let Some(table) = config_value.as_table() {
             let value_for_details;
@@ -417,11 +675,15 @@ impl SyntaxOptArg {
             if let Some(value_for_details) = value_for_details {
                 if let Some(value_str) = value_for_details.as_str() {
                     desc = Some(value_str.to_string());
-                } else if let Some(value_table) = value_for_details.as_table() {
-                    desc = value_table.get("desc")?.as_str();
+                } else if value_for_details.is_table() {
+                    desc = value_for_details.get_as_str("desc");
                     if required.is_none() {
-                        required = value_table.get("required")?.as_bool();
+                        required = value_for_details.get_as_bool("required");
                     }
+                    arg_type = SyntaxOptArgType::from_config_value(&value_for_details);
+                    default = value_for_details.get_as_str_forced("default");
+                    multiple = value_for_details.get_as_bool("multiple").unwrap_or(false);
+                    complete = value_for_details.get_as_str("complete");
                 }
             }
         } else {
@@ -432,10 +694,58 @@ impl SyntaxOptArg {
             name,
             desc,
             required: required.unwrap_or(false),
+            arg_type,
+            default,
+            multiple,
+            complete,
         })
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyntaxOptArgType {
+    String,
+    Int,
+    Flag,
+    Enum(Vec<String>),
+    Path,
+    Repo,
+}
+
+impl SyntaxOptArgType {
+    /// Reads the type from the `type` key of a parameter, and the `values`
+    /// key for enums; providing `values` alone also makes it an enum
+    fn from_config_value(config_value: &ConfigValue) -> Option<Self> {
+        let values = config_value.get_as_array("values").map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str_forced())
+                .collect::<Vec<String>>()
+        });
+
+        match config_value.get_as_str("type") {
+            Some(arg_type) => Self::from_str(&arg_type, values),
+            None => values.map(Self::Enum),
+        }
+    }
+
+    pub fn from_str(arg_type: &str, values: Option<Vec<String>>) -> Option<Self> {
+        match arg_type.to_lowercase().as_str() {
+            "string" | "str" => Some(Self::String),
+            "int" | "integer" => Some(Self::Int),
+            "flag" | "bool" | "boolean" => Some(Self::Flag),
+            "enum" => match values {
+                Some(values) if !values.is_empty() => Some(Self::Enum(values)),
+                _ => None,
+            },
+            "path" => Some(Self::Path),
+            "repo" | "repository" => Some(Self::Repo),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MatchSkipPromptIfConfig {
     pub enabled: bool,
@@ -526,6 +836,75 @@ impl MakefileCommandsConfig {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JustfileCommandsConfig {
+    pub enabled: bool,
+    pub split_on_dash: bool,
+}
+
+impl JustfileCommandsConfig {
+    fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+        match config_value {
+            Some(config_value) => Self {
+                enabled: config_value.get_as_bool("enabled").unwrap_or(true),
+                split_on_dash: config_value.get_as_bool("split_on_dash").unwrap_or(true),
+            },
+            None => Self {
+                enabled: true,
+                split_on_dash: true,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageJsonCommandsConfig {
+    pub enabled: bool,
+    pub split_on_colon: bool,
+    pub split_on_dash: bool,
+    pub workspaces: bool,
+}
+
+impl PackageJsonCommandsConfig {
+    fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+        match config_value {
+            Some(config_value) => Self {
+                enabled: config_value.get_as_bool("enabled").unwrap_or(true),
+                split_on_colon: config_value.get_as_bool("split_on_colon").unwrap_or(true),
+                split_on_dash: config_value.get_as_bool("split_on_dash").unwrap_or(true),
+                workspaces: config_value.get_as_bool("workspaces").unwrap_or(false),
+            },
+            None => Self {
+                enabled: true,
+                split_on_colon: true,
+                split_on_dash: true,
+                workspaces: false,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskfileCommandsConfig {
+    pub enabled: bool,
+    pub split_on_dash: bool,
+}
+
+impl TaskfileCommandsConfig {
+    fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+        match config_value {
+            Some(config_value) => Self {
+                enabled: config_value.get_as_bool("enabled").unwrap_or(true),
+                split_on_dash: config_value.get_as_bool("split_on_dash").unwrap_or(true),
+            },
+            None => Self {
+                enabled: true,
+                split_on_dash: true,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OrgConfig {
     pub handle: String,
@@ -912,6 +1291,27 @@ impl CdConfig {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GithubReleaseConfig {
+    /// Hosts, other than `github.com` and `api.github.com`, to which the
+    /// GitHub token can be sent; only read from the user configuration, so
+    /// that a repository cannot get the token sent to a host it controls
+    pub token_hosts: Vec<String>,
+}
+
+impl GithubReleaseConfig {
+    fn from_config_value(config_value: Option<ConfigValue>) -> Self {
+        let token_hosts = config_value
+            .and_then(|config_value| config_value.reject_label("git_repo"))
+            .and_then(|config_value| config_value.get("token_hosts"))
+            .and_then(|value| value.as_array())
+            .map(|hosts| hosts.iter().filter_map(|host| host.as_str()).collect())
+            .unwrap_or_default();
+
+        Self { token_hosts }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CloneConfig {
     pub ls_remote_timeout_seconds: u64,
@@ -1059,23 +1459,102 @@ impl SuggestCloneRepositoryConfig {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpCommandConfig {
     pub auto_bootstrap: bool,
+    pub notify_outdated: bool,
+    pub auto_up: bool,
+    pub watch_files: Vec<String>,
+    pub per_repo_config: HashMap<String, UpCommandPerRepoConfig>,
 }
 
 impl UpCommandConfig {
+    const DEFAULT_WATCH_FILES: [&'static str; 10] = [
+        "Gemfile.lock",
+        "go.sum",
+        "Cargo.lock",
+        "package-lock.json",
+        "yarn.lock",
+        "pnpm-lock.yaml",
+        "poetry.lock",
+        "Pipfile.lock",
+        "requirements.txt",
+        "Brewfile",
+    ];
+
     fn from_config_value(config_value: Option<ConfigValue>) -> Self {
         if let Some(config_value) = config_value {
             if let Some(config_value) = config_value.reject_label("git_repo") {
+                let mut per_repo_config = HashMap::new();
+                if let Some(value) = config_value.get("per_repo_config") {
+                    if let Some(table) = value.as_table() {
+                        for (key, value) in table {
+                            per_repo_config.insert(
+                                key.to_string(),
+                                UpCommandPerRepoConfig::from_config_value(&value),
+                            );
+                        }
+                    }
+                }
+
+                let watch_files = match config_value.get("watch_files") {
+                    Some(value) => match value.as_array() {
+                        Some(array) => array.iter().filter_map(|value| value.as_str()).collect(),
+                        None => Self::default_watch_files(),
+                    },
+                    None => Self::default_watch_files(),
+                };
+
                 return Self {
                     auto_bootstrap: match config_value.get("auto_bootstrap") {
                         Some(value) => value.as_bool().unwrap(),
                         None => true,
                     },
+                    notify_outdated: config_value.get_as_bool("notify_outdated").unwrap_or(true),
+                    auto_up: config_value.get_as_bool("auto_up").unwrap_or(false),
+                    watch_files,
+                    per_repo_config,
                 };
             }
         }
 
         Self {
             auto_bootstrap: true,
+            notify_outdated: true,
+            auto_up: false,
+            watch_files: Self::default_watch_files(),
+            per_repo_config: HashMap::new(),
+        }
+    }
+
+    fn default_watch_files() -> Vec<String> {
+        Self::DEFAULT_WATCH_FILES
+            .iter()
+            .map(|file| file.to_string())
+            .collect()
+    }
+
+    /// Returns whether to notify that `omni up` is outdated for the
+    /// repository, and whether to run it automatically instead
+    pub fn outdated_config(&self, repo_id: &str) -> (bool, bool) {
+        match self.per_repo_config.get(repo_id) {
+            Some(value) => (
+                value.notify_outdated.unwrap_or(self.notify_outdated),
+                value.auto_up.unwrap_or(self.auto_up),
+            ),
+            None => (self.notify_outdated, self.auto_up),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpCommandPerRepoConfig {
+    pub notify_outdated: Option<bool>,
+    pub auto_up: Option<bool>,
+}
+
+impl UpCommandPerRepoConfig {
+    fn from_config_value(config_value: &ConfigValue) -> Self {
+        Self {
+            notify_outdated: config_value.get_as_bool("notify_outdated"),
+            auto_up: config_value.get_as_bool("auto_up"),
         }
     }
 }
@@ -1143,3 +1622,67 @@ impl ShellAliasConfig {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::config::ConfigExtendOptions;
+
+    fn omni_config(yaml: &str) -> OmniConfig {
+        let mut config_value = ConfigValue::default();
+        config_value.extend(
+            ConfigValue::from_str(yaml),
+            ConfigExtendOptions::new(),
+            vec![],
+        );
+        OmniConfig::from_config_value(&config_value)
+    }
+
+    fn up_fingerprint(yaml: &str) -> u64 {
+        omni_config(yaml).up_fingerprint()
+    }
+
+    #[test]
+    fn up_fingerprint_without_up_config() {
+        assert_eq!(up_fingerprint("worktree: /tmp\n"), 0);
+    }
+
+    #[test]
+    fn up_fingerprint_depends_on_tool_versions() {
+        let node18 = up_fingerprint("up:\n  - nodejs: 18.0.0\n");
+        let node20 = up_fingerprint("up:\n  - nodejs: 20.0.0\n");
+
+        assert_ne!(node18, 0);
+        assert_ne!(node18, node20);
+        assert_eq!(node18, up_fingerprint("up:\n  - nodejs: 18.0.0\n"));
+    }
+
+    #[test]
+    fn up_fingerprint_depends_on_env() {
+        let first = up_fingerprint("env:\n  FOO: bar\n");
+        let second = up_fingerprint("env:\n  FOO: baz\n");
+
+        assert_ne!(first, 0);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn repo_sets() {
+        let config = omni_config(concat!(
+            "repo_sets:\n",
+            "  services:\n",
+            "    - github.com/acme/*-service\n",
+            "    - github.com/acme/gateway\n",
+            "  docs: github.com/acme/docs\n",
+            "  invalid:\n",
+            "    key: value\n",
+        ));
+
+        assert_eq!(config.repo_sets.len(), 2);
+        assert_eq!(
+            config.repo_sets["services"],
+            vec!["github.com/acme/*-service", "github.com/acme/gateway"]
+        );
+        assert_eq!(config.repo_sets["docs"], vec!["github.com/acme/docs"]);
+    }
+}
@@ -17,6 +17,9 @@ use walkdir::WalkDir;
 use crate::internal::cache::AsdfOperationCache;
 use crate::internal::cache::CacheObject;
 use crate::internal::cache::UpEnvironmentsCache;
+use crate::internal::config::up::backend::backend_for_installed;
+use crate::internal::config::up::backend::backend_from_name;
+use crate::internal::config::up::backend::UpVersionBackend;
 use crate::internal::config::up::tool::UpConfigTool;
 use crate::internal::config::up::utils::run_progress;
 use crate::internal::config::up::utils::PrintProgressHandler;
@@ -128,6 +131,8 @@ fn is_asdf_tool_version_installed(tool: &str, version: &str) -> bool {
 pub struct UpConfigAsdfBase {
     pub tool: String,
     pub tool_url: Option<String>,
+    pub plugin_ref: Option<String>,
+    pub backend: Option<String>,
     pub version: String,
     pub dirs: BTreeSet<String>,
     #[serde(skip)]
@@ -143,6 +148,8 @@ impl UpConfigAsdfBase {
         UpConfigAsdfBase {
             tool: tool.to_string(),
             tool_url: None,
+            plugin_ref: None,
+            backend: None,
             version: version.to_string(),
             dirs: BTreeSet::new(),
             detect_version_funcs: vec![],
@@ -155,10 +162,27 @@ impl UpConfigAsdfBase {
         self.detect_version_funcs.push(func);
     }
 
+    pub fn set_plugin_source(&mut self, plugin_url: Option<String>, plugin_ref: Option<String>) {
+        if plugin_url.is_some() {
+            self.tool_url = plugin_url;
+        }
+        self.plugin_ref = plugin_ref;
+    }
+
+    pub fn set_backend(&mut self, backend: Option<String>) {
+        self.backend = backend;
+    }
+
+    fn backend(&self) -> Result<Box<dyn UpVersionBackend>, UpError> {
+        backend_from_name(self.backend.as_deref().unwrap_or("asdf"), &self.tool)
+    }
+
     fn new_from_auto(&self, version: &str, dirs: BTreeSet<String>) -> Self {
         UpConfigAsdfBase {
             tool: self.tool.clone(),
             tool_url: self.tool_url.clone(),
+            plugin_ref: self.plugin_ref.clone(),
+            backend: self.backend.clone(),
             version: version.to_string(),
             dirs: dirs.clone(),
             detect_version_funcs: vec![],
@@ -186,6 +210,9 @@ impl UpConfigAsdfBase {
     ) -> Self {
         let mut version = "latest".to_string();
         let mut dirs = BTreeSet::new();
+        let mut tool_url = tool_url;
+        let mut plugin_ref = None;
+        let mut backend = None;
 
         if let Some(config_value) = config_value {
             if let Some(value) = config_value.as_str() {
@@ -206,12 +233,26 @@ impl UpConfigAsdfBase {
                         dirs.insert(value.as_str().unwrap().to_string());
                     }
                 }
+
+                if let Some(value) = config_value.get_as_str("plugin_url") {
+                    tool_url = Some(value.to_string());
+                }
+
+                if let Some(value) = config_value.get_as_str_forced("plugin_ref") {
+                    plugin_ref = Some(value.to_string());
+                }
+
+                if let Some(value) = config_value.get_as_str("backend") {
+                    backend = Some(value.to_string());
+                }
             }
         }
 
         UpConfigAsdfBase {
             tool: tool.to_string(),
             tool_url,
+            plugin_ref,
+            backend,
             version,
             dirs,
             detect_version_funcs: vec![],
@@ -279,14 +320,10 @@ impl UpConfigAsdfBase {
         };
         let progress_handler: Option<&dyn ProgressHandler> = Some(progress_handler.as_ref());
 
-        if let Err(err) = install_asdf(progress_handler) {
-            if let Some(ph) = progress_handler {
-                ph.error_with_message(format!("error: {}", err))
-            }
-            return Err(err);
-        }
-
-        if let Err(err) = self.install_plugin(progress_handler) {
+        if let Err(err) = self
+            .backend()
+            .and_then(|backend| backend.setup(self, progress_handler))
+        {
             if let Some(ph) = progress_handler {
                 ph.error_with_message(format!("error: {}", err))
             }
@@ -483,55 +520,13 @@ impl UpConfigAsdfBase {
 
     fn version(&self, progress_handler: Option<&dyn ProgressHandler>) -> Result<&String, UpError> {
         let version = self.actual_version.get_or_init(|| {
-            if self.update_plugin(progress_handler).is_err() {
-                return "".to_string();
-            }
-
-            if let Some(handler) = progress_handler {
-                handler.progress("checking available versions".to_string());
-            }
-
-            let available_versions = if let Some(versions) =
-                AsdfOperationCache::get().get_asdf_plugin_versions(&self.tool)
+            let available_versions = match self
+                .backend()
+                .and_then(|backend| backend.list_versions(self, progress_handler))
             {
-                versions
-            } else {
-                let mut asdf_list_all = std::process::Command::new(&(*ASDF_BIN));
-                asdf_list_all.arg("list");
-                asdf_list_all.arg("all");
-                asdf_list_all.arg(self.tool.clone());
-                asdf_list_all.env("ASDF_DIR", &*ASDF_PATH);
-                asdf_list_all.env("ASDF_DATA_DIR", &*ASDF_PATH);
-                asdf_list_all.stdout(std::process::Stdio::piped());
-                asdf_list_all.stderr(std::process::Stdio::piped());
-
-                if let Ok(output) = asdf_list_all.output() {
-                    if output.status.success() {
-                        let stdout = String::from_utf8(output.stdout).unwrap();
-                        let versions = stdout
-                            .lines()
-                            .map(|line| line.trim().to_string())
-                            .filter(|line| !line.is_empty())
-                            .collect::<Vec<String>>();
-
-                        if let Err(err) = AsdfOperationCache::exclusive(|cache| {
-                            cache.set_asdf_plugin_versions(&self.tool, versions.clone());
-                            true
-                        }) {
-                            omni_error!(format!("failed to update cache: {}", err));
-                            return "".to_string();
-                        }
-
-                        versions
-                    } else {
-                        omni_error!(format!(
-                            "failed to list versions for {}; exited with status {}",
-                            self.tool, output.status
-                        ));
-                        return "".to_string();
-                    }
-                } else {
-                    omni_error!(format!("failed to list versions for {}", self.tool));
+                Ok(versions) => versions,
+                Err(err) => {
+                    omni_error!(format!("{}", err));
                     return "".to_string();
                 }
             };
@@ -575,38 +570,275 @@ impl UpConfigAsdfBase {
         false
     }
 
+    fn plugin_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}/plugins/{}", *ASDF_PATH, self.tool))
+    }
+
+    /// The URL of the plugin in the short-name repository of asdf, which is
+    /// where the plugin is installed from when no `plugin_url` is specified
+    fn default_plugin_url(&self) -> Option<String> {
+        let index_path = PathBuf::from(format!("{}/repository/plugins/{}", *ASDF_PATH, self.tool));
+
+        // The short-name repository is only cloned by asdf when a plugin is
+        // added without URL, which might never have happened if the plugin
+        // was first added from a different source
+        if !index_path.exists() {
+            let mut asdf_plugin_list = std::process::Command::new(&(*ASDF_BIN));
+            asdf_plugin_list.arg("plugin");
+            asdf_plugin_list.arg("list");
+            asdf_plugin_list.arg("all");
+            asdf_plugin_list.env("ASDF_DIR", &*ASDF_PATH);
+            asdf_plugin_list.env("ASDF_DATA_DIR", &*ASDF_PATH);
+            asdf_plugin_list.stdout(std::process::Stdio::null());
+            asdf_plugin_list.stderr(std::process::Stdio::null());
+            let _ = asdf_plugin_list.status();
+        }
+
+        parse_plugin_index(&std::fs::read_to_string(index_path).ok()?)
+    }
+
+    fn plugin_git(&self, args: &[&str]) -> Option<String> {
+        let mut git = std::process::Command::new("git");
+        git.arg("-C");
+        git.arg(self.plugin_path());
+        git.args(args);
+        git.stdout(std::process::Stdio::piped());
+        git.stderr(std::process::Stdio::null());
+
+        if let Ok(output) = git.output() {
+            if output.status.success() {
+                let stdout = String::from_utf8(output.stdout).unwrap();
+                return Some(stdout.trim().to_string());
+            }
+        }
+
+        None
+    }
+
+    fn run_plugin_git(
+        &self,
+        args: &[&str],
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        let mut git = TokioCommand::new("git");
+        git.arg("-C");
+        git.arg(self.plugin_path());
+        git.args(args);
+        git.stdout(std::process::Stdio::piped());
+        git.stderr(std::process::Stdio::piped());
+
+        run_progress(&mut git, progress_handler, RunConfig::default())
+    }
+
+    fn resolve_plugin_ref(&self, plugin_ref: &str) -> Option<String> {
+        for candidate in [plugin_ref.to_string(), format!("origin/{}", plugin_ref)] {
+            let candidate = format!("{}^{{commit}}", candidate);
+            if let Some(commit) = self.plugin_git(&["rev-parse", "--verify", "--quiet", &candidate])
+            {
+                return Some(commit);
+            }
+        }
+
+        None
+    }
+
     fn install_plugin(
         &self,
         progress_handler: Option<&dyn ProgressHandler>,
     ) -> Result<(), UpError> {
-        if self.is_plugin_installed() {
+        if !self.is_plugin_installed() {
+            if let Some(handler) = progress_handler {
+                handler.progress(format!("installing {} plugin", self.tool));
+            }
+
+            let mut asdf_plugin_add = TokioCommand::new(&(*ASDF_BIN));
+            asdf_plugin_add.arg("plugin");
+            asdf_plugin_add.arg("add");
+            asdf_plugin_add.arg(self.tool.clone());
+            if let Some(tool_url) = &self.tool_url {
+                asdf_plugin_add.arg(tool_url.clone());
+            }
+            asdf_plugin_add.env("ASDF_DIR", &*ASDF_PATH);
+            asdf_plugin_add.env("ASDF_DATA_DIR", &*ASDF_PATH);
+            asdf_plugin_add.stdout(std::process::Stdio::piped());
+            asdf_plugin_add.stderr(std::process::Stdio::piped());
+
+            run_progress(&mut asdf_plugin_add, progress_handler, RunConfig::default())?;
+        }
+
+        // Make sure the plugin is pulled from the requested source; we do not
+        // remove and re-add the plugin as this would remove the installed versions.
+        // The plugin directory is shared by all the repositories, so if another
+        // repository switched it to a different source, it is brought back to
+        // the default source when this one does not request any
+        let mut source_changed = false;
+        let source_url = match &self.tool_url {
+            Some(tool_url) => Some(tool_url.clone()),
+            None => self.default_plugin_url(),
+        };
+        if let Some(source_url) = source_url {
+            let current_url = self.plugin_git(&["remote", "get-url", "origin"]);
+            if !current_url.is_some_and(|current_url| same_plugin_url(&current_url, &source_url)) {
+                if let Some(handler) = progress_handler {
+                    handler.progress(format!("switching {} plugin to {}", self.tool, source_url));
+                }
+
+                self.run_plugin_git(
+                    &["remote", "set-url", "origin", &source_url],
+                    progress_handler,
+                )?;
+                source_changed = true;
+            }
+        }
+
+        if let Some(plugin_ref) = &self.plugin_ref {
+            self.checkout_plugin_ref(plugin_ref, source_changed, progress_handler)?;
+        } else if self.plugin_git(&["symbolic-ref", "-q", "HEAD"]).is_none() {
+            // The plugin directory is shared by all the repositories; if it
+            // was pinned by another repository, bring it back to its default
+            // branch since this one does not request any specific ref
+            self.checkout_plugin_default_branch(progress_handler)?;
+        } else if source_changed {
+            self.run_plugin_update(progress_handler)?;
+        }
+
+        self.record_plugin();
+
+        Ok(())
+    }
+
+    fn checkout_plugin_ref(
+        &self,
+        plugin_ref: &str,
+        force_fetch: bool,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        let head = self.plugin_git(&["rev-parse", "HEAD"]);
+
+        let mut target = if force_fetch {
+            None
+        } else {
+            self.resolve_plugin_ref(plugin_ref)
+        };
+
+        if target.is_none() {
+            if let Some(handler) = progress_handler {
+                handler.progress(format!("fetching {} plugin", self.tool));
+            }
+
+            self.run_plugin_git(
+                &["fetch", "--prune", "--tags", "--force", "origin"],
+                progress_handler,
+            )?;
+
+            target = self.resolve_plugin_ref(plugin_ref);
+        }
+
+        let target = match target {
+            Some(target) => target,
+            None => {
+                return Err(UpError::Exec(format!(
+                    "unable to find ref {} for {} plugin",
+                    plugin_ref, self.tool,
+                )));
+            }
+        };
+
+        if head.as_ref() == Some(&target) {
             return Ok(());
         }
 
         if let Some(handler) = progress_handler {
-            handler.progress(format!("installing {} plugin", self.tool));
+            handler.progress(format!(
+                "checking out {} plugin at {}",
+                self.tool, plugin_ref
+            ));
+        }
+
+        self.run_plugin_git(
+            &["checkout", "--force", "--detach", &target],
+            progress_handler,
+        )
+    }
+
+    fn checkout_plugin_default_branch(
+        &self,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        if let Some(handler) = progress_handler {
+            handler.progress(format!("checking out {} plugin default branch", self.tool));
         }
 
-        let mut asdf_plugin_add = TokioCommand::new(&(*ASDF_BIN));
-        asdf_plugin_add.arg("plugin");
-        asdf_plugin_add.arg("add");
-        asdf_plugin_add.arg(self.tool.clone());
-        if let Some(tool_url) = &self.tool_url {
-            asdf_plugin_add.arg(tool_url.clone());
+        self.run_plugin_git(
+            &["fetch", "--prune", "--tags", "--force", "origin"],
+            progress_handler,
+        )?;
+
+        let default_ref = "refs/remotes/origin/HEAD";
+        if self.plugin_git(&["symbolic-ref", default_ref]).is_none() {
+            self.run_plugin_git(
+                &["remote", "set-head", "origin", "--auto"],
+                progress_handler,
+            )?;
         }
-        asdf_plugin_add.env("ASDF_DIR", &*ASDF_PATH);
-        asdf_plugin_add.env("ASDF_DATA_DIR", &*ASDF_PATH);
-        asdf_plugin_add.stdout(std::process::Stdio::piped());
-        asdf_plugin_add.stderr(std::process::Stdio::piped());
 
-        run_progress(&mut asdf_plugin_add, progress_handler, RunConfig::default())
+        let branch = match self.plugin_git(&["symbolic-ref", "--short", default_ref]) {
+            Some(branch) => branch.trim_start_matches("origin/").to_string(),
+            None => {
+                return Err(UpError::Exec(format!(
+                    "unable to find default branch for {} plugin",
+                    self.tool,
+                )));
+            }
+        };
+
+        self.run_plugin_git(
+            &[
+                "checkout",
+                "--force",
+                "-B",
+                &branch,
+                &format!("origin/{}", branch),
+            ],
+            progress_handler,
+        )
+    }
+
+    fn record_plugin(&self) {
+        let commit = match self.plugin_git(&["rev-parse", "HEAD"]) {
+            Some(commit) => commit,
+            None => return,
+        };
+        let url = self.plugin_git(&["remote", "get-url", "origin"]);
+
+        if let Err(err) = AsdfOperationCache::exclusive(|cache| {
+            cache.set_asdf_plugin(&self.tool, url, self.plugin_ref.clone(), &commit)
+        }) {
+            omni_error!(format!("failed to update cache: {}", err));
+        }
     }
 
     fn update_plugin(&self, progress_handler: Option<&dyn ProgressHandler>) -> Result<(), UpError> {
+        // A pinned plugin is only ever moved to the requested ref, which
+        // is handled when installing the plugin
+        if self.plugin_ref.is_some() {
+            return Ok(());
+        }
+
         if !AsdfOperationCache::get().should_update_asdf_plugin(&self.tool) {
             return Ok(());
         }
 
+        self.run_plugin_update(progress_handler)?;
+        self.record_plugin();
+
+        Ok(())
+    }
+
+    fn run_plugin_update(
+        &self,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
         if let Some(handler) = progress_handler {
             handler.progress(format!("updating {} plugin", self.tool));
         }
@@ -644,7 +876,10 @@ impl UpConfigAsdfBase {
         }
         let version = version.unwrap();
 
-        is_asdf_tool_version_installed(&self.tool, version)
+        match self.backend() {
+            Ok(backend) => backend.is_version_installed(&self.tool, version),
+            Err(_) => false,
+        }
     }
 
     fn install_version(
@@ -661,16 +896,8 @@ impl UpConfigAsdfBase {
             handler.progress(format!("installing {} {}", self.tool, version));
         }
 
-        let mut asdf_install = tokio::process::Command::new(&(*ASDF_BIN));
-        asdf_install.arg("install");
-        asdf_install.arg(self.tool.clone());
-        asdf_install.arg(version);
-        asdf_install.env("ASDF_DIR", &*ASDF_PATH);
-        asdf_install.env("ASDF_DATA_DIR", &*ASDF_PATH);
-        asdf_install.stdout(std::process::Stdio::piped());
-        asdf_install.stderr(std::process::Stdio::piped());
-
-        run_progress(&mut asdf_install, progress_handler, RunConfig::default())?;
+        self.backend()?
+            .install_version(self, version, progress_handler)?;
 
         Ok(true)
     }
@@ -732,7 +959,8 @@ impl UpConfigAsdfBase {
             }
 
             for (idx, to_remove) in to_remove.iter().rev() {
-                if is_asdf_tool_version_installed(&to_remove.tool, &to_remove.version) {
+                let backend = backend_for_installed(&to_remove.tool, &to_remove.version);
+                if backend.is_version_installed(&to_remove.tool, &to_remove.version) {
                     if let Some(handler) = progress_handler {
                         handler.progress(format!(
                             "uninstalling {} {}",
@@ -740,18 +968,11 @@ impl UpConfigAsdfBase {
                         ));
                     }
 
-                    let mut asdf_uninstall = tokio::process::Command::new(&(*ASDF_BIN));
-                    asdf_uninstall.arg("uninstall");
-                    asdf_uninstall.arg(to_remove.tool.clone());
-                    asdf_uninstall.arg(to_remove.version.clone());
-                    asdf_uninstall.env("ASDF_DIR", &*ASDF_PATH);
-                    asdf_uninstall.env("ASDF_DATA_DIR", &*ASDF_PATH);
-                    asdf_uninstall.stdout(std::process::Stdio::piped());
-                    asdf_uninstall.stderr(std::process::Stdio::piped());
-
-                    if let Err(_err) =
-                        run_progress(&mut asdf_uninstall, progress_handler, RunConfig::default())
-                    {
+                    if let Err(_err) = backend.uninstall_version(
+                        &to_remove.tool,
+                        &to_remove.version,
+                        progress_handler,
+                    ) {
                         if let Some(handler) = progress_handler {
                             handler.error_with_message(format!(
                                 "failed to uninstall {} {}",
@@ -794,7 +1015,140 @@ impl UpConfigAsdfBase {
     }
 }
 
-fn version_match(expect: &str, version: &str) -> bool {
+pub struct AsdfBackend;
+
+impl UpVersionBackend for AsdfBackend {
+    fn setup(
+        &self,
+        config: &UpConfigAsdfBase,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        install_asdf(progress_handler)?;
+        config.install_plugin(progress_handler)
+    }
+
+    fn list_versions(
+        &self,
+        config: &UpConfigAsdfBase,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<Vec<String>, UpError> {
+        config.update_plugin(progress_handler)?;
+
+        if let Some(handler) = progress_handler {
+            handler.progress("checking available versions".to_string());
+        }
+
+        if let Some(versions) = AsdfOperationCache::get().get_asdf_plugin_versions(&config.tool) {
+            return Ok(versions);
+        }
+
+        let mut asdf_list_all = std::process::Command::new(&(*ASDF_BIN));
+        asdf_list_all.arg("list");
+        asdf_list_all.arg("all");
+        asdf_list_all.arg(config.tool.clone());
+        asdf_list_all.env("ASDF_DIR", &*ASDF_PATH);
+        asdf_list_all.env("ASDF_DATA_DIR", &*ASDF_PATH);
+        asdf_list_all.stdout(std::process::Stdio::piped());
+        asdf_list_all.stderr(std::process::Stdio::piped());
+
+        let output = match asdf_list_all.output() {
+            Ok(output) => output,
+            Err(_err) => {
+                return Err(UpError::Exec(format!(
+                    "failed to list versions for {}",
+                    config.tool
+                )));
+            }
+        };
+
+        if !output.status.success() {
+            return Err(UpError::Exec(format!(
+                "failed to list versions for {}; exited with status {}",
+                config.tool, output.status
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let versions = stdout
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<String>>();
+
+        if let Err(err) = AsdfOperationCache::exclusive(|cache| {
+            cache.set_asdf_plugin_versions(&config.tool, versions.clone());
+            true
+        }) {
+            return Err(UpError::Cache(err.to_string()));
+        }
+
+        Ok(versions)
+    }
+
+    fn is_version_installed(&self, tool: &str, version: &str) -> bool {
+        is_asdf_tool_version_installed(tool, version)
+    }
+
+    fn install_version(
+        &self,
+        config: &UpConfigAsdfBase,
+        version: &str,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        let mut asdf_install = tokio::process::Command::new(&(*ASDF_BIN));
+        asdf_install.arg("install");
+        asdf_install.arg(config.tool.clone());
+        asdf_install.arg(version);
+        asdf_install.env("ASDF_DIR", &*ASDF_PATH);
+        asdf_install.env("ASDF_DATA_DIR", &*ASDF_PATH);
+        asdf_install.stdout(std::process::Stdio::piped());
+        asdf_install.stderr(std::process::Stdio::piped());
+
+        run_progress(&mut asdf_install, progress_handler, RunConfig::default())
+    }
+
+    fn uninstall_version(
+        &self,
+        tool: &str,
+        version: &str,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        let mut asdf_uninstall = tokio::process::Command::new(&(*ASDF_BIN));
+        asdf_uninstall.arg("uninstall");
+        asdf_uninstall.arg(tool);
+        asdf_uninstall.arg(version);
+        asdf_uninstall.env("ASDF_DIR", &*ASDF_PATH);
+        asdf_uninstall.env("ASDF_DATA_DIR", &*ASDF_PATH);
+        asdf_uninstall.stdout(std::process::Stdio::piped());
+        asdf_uninstall.stderr(std::process::Stdio::piped());
+
+        run_progress(&mut asdf_uninstall, progress_handler, RunConfig::default())
+    }
+}
+
+/// Returns the URL of a plugin from its entry in the short-name repository
+/// of asdf, which contains a `repository = <url>` line
+fn parse_plugin_index(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        if key.trim() != "repository" || value.trim().is_empty() {
+            return None;
+        }
+        Some(value.trim().to_string())
+    })
+}
+
+/// Returns whether two plugin URLs point to the same repository, ignoring
+/// the `.git` suffix and trailing slashes that are usually optional
+fn same_plugin_url(a: &str, b: &str) -> bool {
+    let normalize = |url: &str| {
+        let url = url.trim().trim_end_matches('/');
+        url.strip_suffix(".git").unwrap_or(url).to_string()
+    };
+    normalize(a) == normalize(b)
+}
+
+pub fn version_match(expect: &str, version: &str) -> bool {
     if expect == "latest" {
         let mut prev = '.';
         for c in version.chars() {
@@ -904,3 +1258,38 @@ fn detect_version_from_tool_version_file(tool_name: String, path: PathBuf) -> Op
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_plugin_index_reads_repository() {
+        assert_eq!(
+            parse_plugin_index("repository = https://github.com/asdf-vm/asdf-nodejs.git\n"),
+            Some("https://github.com/asdf-vm/asdf-nodejs.git".to_string())
+        );
+        assert_eq!(
+            parse_plugin_index("# comment\nrepository=https://example.com/plugin\n"),
+            Some("https://example.com/plugin".to_string())
+        );
+        assert_eq!(parse_plugin_index("repository =\n"), None);
+        assert_eq!(parse_plugin_index(""), None);
+    }
+
+    #[test]
+    fn same_plugin_url_ignores_optional_suffixes() {
+        assert!(same_plugin_url(
+            "https://github.com/asdf-vm/asdf-nodejs.git",
+            "https://github.com/asdf-vm/asdf-nodejs"
+        ));
+        assert!(same_plugin_url(
+            "https://github.com/asdf-vm/asdf-nodejs/",
+            "https://github.com/asdf-vm/asdf-nodejs.git"
+        ));
+        assert!(!same_plugin_url(
+            "https://github.com/fork/asdf-nodejs.git",
+            "https://github.com/asdf-vm/asdf-nodejs.git"
+        ));
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use crate::internal::config::up::asdf_base::AsdfBackend;
+use crate::internal::config::up::native::NativeBackend;
+use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::UpConfigAsdfBase;
+use crate::internal::config::up::UpError;
+use crate::internal::config::up::ASDF_PATH;
+
+/// A backend is in charge of listing, installing and uninstalling the
+/// versions of a tool; all backends install tools in the same layout
+/// (`<ASDF_PATH>/installs/<tool>/<version>`), so that the dynamic
+/// environment does not need to know which backend was used.
+pub trait UpVersionBackend {
+    fn setup(
+        &self,
+        config: &UpConfigAsdfBase,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError>;
+
+    fn list_versions(
+        &self,
+        config: &UpConfigAsdfBase,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<Vec<String>, UpError>;
+
+    fn is_version_installed(&self, tool: &str, version: &str) -> bool;
+
+    fn install_version(
+        &self,
+        config: &UpConfigAsdfBase,
+        version: &str,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError>;
+
+    fn uninstall_version(
+        &self,
+        tool: &str,
+        version: &str,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError>;
+}
+
+pub fn tool_install_path(tool: &str, version: &str) -> PathBuf {
+    PathBuf::from(format!("{}/installs/{}/{}", *ASDF_PATH, tool, version))
+}
+
+pub fn backend_from_name(name: &str, tool: &str) -> Result<Box<dyn UpVersionBackend>, UpError> {
+    match name {
+        "asdf" => Ok(Box::new(AsdfBackend)),
+        "native" => match NativeBackend::for_tool(tool) {
+            Some(backend) => Ok(Box::new(backend)),
+            None => Err(UpError::Config(format!(
+                "native backend is not supported for {}",
+                tool
+            ))),
+        },
+        _ => Err(UpError::Config(format!(
+            "unknown backend '{}' for {}",
+            name, tool
+        ))),
+    }
+}
+
+/// Returns the backend that was used to install a given version of a tool,
+/// so that it can be uninstalled the same way.
+pub fn backend_for_installed(tool: &str, version: &str) -> Box<dyn UpVersionBackend> {
+    if let Some(backend) = NativeBackend::for_installed(tool, version) {
+        Box::new(backend)
+    } else {
+        Box::new(AsdfBackend)
+    }
+}
@@ -57,7 +57,12 @@ impl UpConfigBundler {
         }
 
         if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
-            up_env.add_env_var(&workdir_id, "BUNDLE_GEMFILE", &self.gemfile_abs_path());
+            up_env.add_env_var(
+                &workdir_id,
+                "BUNDLE_GEMFILE",
+                &self.gemfile_abs_path(),
+                "bundler",
+            );
             true
         }) {
             if let Some(progress_handler) = progress_handler {
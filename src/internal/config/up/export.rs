@@ -0,0 +1,673 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::internal::cache::UpVersion;
+use crate::internal::config::up::UpConfig;
+use crate::internal::config::up::UpConfigTool;
+
+const ASDF_VERSION: &str = "v0.13.1";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpExportFormat {
+    Dockerfile,
+    Devcontainer,
+    GithubActions,
+}
+
+impl FromStr for UpExportFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dockerfile" => Ok(Self::Dockerfile),
+            "devcontainer" => Ok(Self::Devcontainer),
+            "github-actions" => Ok(Self::GithubActions),
+            _ => Err(()),
+        }
+    }
+}
+
+impl UpExportFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Dockerfile => "dockerfile",
+            Self::Devcontainer => "devcontainer",
+            Self::GithubActions => "github-actions",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UpExportTool {
+    tool: String,
+    plugin_url: Option<String>,
+    version: String,
+    resolved: bool,
+}
+
+#[derive(Debug, Clone)]
+struct UpExportCommand {
+    name: String,
+    dir: Option<String>,
+    run: String,
+}
+
+/// The steps of an `up` configuration, flattened into what needs to be
+/// installed and run, so they can be rendered into other formats.
+#[derive(Debug, Default)]
+pub struct UpExport {
+    tools: Vec<UpExportTool>,
+    taps: Vec<(String, Option<String>)>,
+    formulae: Vec<String>,
+    casks: Vec<String>,
+    commands: Vec<UpExportCommand>,
+    env: BTreeMap<String, String>,
+    skipped: Vec<String>,
+}
+
+impl UpExport {
+    /// Prepares the export of the given configuration; the versions resolved
+    /// during the last `omni up` are used when available, and the versions
+    /// from the configuration otherwise.
+    pub fn new(
+        up_config: &UpConfig,
+        env: &HashMap<String, String>,
+        resolved_versions: &[UpVersion],
+    ) -> Self {
+        let mut export = Self {
+            env: env
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            ..Default::default()
+        };
+
+        for step in up_config.steps.iter() {
+            match step {
+                UpConfigTool::Bundler(config) => {
+                    let mut run = vec![];
+                    if let Some(path) = &config.path {
+                        run.push(format!("bundle config --local path {}", shell_quote(path)));
+                    }
+                    match &config.gemfile {
+                        Some(gemfile) => {
+                            run.push(format!("bundle install --gemfile {}", shell_quote(gemfile)))
+                        }
+                        None => run.push("bundle install".to_string()),
+                    }
+                    export.commands.push(UpExportCommand {
+                        name: "Install Gemfile dependencies".to_string(),
+                        dir: None,
+                        run: run.join("\n"),
+                    });
+                }
+                UpConfigTool::Custom(config) => {
+                    if config.meet.is_empty() {
+                        continue;
+                    }
+                    export.commands.push(UpExportCommand {
+                        name: config.name.clone().unwrap_or(config.meet.clone()),
+                        dir: config.dir.clone(),
+                        run: config.meet.trim_end().to_string(),
+                    });
+                }
+                UpConfigTool::GithubRelease(config) => {
+                    for release in config.releases.iter() {
+                        export
+                            .skipped
+                            .push(format!("github-release {}", release.repository));
+                    }
+                }
+                UpConfigTool::Homebrew(config) => {
+                    for tap in config.tap.iter() {
+                        export.taps.push((tap.name(), tap.url()));
+                    }
+                    for install in config.install.iter() {
+                        let name = match install.version() {
+                            Some(version) => format!("{}@{}", install.name(), version),
+                            None => install.name(),
+                        };
+                        if install.is_cask() {
+                            export.casks.push(name);
+                        } else {
+                            export.formulae.push(name);
+                        }
+                    }
+                }
+                _ => {
+                    // Steps with an invalid configuration do not have an
+                    // asdf tool, and are reported when running `omni up`
+                    if let Some(asdf_tool) = step.asdf_tool() {
+                        let resolved = resolved_versions.iter().find(|version| {
+                            version.tool == asdf_tool.tool
+                                && (version.dir.is_empty() || asdf_tool.dirs.contains(&version.dir))
+                        });
+
+                        let (version, resolved) = match resolved {
+                            Some(resolved) => (resolved.version.clone(), true),
+                            None if asdf_tool.version == "auto" => {
+                                export.skipped.push(format!(
+                                    "{} (version 'auto' needs to be resolved by running omni up)",
+                                    asdf_tool.tool
+                                ));
+                                continue;
+                            }
+                            None => (asdf_tool.version.clone(), false),
+                        };
+
+                        export.tools.push(UpExportTool {
+                            tool: asdf_tool.tool.clone(),
+                            plugin_url: asdf_tool.tool_url.clone(),
+                            version,
+                            resolved,
+                        });
+                    }
+                }
+            }
+        }
+
+        export
+    }
+
+    /// The steps that cannot be exported to the given format.
+    pub fn skipped(&self, format: UpExportFormat) -> Vec<String> {
+        let mut skipped = self.skipped.clone();
+
+        match format {
+            UpExportFormat::Dockerfile => {
+                if self.has_homebrew() {
+                    let dependencies = self
+                        .taps
+                        .iter()
+                        .map(|(tap, _)| tap.clone())
+                        .chain(self.formulae.iter().cloned())
+                        .chain(self.casks.iter().cloned())
+                        .collect::<Vec<_>>();
+                    skipped.push(format!("homebrew ({})", dependencies.join(", ")));
+                }
+            }
+            UpExportFormat::Devcontainer => skipped.extend(
+                self.tools
+                    .iter()
+                    .filter(|tool| devcontainer_feature(&tool.tool).is_none())
+                    .map(|tool| tool.tool.clone()),
+            ),
+            UpExportFormat::GithubActions => skipped.extend(
+                self.tools
+                    .iter()
+                    .filter(|tool| github_action(&tool.tool).is_none())
+                    .map(|tool| tool.tool.clone()),
+            ),
+        }
+
+        skipped
+    }
+
+    pub fn render(&self, format: UpExportFormat, name: &str) -> String {
+        match format {
+            UpExportFormat::Dockerfile => self.render_dockerfile(),
+            UpExportFormat::Devcontainer => self.render_devcontainer(name),
+            UpExportFormat::GithubActions => self.render_github_actions(name),
+        }
+    }
+
+    fn has_homebrew(&self) -> bool {
+        !self.taps.is_empty() || !self.formulae.is_empty() || !self.casks.is_empty()
+    }
+
+    fn homebrew_commands(&self) -> Vec<String> {
+        let mut commands = vec![];
+        for (tap, url) in self.taps.iter() {
+            match url {
+                Some(url) => commands.push(format!("brew tap {} {}", tap, shell_quote(url))),
+                None => commands.push(format!("brew tap {}", tap)),
+            }
+        }
+        if !self.formulae.is_empty() {
+            commands.push(format!("brew install {}", self.formulae.join(" ")));
+        }
+        if !self.casks.is_empty() {
+            commands.push(format!("brew install --cask {}", self.casks.join(" ")));
+        }
+        commands
+    }
+
+    fn command_with_dir(command: &UpExportCommand) -> String {
+        match &command.dir {
+            Some(dir) => format!("cd {}\n{}", shell_quote(dir), command.run),
+            None => command.run.clone(),
+        }
+    }
+
+    fn render_dockerfile(&self) -> String {
+        let mut lines = vec![
+            "# syntax=docker/dockerfile:1".to_string(),
+            "# Generated by `omni up export --format dockerfile`".to_string(),
+            "FROM ubuntu:22.04".to_string(),
+            "".to_string(),
+            "ARG DEBIAN_FRONTEND=noninteractive".to_string(),
+            "RUN apt-get update \\".to_string(),
+            "  && apt-get install -y --no-install-recommends \\".to_string(),
+            "    build-essential ca-certificates curl git unzip \\".to_string(),
+            "  && rm -rf /var/lib/apt/lists/*".to_string(),
+        ];
+
+        if !self.env.is_empty() {
+            lines.push("".to_string());
+            for (key, value) in self.env.iter() {
+                lines.push(format!("ENV {}={}", key, json_quote(value)));
+            }
+        }
+
+        if !self.tools.is_empty() {
+            lines.push("".to_string());
+            lines.push("ENV ASDF_DIR=/opt/asdf ASDF_DATA_DIR=/opt/asdf".to_string());
+            lines.push("ENV PATH=/opt/asdf/shims:/opt/asdf/bin:$PATH".to_string());
+            lines.push(format!(
+                "RUN git clone --depth 1 --branch {} https://github.com/asdf-vm/asdf.git /opt/asdf",
+                ASDF_VERSION
+            ));
+
+            for tool in self.tools.iter() {
+                // asdf understands `latest:<prefix>` for versions that
+                // still need to be resolved
+                let version = if tool.resolved || tool.version == "latest" {
+                    tool.version.clone()
+                } else {
+                    format!("latest:{}", tool.version)
+                };
+
+                let plugin_add = match &tool.plugin_url {
+                    Some(url) => format!("asdf plugin add {} {}", tool.tool, shell_quote(url)),
+                    None => format!("asdf plugin add {}", tool.tool),
+                };
+
+                lines.push(format!("RUN {} \\", plugin_add));
+                lines.push(format!("  && asdf install {} {} \\", tool.tool, version));
+                lines.push(format!("  && asdf global {} {}", tool.tool, version));
+            }
+        }
+
+        if self.has_homebrew() {
+            // The base image does not have homebrew, so its dependencies
+            // are left for the user to install through other means
+            lines.push("".to_string());
+            lines.push("# Homebrew is not available in the base image; the following".to_string());
+            lines.push("# dependencies need to be installed through other means:".to_string());
+            for command in self.homebrew_commands() {
+                lines.push(format!("#   {}", command));
+            }
+        }
+
+        if !self.commands.is_empty() {
+            lines.push("".to_string());
+            lines.push("WORKDIR /workspace".to_string());
+            lines.push("COPY . .".to_string());
+
+            for command in self.commands.iter() {
+                let run = Self::command_with_dir(command);
+                lines.push(format!("# {}", command.name.lines().next().unwrap_or("")));
+                if run.contains('\n') {
+                    lines.push("RUN <<'OMNI_EOF'".to_string());
+                    lines.push(run);
+                    lines.push("OMNI_EOF".to_string());
+                } else {
+                    lines.push(format!("RUN {}", run));
+                }
+            }
+        }
+
+        format!("{}\n", lines.join("\n"))
+    }
+
+    fn render_devcontainer(&self, name: &str) -> String {
+        let mut features = serde_json::Map::new();
+        for tool in self.tools.iter() {
+            let feature = match devcontainer_feature(&tool.tool) {
+                Some(feature) => feature,
+                None => continue,
+            };
+            features.insert(
+                feature.to_string(),
+                serde_json::json!({ "version": tool.version }),
+            );
+        }
+
+        let mut post_create = vec![];
+        if self.has_homebrew() {
+            features.insert(
+                "ghcr.io/meaningful-ooo/devcontainer-features/homebrew:2".to_string(),
+                serde_json::json!({}),
+            );
+            post_create.extend(self.homebrew_commands());
+        }
+        for command in self.commands.iter() {
+            let run = command.run.lines().collect::<Vec<&str>>().join("; ");
+            match &command.dir {
+                Some(dir) => post_create.push(format!("(cd {} && {})", shell_quote(dir), run)),
+                None => post_create.push(format!("({})", run)),
+            }
+        }
+
+        let mut devcontainer = serde_json::Map::new();
+        devcontainer.insert("name".to_string(), serde_json::json!(name));
+        devcontainer.insert(
+            "image".to_string(),
+            serde_json::json!("mcr.microsoft.com/devcontainers/base:ubuntu"),
+        );
+        if !features.is_empty() {
+            devcontainer.insert("features".to_string(), serde_json::Value::Object(features));
+        }
+        if !self.env.is_empty() {
+            devcontainer.insert("containerEnv".to_string(), serde_json::json!(self.env));
+        }
+        if !post_create.is_empty() {
+            devcontainer.insert(
+                "postCreateCommand".to_string(),
+                serde_json::json!(post_create.join(" && ")),
+            );
+        }
+
+        format!(
+            "{}\n",
+            serde_json::to_string_pretty(&serde_json::Value::Object(devcontainer)).unwrap()
+        )
+    }
+
+    fn render_github_actions(&self, name: &str) -> String {
+        let mut steps = vec![yaml_mapping(vec![(
+            "uses",
+            yaml_str("actions/checkout@v4"),
+        )])];
+
+        for tool in self.tools.iter() {
+            let (action, version_key) = match github_action(&tool.tool) {
+                Some(action) => action,
+                None => continue,
+            };
+
+            let version = match (tool.tool.as_str(), tool.version.as_str()) {
+                ("rust", "latest") => "stable",
+                (_, version) => version,
+            };
+
+            steps.push(yaml_mapping(vec![
+                ("uses", yaml_str(action)),
+                ("with", yaml_mapping(vec![(version_key, yaml_str(version))])),
+            ]));
+        }
+
+        if self.has_homebrew() {
+            steps.push(yaml_mapping(vec![
+                ("name", yaml_str("Install homebrew dependencies")),
+                ("run", yaml_str(&self.homebrew_commands().join("\n"))),
+            ]));
+        }
+
+        for command in self.commands.iter() {
+            let mut step = vec![
+                ("name", yaml_str(command.name.lines().next().unwrap_or(""))),
+                ("run", yaml_str(&command.run)),
+            ];
+            if let Some(dir) = &command.dir {
+                step.push(("working-directory", yaml_str(dir)));
+            }
+            steps.push(yaml_mapping(step));
+        }
+
+        let mut job = vec![("runs-on", yaml_str("ubuntu-latest"))];
+        if !self.env.is_empty() {
+            job.push((
+                "env",
+                serde_yaml::Value::Mapping(
+                    self.env
+                        .iter()
+                        .map(|(key, value)| (yaml_str(key), yaml_str(value)))
+                        .collect(),
+                ),
+            ));
+        }
+        job.push(("steps", serde_yaml::Value::Sequence(steps)));
+
+        let workflow = yaml_mapping(vec![
+            ("name", yaml_str(name)),
+            (
+                "on",
+                serde_yaml::Value::Sequence(vec![yaml_str("push"), yaml_str("pull_request")]),
+            ),
+            ("jobs", yaml_mapping(vec![("up", yaml_mapping(job))])),
+        ]);
+
+        format!(
+            "# Generated by `omni up export --format github-actions`\n{}",
+            serde_yaml::to_string(&workflow).unwrap()
+        )
+    }
+}
+
+/// The devcontainer feature installing the tool, if any
+fn devcontainer_feature(tool: &str) -> Option<&'static str> {
+    match tool {
+        "golang" => Some("ghcr.io/devcontainers/features/go:1"),
+        "nodejs" => Some("ghcr.io/devcontainers/features/node:1"),
+        "python" => Some("ghcr.io/devcontainers/features/python:1"),
+        "ruby" => Some("ghcr.io/devcontainers/features/ruby:1"),
+        "rust" => Some("ghcr.io/devcontainers/features/rust:1"),
+        "terraform" => Some("ghcr.io/devcontainers/features/terraform:1"),
+        _ => None,
+    }
+}
+
+/// The GitHub action installing the tool, if any, with the name of its
+/// input for the version to install
+fn github_action(tool: &str) -> Option<(&'static str, &'static str)> {
+    match tool {
+        "golang" => Some(("actions/setup-go@v5", "go-version")),
+        "nodejs" => Some(("actions/setup-node@v4", "node-version")),
+        "python" => Some(("actions/setup-python@v5", "python-version")),
+        "ruby" => Some(("ruby/setup-ruby@v1", "ruby-version")),
+        "rust" => Some(("dtolnay/rust-toolchain@master", "toolchain")),
+        "terraform" => Some(("hashicorp/setup-terraform@v3", "terraform_version")),
+        _ => None,
+    }
+}
+
+fn yaml_str(value: &str) -> serde_yaml::Value {
+    serde_yaml::Value::String(value.to_string())
+}
+
+fn yaml_mapping(pairs: Vec<(&str, serde_yaml::Value)>) -> serde_yaml::Value {
+    serde_yaml::Value::Mapping(
+        pairs
+            .into_iter()
+            .map(|(key, value)| (yaml_str(key), value))
+            .collect(),
+    )
+}
+
+fn shell_quote(value: &str) -> String {
+    shell_escape::escape(value.into()).to_string()
+}
+
+fn json_quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::config::ConfigValue;
+
+    fn export() -> UpExport {
+        let up_config = UpConfig::from_config_value(Some(ConfigValue::from_str(concat!(
+            "- node: 20\n",
+            "- python: 3.11\n",
+            "- bash: 5.2\n",
+            "- homebrew:\n",
+            "    tap: my/tap\n",
+            "    install:\n",
+            "      - jq\n",
+            "      - cask: iterm2\n",
+            "- github-release: owner/tool\n",
+            "- bundler\n",
+            "- custom:\n",
+            "    name: Generate the assets\n",
+            "    meet: |\n",
+            "      make assets\n",
+            "      make docs\n",
+            "    dir: web\n",
+        ))))
+        .unwrap();
+        let env = HashMap::from([("APP_ENV".to_string(), "development".to_string())]);
+        let resolved_versions = vec![UpVersion {
+            tool: "nodejs".to_string(),
+            version: "20.11.1".to_string(),
+            dir: "".to_string(),
+        }];
+
+        UpExport::new(&up_config, &env, &resolved_versions)
+    }
+
+    #[test]
+    fn render_dockerfile() {
+        assert_eq!(
+            export().render(UpExportFormat::Dockerfile, "github.com/acme/app"),
+            r#"# syntax=docker/dockerfile:1
+# Generated by `omni up export --format dockerfile`
+FROM ubuntu:22.04
+
+ARG DEBIAN_FRONTEND=noninteractive
+RUN apt-get update \
+  && apt-get install -y --no-install-recommends \
+    build-essential ca-certificates curl git unzip \
+  && rm -rf /var/lib/apt/lists/*
+
+ENV APP_ENV="development"
+
+ENV ASDF_DIR=/opt/asdf ASDF_DATA_DIR=/opt/asdf
+ENV PATH=/opt/asdf/shims:/opt/asdf/bin:$PATH
+RUN git clone --depth 1 --branch v0.13.1 https://github.com/asdf-vm/asdf.git /opt/asdf
+RUN asdf plugin add nodejs \
+  && asdf install nodejs 20.11.1 \
+  && asdf global nodejs 20.11.1
+RUN asdf plugin add python \
+  && asdf install python latest:3.11 \
+  && asdf global python latest:3.11
+RUN asdf plugin add bash 'https://github.com/XaF/asdf-bash' \
+  && asdf install bash latest:5.2 \
+  && asdf global bash latest:5.2
+
+# Homebrew is not available in the base image; the following
+# dependencies need to be installed through other means:
+#   brew tap my/tap
+#   brew install jq
+#   brew install --cask iterm2
+
+WORKDIR /workspace
+COPY . .
+# Install Gemfile dependencies
+RUN <<'OMNI_EOF'
+bundle config --local path vendor/bundle
+bundle install
+OMNI_EOF
+# Generate the assets
+RUN <<'OMNI_EOF'
+cd web
+make assets
+make docs
+OMNI_EOF
+"#
+        );
+    }
+
+    #[test]
+    fn render_devcontainer() {
+        assert_eq!(
+            export().render(UpExportFormat::Devcontainer, "github.com/acme/app"),
+            r#"{
+  "containerEnv": {
+    "APP_ENV": "development"
+  },
+  "features": {
+    "ghcr.io/devcontainers/features/node:1": {
+      "version": "20.11.1"
+    },
+    "ghcr.io/devcontainers/features/python:1": {
+      "version": "3.11"
+    },
+    "ghcr.io/meaningful-ooo/devcontainer-features/homebrew:2": {}
+  },
+  "image": "mcr.microsoft.com/devcontainers/base:ubuntu",
+  "name": "github.com/acme/app",
+  "postCreateCommand": "brew tap my/tap && brew install jq && brew install --cask iterm2 && (bundle config --local path vendor/bundle; bundle install) && (cd web && make assets; make docs)"
+}
+"#
+        );
+    }
+
+    #[test]
+    fn render_github_actions() {
+        assert_eq!(
+            export().render(UpExportFormat::GithubActions, "github.com/acme/app"),
+            r#"# Generated by `omni up export --format github-actions`
+name: github.com/acme/app
+on:
+- push
+- pull_request
+jobs:
+  up:
+    runs-on: ubuntu-latest
+    env:
+      APP_ENV: development
+    steps:
+    - uses: actions/checkout@v4
+    - uses: actions/setup-node@v4
+      with:
+        node-version: 20.11.1
+    - uses: actions/setup-python@v5
+      with:
+        python-version: '3.11'
+    - name: Install homebrew dependencies
+      run: |-
+        brew tap my/tap
+        brew install jq
+        brew install --cask iterm2
+    - name: Install Gemfile dependencies
+      run: |-
+        bundle config --local path vendor/bundle
+        bundle install
+    - name: Generate the assets
+      run: |-
+        make assets
+        make docs
+      working-directory: web
+"#
+        );
+    }
+
+    #[test]
+    fn skipped_depends_only_on_format() {
+        let export = export();
+        for format in [
+            UpExportFormat::GithubActions,
+            UpExportFormat::Dockerfile,
+            UpExportFormat::Devcontainer,
+        ] {
+            export.render(format, "github.com/acme/app");
+        }
+
+        assert_eq!(
+            export.skipped(UpExportFormat::Dockerfile),
+            vec!["github-release owner/tool", "homebrew (my/tap, jq, iterm2)"]
+        );
+        assert_eq!(
+            export.skipped(UpExportFormat::Devcontainer),
+            vec!["github-release owner/tool", "bash"]
+        );
+        assert_eq!(
+            export.skipped(UpExportFormat::GithubActions),
+            vec!["github-release owner/tool", "bash"]
+        );
+    }
+}
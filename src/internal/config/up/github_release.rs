@@ -0,0 +1,957 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Read;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::internal::cache::CacheObject;
+use crate::internal::cache::GithubReleaseOperationCache;
+use crate::internal::cache::UpEnvironmentsCache;
+use crate::internal::config::global_config;
+use crate::internal::config::up::asdf_base::version_match;
+use crate::internal::config::up::native::compare_versions;
+use crate::internal::config::up::native::install_staged;
+use crate::internal::config::up::native::unpack_tar_gz;
+use crate::internal::config::up::native::unpack_zip;
+use crate::internal::config::up::native::verify_sha256;
+use crate::internal::config::up::native::NATIVE_ARCH;
+use crate::internal::config::up::native::NATIVE_OS;
+use crate::internal::config::up::utils::PrintProgressHandler;
+use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::utils::SpinnerProgressHandler;
+use crate::internal::config::up::UpError;
+use crate::internal::config::up::UpOptions;
+use crate::internal::config::ConfigValue;
+use crate::internal::env::data_home;
+use crate::internal::env::shell_is_interactive;
+use crate::internal::user_interface::StringColor;
+use crate::internal::workdir;
+
+lazy_static! {
+    pub static ref GITHUB_RELEASES_PATH: String = format!("{}/ghreleases", data_home());
+}
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpConfigGithubReleases {
+    pub releases: Vec<UpConfigGithubRelease>,
+}
+
+impl UpConfigGithubReleases {
+    pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
+        let mut releases = Vec::new();
+
+        if let Some(config_value) = config_value {
+            if let Some(array) = config_value.as_array() {
+                for value in array {
+                    releases.push(UpConfigGithubRelease::from_config_value(&value));
+                }
+            } else {
+                releases.push(UpConfigGithubRelease::from_config_value(config_value));
+            }
+        }
+
+        UpConfigGithubReleases { releases }
+    }
+
+    pub fn up(&self, options: &UpOptions, progress: Option<(usize, usize)>) -> Result<(), UpError> {
+        let desc = "install github releases:".light_blue();
+        let main_progress_handler = PrintProgressHandler::new(desc, progress);
+        main_progress_handler.progress("".to_string());
+
+        let num_releases = self.releases.len();
+        for (idx, release) in self.releases.iter().enumerate() {
+            if let Err(err) = release.up(options, progress, Some((idx + 1, num_releases))) {
+                main_progress_handler.error();
+                return Err(err);
+            }
+        }
+
+        main_progress_handler.success_with_message(format!(
+            "installed {} release{}",
+            num_releases,
+            if num_releases > 1 { "s" } else { "" },
+        ));
+
+        Ok(())
+    }
+
+    pub fn down(&self, progress: Option<(usize, usize)>) -> Result<(), UpError> {
+        // Releases are installed in a store shared across work directories,
+        // and the work directory paths are cleared with the environment
+        let desc = "uninstall github releases:".light_blue();
+        let progress_handler = PrintProgressHandler::new(desc, progress);
+        progress_handler.success_with_message("skipping (shared store)".light_black());
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpConfigGithubRelease {
+    pub repository: String,
+    pub version: String,
+    pub assets: BTreeMap<String, String>,
+    pub checksum: Option<String>,
+    pub binaries: Vec<String>,
+    pub prerelease: bool,
+    pub api_url: Option<String>,
+}
+
+impl UpConfigGithubRelease {
+    fn from_config_value(config_value: &ConfigValue) -> Self {
+        let mut release = UpConfigGithubRelease {
+            repository: "".to_string(),
+            version: "latest".to_string(),
+            assets: BTreeMap::new(),
+            checksum: None,
+            binaries: Vec::new(),
+            prerelease: false,
+            api_url: None,
+        };
+
+        let table = match config_value.as_table() {
+            Some(table) => table,
+            None => {
+                if let Some(repository) = config_value.as_str() {
+                    release.repository = repository;
+                }
+                return release;
+            }
+        };
+
+        if let Some(repository) = table.get("repository").and_then(|value| value.as_str()) {
+            release.repository = repository;
+        }
+
+        if let Some(version) = config_value.get_as_str_forced("version") {
+            release.version = version;
+        }
+
+        if let Some(asset) = table.get("asset") {
+            if let Some(patterns) = asset.as_table() {
+                for (key, value) in patterns {
+                    if let Some(pattern) = value.as_str() {
+                        release.assets.insert(key.to_string(), pattern);
+                    }
+                }
+            } else if let Some(pattern) = asset.as_str() {
+                release.assets.insert("*".to_string(), pattern);
+            }
+        }
+
+        if let Some(checksum) = table.get("checksum").and_then(|value| value.as_str()) {
+            release.checksum = Some(checksum);
+        }
+
+        if let Some(binaries) = table.get("binaries") {
+            if let Some(binaries) = binaries.as_array() {
+                release.binaries = binaries
+                    .iter()
+                    .filter_map(|binary| binary.as_str())
+                    .collect();
+            } else if let Some(binary) = binaries.as_str() {
+                release.binaries.push(binary);
+            }
+        }
+
+        if let Some(prerelease) = table.get("prerelease").and_then(|value| value.as_bool()) {
+            release.prerelease = prerelease;
+        }
+
+        if let Some(api_url) = table.get("api_url").and_then(|value| value.as_str()) {
+            release.api_url = Some(api_url);
+        }
+
+        release
+    }
+
+    fn up(
+        &self,
+        options: &UpOptions,
+        main_progress: Option<(usize, usize)>,
+        sub_progress: Option<(usize, usize)>,
+    ) -> Result<(), UpError> {
+        let progress_str = if let Some((current, total)) = sub_progress {
+            let padding = format!("{}", total).len();
+            format!(
+                "[{:padding$}/{:padding$}] ",
+                current,
+                total,
+                padding = padding,
+            )
+        } else {
+            "".to_string()
+        };
+
+        let desc = format!(
+            "  {}install {} ({}):",
+            progress_str, self.repository, self.version
+        )
+        .light_yellow();
+
+        let progress_handler: Box<dyn ProgressHandler> = if shell_is_interactive() {
+            Box::new(SpinnerProgressHandler::new(desc, main_progress))
+        } else {
+            Box::new(PrintProgressHandler::new(desc, main_progress))
+        };
+        let progress_handler: Option<&dyn ProgressHandler> = Some(progress_handler.as_ref());
+
+        match self.install(options, progress_handler) {
+            Ok((version, installed)) => {
+                self.update_cache(&version, progress_handler);
+                if let Some(progress_handler) = progress_handler {
+                    progress_handler.success_with_message(if installed {
+                        format!("{} installed", version).light_green()
+                    } else {
+                        format!("{} already installed", version).light_black()
+                    })
+                }
+                Ok(())
+            }
+            Err(err) => {
+                if let Some(progress_handler) = progress_handler {
+                    progress_handler.error_with_message(err.to_string());
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Resolves the version to use and installs it if needed; returns the
+    /// version and whether it was installed during this run.
+    fn install(
+        &self,
+        options: &UpOptions,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(String, bool), UpError> {
+        if !valid_repository(&self.repository) {
+            return Err(UpError::Config(format!(
+                "invalid repository '{}', expected 'owner/name'",
+                self.repository
+            )));
+        }
+
+        let (version, tag) = match self.resolve_version(options, progress_handler) {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                // If we cannot reach the API but already have a matching
+                // version installed, there is no need to fail
+                match self.installed_versions().into_iter().last() {
+                    Some(version) => {
+                        self.link_binaries(&version, progress_handler)?;
+                        return Ok((version, false));
+                    }
+                    None => return Err(err),
+                }
+            }
+        };
+
+        let installed = if self.release_path(&version).is_dir() {
+            false
+        } else {
+            self.download_and_extract(&tag, &version, progress_handler)?;
+            true
+        };
+
+        self.link_binaries(&version, progress_handler)?;
+
+        Ok((version, installed))
+    }
+
+    fn api_url(&self) -> String {
+        self.api_url
+            .clone()
+            .unwrap_or(GITHUB_API_URL.to_string())
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    fn store_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}/{}", *GITHUB_RELEASES_PATH, self.repository))
+    }
+
+    fn release_path(&self, version: &str) -> PathBuf {
+        self.store_path().join(version)
+    }
+
+    fn bin_path(&self, version: &str) -> PathBuf {
+        self.release_path(version).join("bin")
+    }
+
+    fn tags_cache_key(&self) -> String {
+        let api_url = self.api_url();
+        let repository = if api_url == GITHUB_API_URL {
+            self.repository.clone()
+        } else {
+            format!("{}/{}", api_url, self.repository)
+        };
+
+        if self.prerelease {
+            format!("{}+prerelease", repository)
+        } else {
+            repository
+        }
+    }
+
+    fn matches(&self, version: &str) -> bool {
+        if version_match(&self.version, version) {
+            return true;
+        }
+
+        self.prerelease
+            && (self.version == "latest"
+                || version == self.version
+                || version.starts_with(&format!("{}.", self.version))
+                || version.starts_with(&format!("{}-", self.version)))
+    }
+
+    fn installed_versions(&self) -> Vec<String> {
+        let mut versions = match std::fs::read_dir(self.store_path()) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(String::from))
+                .filter(|version| !version.starts_with('.') && self.matches(version))
+                .collect::<Vec<String>>(),
+            Err(_) => Vec::new(),
+        };
+
+        versions.sort_by(|a, b| compare_versions(a, b));
+        versions
+    }
+
+    /// Returns the highest version matching the constraint, along with
+    /// the tag of the release it comes from.
+    fn resolve_version(
+        &self,
+        options: &UpOptions,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(String, String), UpError> {
+        if let Some(handler) = progress_handler {
+            handler.progress("checking available versions".to_string());
+        }
+
+        let cache_key = self.tags_cache_key();
+        let cached_tags = if options.read_cache {
+            GithubReleaseOperationCache::get().get_release_tags(&cache_key)
+        } else {
+            None
+        };
+
+        let tags = match cached_tags {
+            Some(tags) => tags,
+            None => {
+                let tags = self.fetch_tags()?;
+                if let Err(err) = GithubReleaseOperationCache::exclusive(|cache| {
+                    cache.set_release_tags(&cache_key, tags.clone());
+                    true
+                }) {
+                    return Err(UpError::Cache(err.to_string()));
+                }
+                tags
+            }
+        };
+
+        let mut versions = tags
+            .into_iter()
+            .filter_map(|tag| tag_version(&tag).map(|version| (version, tag)))
+            .filter(|(version, _)| self.matches(version))
+            .collect::<Vec<(String, String)>>();
+        versions.sort_by(|(a, _), (b, _)| compare_versions(a, b));
+
+        match versions.pop() {
+            Some(resolved) => Ok(resolved),
+            None => Err(UpError::Exec(format!(
+                "no release of {} matching version {}",
+                self.repository, self.version
+            ))),
+        }
+    }
+
+    fn fetch_tags(&self) -> Result<Vec<String>, UpError> {
+        let per_page = 100;
+        let mut tags = Vec::new();
+
+        // Only the most recent releases are considered, which should
+        // be more than enough for any reasonable version constraint
+        for page in 1..=5 {
+            let releases: Vec<GithubRelease> = github_get_json(&format!(
+                "{}/repos/{}/releases?per_page={}&page={}",
+                self.api_url(),
+                self.repository,
+                per_page,
+                page,
+            ))?;
+
+            let num_releases = releases.len();
+            tags.extend(
+                releases
+                    .into_iter()
+                    .filter(|release| !release.draft && (self.prerelease || !release.prerelease))
+                    .map(|release| release.tag_name),
+            );
+
+            if num_releases < per_page {
+                break;
+            }
+        }
+
+        Ok(tags)
+    }
+
+    fn expand_pattern(&self, pattern: &str, version: &str, tag: &str) -> String {
+        pattern
+            .replace("{version}", version)
+            .replace("{tag}", tag)
+            .replace("{os}", &NATIVE_OS)
+            .replace("{arch}", &NATIVE_ARCH)
+    }
+
+    fn asset_pattern(&self) -> Option<&String> {
+        [
+            format!("{}/{}", *NATIVE_OS, *NATIVE_ARCH),
+            format!("{}-{}", *NATIVE_OS, *NATIVE_ARCH),
+            NATIVE_OS.to_string(),
+            "*".to_string(),
+        ]
+        .iter()
+        .find_map(|key| self.assets.get(key))
+    }
+
+    fn select_asset<'a>(
+        &self,
+        release: &'a GithubRelease,
+        version: &str,
+    ) -> Result<&'a GithubReleaseAsset, UpError> {
+        if !self.assets.is_empty() {
+            let pattern = match self.asset_pattern() {
+                Some(pattern) => self.expand_pattern(pattern, version, &release.tag_name),
+                None => {
+                    return Err(UpError::Config(format!(
+                        "no asset pattern defined for {}/{}",
+                        *NATIVE_OS, *NATIVE_ARCH
+                    )))
+                }
+            };
+
+            let regex = glob_regex(&pattern)?;
+            return release
+                .assets
+                .iter()
+                .find(|asset| regex.is_match(&asset.name))
+                .ok_or_else(|| {
+                    UpError::Exec(format!(
+                        "no asset matching '{}' in release {}",
+                        pattern, release.tag_name
+                    ))
+                });
+        }
+
+        // Without a pattern, try and find the asset for the current
+        // platform from the usual naming conventions
+        let os_aliases: &[&str] = match NATIVE_OS.as_str() {
+            "darwin" => &["darwin", "macos", "apple", "osx"],
+            os => &[os],
+        };
+        let arch_aliases: &[&str] = match (NATIVE_OS.as_str(), NATIVE_ARCH.as_str()) {
+            ("darwin", "amd64") => &["amd64", "x86_64", "x64", "universal"],
+            ("darwin", "arm64") => &["arm64", "aarch64", "universal"],
+            (_, "amd64") => &["amd64", "x86_64", "x64"],
+            (_, "arm64") => &["arm64", "aarch64"],
+            (_, arch) => &[arch],
+        };
+        let ignored_extensions = [
+            ".sha256", ".sha512", ".md5", ".txt", ".sig", ".asc", ".pem", ".sbom", ".json", ".deb",
+            ".rpm", ".apk", ".msi", ".pkg", ".dmg",
+        ];
+
+        release
+            .assets
+            .iter()
+            .filter(|asset| {
+                let name = asset.name.to_lowercase();
+                os_aliases.iter().any(|alias| name.contains(alias))
+                    && arch_aliases.iter().any(|alias| name.contains(alias))
+                    && !ignored_extensions.iter().any(|ext| name.ends_with(ext))
+            })
+            .min_by_key(|asset| asset.name.len())
+            .ok_or_else(|| {
+                UpError::Exec(format!(
+                    "no asset found for {}/{} in release {}, use 'asset' to specify it",
+                    *NATIVE_OS, *NATIVE_ARCH, release.tag_name
+                ))
+            })
+    }
+
+    fn expected_sha256(
+        &self,
+        release: &GithubRelease,
+        version: &str,
+        asset: &GithubReleaseAsset,
+    ) -> Result<Option<String>, UpError> {
+        let pattern = match &self.checksum {
+            Some(pattern) => self.expand_pattern(pattern, version, &release.tag_name),
+            None => return Ok(None),
+        };
+
+        let regex = glob_regex(&pattern)?;
+        let checksum_asset = release
+            .assets
+            .iter()
+            .find(|asset| regex.is_match(&asset.name))
+            .ok_or_else(|| {
+                UpError::Exec(format!(
+                    "no checksum asset matching '{}' in release {}",
+                    pattern, release.tag_name
+                ))
+            })?;
+
+        let mut response = github_get_asset(checksum_asset)?;
+        let mut content = String::new();
+        response.read_to_string(&mut content).map_err(|err| {
+            UpError::Exec(format!("failed to read {}: {}", checksum_asset.name, err))
+        })?;
+
+        // Checksum assets are either a list of `<hash>  <filename>` lines,
+        // or a single hash for the asset they are named after
+        let lines = content
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+            .filter(|parts| !parts.is_empty())
+            .collect::<Vec<Vec<&str>>>();
+
+        for parts in lines.iter() {
+            if parts.len() >= 2 {
+                let filename = parts[1].trim_start_matches('*').trim_start_matches("./");
+                if filename == asset.name {
+                    return Ok(Some(parts[0].to_lowercase()));
+                }
+            }
+        }
+
+        if lines.len() == 1 && lines[0].len() == 1 {
+            return Ok(Some(lines[0][0].to_lowercase()));
+        }
+
+        Err(UpError::Exec(format!(
+            "no checksum found for {} in {}",
+            asset.name, checksum_asset.name
+        )))
+    }
+
+    fn download_and_extract(
+        &self,
+        tag: &str,
+        version: &str,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        let release: GithubRelease = github_get_json(&format!(
+            "{}/repos/{}/releases/tags/{}",
+            self.api_url(),
+            self.repository,
+            tag,
+        ))?;
+        let asset = self.select_asset(&release, version)?;
+
+        let tmp_dir = tempfile::Builder::new()
+            .prefix("omni_up.")
+            .tempdir()
+            .map_err(|err| UpError::Exec(format!("failed to create temp dir: {}", err)))?;
+        let asset_path = tmp_dir.path().join(&asset.name);
+
+        if let Some(handler) = progress_handler {
+            handler.progress(format!("downloading: {}", asset.browser_download_url));
+        }
+        github_download(asset, &asset_path)?;
+
+        if let Some(sha256) = self.expected_sha256(&release, version, asset)? {
+            if let Some(handler) = progress_handler {
+                handler.progress("checking asset integrity (sha256)".to_string());
+            }
+            verify_sha256(&asset_path, &sha256)?;
+        }
+
+        if let Some(handler) = progress_handler {
+            handler.progress("extracting asset".to_string());
+        }
+
+        install_staged(&self.release_path(version), |staging_path| {
+            self.extract(&asset_path, &staging_path.join("release"))
+                .map_err(|err| UpError::Exec(format!("failed to extract asset: {}", err)))
+        })
+    }
+
+    fn extract(&self, asset_path: &Path, target: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(target)?;
+
+        let filename = asset_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        if filename.ends_with(".tar.gz") || filename.ends_with(".tgz") {
+            return unpack_tar_gz(asset_path, target, 0);
+        }
+
+        if filename.ends_with(".zip") {
+            return unpack_zip(asset_path, target);
+        }
+
+        // Anything else is considered to be the binary itself
+        let binary_name = match self.binaries.first() {
+            Some(binary) => binary.rsplit('/').next().unwrap_or(binary).to_string(),
+            None => self.repository.rsplit('/').next().unwrap().to_string(),
+        };
+        let binary_path = target.join(binary_name);
+        std::fs::copy(asset_path, &binary_path)?;
+        std::fs::set_permissions(&binary_path, std::fs::Permissions::from_mode(0o755))?;
+
+        Ok(())
+    }
+
+    /// Exposes the requested binaries (or all the executables of the release
+    /// if none were specified) in the bin directory of the release.
+    fn link_binaries(
+        &self,
+        version: &str,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        let release_dir = self.release_path(version).join("release");
+        let bin_path = self.bin_path(version);
+
+        let is_executable = |path: &Path| {
+            path.metadata()
+                .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+                .unwrap_or(false)
+        };
+
+        let mut targets = Vec::new();
+        if self.binaries.is_empty() {
+            targets.extend(
+                WalkDir::new(&release_dir)
+                    .into_iter()
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.into_path())
+                    .filter(|path| is_executable(path)),
+            );
+        } else {
+            for binary in self.binaries.iter() {
+                let target = if binary.contains('/') {
+                    Some(release_dir.join(binary)).filter(|path| is_executable(path))
+                } else {
+                    WalkDir::new(&release_dir)
+                        .into_iter()
+                        .filter_map(|entry| entry.ok())
+                        .map(|entry| entry.into_path())
+                        .find(|path| {
+                            path.file_name().and_then(|name| name.to_str()) == Some(binary.as_str())
+                                && is_executable(path)
+                        })
+                };
+
+                match target {
+                    Some(target) => targets.push(target),
+                    None => {
+                        return Err(UpError::Exec(format!(
+                            "binary {} not found in release {} of {}",
+                            binary, version, self.repository
+                        )))
+                    }
+                }
+            }
+        }
+
+        if targets.is_empty() {
+            return Err(UpError::Exec(format!(
+                "no binary found in release {} of {}",
+                version, self.repository
+            )));
+        }
+
+        if let Some(handler) = progress_handler {
+            handler.progress("linking binaries".to_string());
+        }
+
+        std::fs::create_dir_all(&bin_path).map_err(|err| {
+            UpError::Exec(format!("failed to create {}: {}", bin_path.display(), err))
+        })?;
+
+        for target in targets {
+            let link_path = bin_path.join(target.file_name().unwrap());
+            if std::fs::read_link(&link_path).ok().as_ref() == Some(&target) {
+                continue;
+            }
+
+            let _ = std::fs::remove_file(&link_path);
+            std::os::unix::fs::symlink(&target, &link_path).map_err(|err| {
+                UpError::Exec(format!("failed to link {}: {}", link_path.display(), err))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn update_cache(&self, version: &str, progress_handler: Option<&dyn ProgressHandler>) {
+        let workdir = workdir(".");
+        let workdir_id = workdir.id();
+        if workdir_id.is_none() {
+            return;
+        }
+        let workdir_id = workdir_id.unwrap();
+
+        if let Some(progress_handler) = progress_handler {
+            progress_handler.progress("updating cache".to_string())
+        }
+
+        let bin_path = self.bin_path(version);
+        if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
+            up_env.add_path(&workdir_id, bin_path.clone(), "github-release")
+        }) {
+            if let Some(progress_handler) = progress_handler {
+                progress_handler.progress(format!("failed to update cache: {}", err))
+            }
+        } else if let Some(progress_handler) = progress_handler {
+            progress_handler.progress("updated cache".to_string())
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<GithubReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubReleaseAsset {
+    name: String,
+    /// The API URL of the asset, which allows to download it with the
+    /// token, as needed for the assets of private repositories
+    #[serde(default)]
+    url: String,
+    browser_download_url: String,
+}
+
+/// Returns whether the repository is of the form `owner/name`; as it is
+/// used as a path in the store, each part can only contain the characters
+/// allowed by GitHub, and cannot be `.` or `..`
+fn valid_repository(repository: &str) -> bool {
+    let parts = repository.split('/').collect::<Vec<&str>>();
+    parts.len() == 2
+        && parts.iter().all(|part| {
+            !part.is_empty()
+                && *part != "."
+                && *part != ".."
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        })
+}
+
+/// Returns the version of a release tag, skipping any prefix before
+/// the first digit (e.g. `v1.2.3` or `cli-v1.2.3`); tags which could not
+/// be used as a directory name in the store are ignored.
+fn tag_version(tag: &str) -> Option<String> {
+    let version = tag.trim_start_matches(|c: char| !c.is_ascii_digit());
+    if version.is_empty() || version.contains('/') || version.contains("..") {
+        None
+    } else {
+        Some(version.to_string())
+    }
+}
+
+/// Converts a pattern where `*` matches any sequence of characters
+/// into a regular expression matching the whole asset name.
+fn glob_regex(pattern: &str) -> Result<Regex, UpError> {
+    let regex = pattern
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<String>>()
+        .join(".*");
+
+    Regex::new(&format!("^{}$", regex))
+        .map_err(|err| UpError::Config(format!("invalid pattern '{}': {}", pattern, err)))
+}
+
+/// Returns the GitHub token to authenticate a request to the given URL, if
+/// any; the token is only sent to GitHub itself, or to the hosts explicitly
+/// allowed in the user configuration, so that a repository cannot get it by
+/// pointing `api_url` to a host it controls
+fn github_token(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    if url.scheme() != "https" {
+        return None;
+    }
+
+    let host = url.host_str()?;
+    if !["api.github.com", "github.com"].contains(&host)
+        && !global_config()
+            .github_release
+            .token_hosts
+            .iter()
+            .any(|allowed| allowed == host)
+    {
+        return None;
+    }
+
+    ["GITHUB_TOKEN", "GH_TOKEN"]
+        .iter()
+        .filter_map(|env_var| std::env::var(env_var).ok())
+        .find(|token| !token.is_empty())
+}
+
+/// Fetches the URL, authenticating the request with the GitHub token if the
+/// host of the URL is allowed to receive it; the token is dropped by the
+/// client if the request gets redirected to another host
+fn github_get(url: &str, accept: Option<&str>) -> Result<reqwest::blocking::Response, UpError> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(format!("omni/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|err| UpError::Exec(format!("failed to fetch {}: {}", url, err)))?;
+
+    let mut request = client.get(url);
+    if let Some(accept) = accept {
+        request = request.header(reqwest::header::ACCEPT, accept);
+    }
+    if let Some(token) = github_token(url) {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request
+        .send()
+        .map_err(|err| UpError::Exec(format!("failed to fetch {}: {}", url, err)))?;
+
+    if !response.status().is_success() {
+        return Err(UpError::Exec(format!(
+            "failed to fetch {}: {}",
+            url,
+            response.status()
+        )));
+    }
+
+    Ok(response)
+}
+
+fn github_get_json<T>(url: &str) -> Result<T, UpError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let mut response = github_get(url, None)?;
+
+    let mut content = String::new();
+    response
+        .read_to_string(&mut content)
+        .map_err(|err| UpError::Exec(format!("failed to read {}: {}", url, err)))?;
+
+    serde_json::from_str(&content)
+        .map_err(|err| UpError::Exec(format!("failed to parse {}: {}", url, err)))
+}
+
+/// Fetches the content of a release asset; this goes through the API when
+/// its URL is known, so that the assets of private repositories can be
+/// downloaded with the token, before being redirected to the CDN
+fn github_get_asset(asset: &GithubReleaseAsset) -> Result<reqwest::blocking::Response, UpError> {
+    if asset.url.is_empty() {
+        github_get(&asset.browser_download_url, None)
+    } else {
+        github_get(&asset.url, Some("application/octet-stream"))
+    }
+}
+
+fn github_download(asset: &GithubReleaseAsset, path: &Path) -> Result<(), UpError> {
+    let mut response = github_get_asset(asset)?;
+
+    let mut file: File = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .map_err(|err| UpError::Exec(format!("failed to open {}: {}", path.display(), err)))?;
+
+    io::copy(&mut response, &mut file)
+        .map_err(|err| UpError::Exec(format!("failed to download {}: {}", asset.name, err)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn github_token_only_sent_to_github() {
+        std::env::remove_var("GH_TOKEN");
+        std::env::set_var("GITHUB_TOKEN", "secret-token");
+
+        assert_eq!(
+            github_token("https://api.github.com/repos/owner/repo/releases"),
+            Some("secret-token".to_string())
+        );
+        assert_eq!(
+            github_token("https://github.com/owner/repo/releases/download/v1/asset.tar.gz"),
+            Some("secret-token".to_string())
+        );
+
+        // Hosts not allowed by the user configuration, even when they
+        // look like GitHub, and unencrypted connections
+        assert_eq!(github_token("https://evil.example.com/repos"), None);
+        assert_eq!(
+            github_token("https://api.github.com.evil.example.com/"),
+            None
+        );
+        assert_eq!(
+            github_token("https://objects.githubusercontent.com/asset"),
+            None
+        );
+        assert_eq!(github_token("http://api.github.com/repos"), None);
+        assert_eq!(github_token("not a url"), None);
+    }
+
+    #[test]
+    fn repository_must_stay_in_store() {
+        assert!(valid_repository("owner/name"));
+        assert!(valid_repository("some-owner/name_with.dots-1"));
+
+        assert!(!valid_repository("name"));
+        assert!(!valid_repository("owner/name/extra"));
+        assert!(!valid_repository("owner//name"));
+        assert!(!valid_repository("/owner/name"));
+        assert!(!valid_repository("../.."));
+        assert!(!valid_repository("owner/.."));
+        assert!(!valid_repository("./name"));
+        assert!(!valid_repository("owner/na me"));
+        assert!(!valid_repository("owner/name\\x"));
+    }
+
+    #[test]
+    fn tag_version_skips_prefix() {
+        assert_eq!(tag_version("v1.2.3"), Some("1.2.3".to_string()));
+        assert_eq!(
+            tag_version("cli-v1.2.3-rc.1"),
+            Some("1.2.3-rc.1".to_string())
+        );
+        assert_eq!(tag_version("nightly"), None);
+
+        // Versions are used as directory names in the store
+        assert_eq!(tag_version("v1/../../x"), None);
+        assert_eq!(tag_version("1.2.3/x"), None);
+        assert_eq!(tag_version("1..2"), None);
+    }
+}
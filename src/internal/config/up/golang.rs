@@ -17,6 +17,9 @@ use crate::internal::ConfigValue;
 pub struct UpConfigGolang {
     pub version: Option<String>,
     pub version_file: Option<String>,
+    pub plugin_url: Option<String>,
+    pub plugin_ref: Option<String>,
+    pub backend: Option<String>,
     #[serde(skip)]
     pub asdf_base: OnceCell<UpConfigAsdfBase>,
 }
@@ -25,6 +28,9 @@ impl UpConfigGolang {
     pub fn from_config_value(config_value: Option<&ConfigValue>) -> Self {
         let mut version = None;
         let mut version_file = None;
+        let mut plugin_url = None;
+        let mut plugin_ref = None;
+        let mut backend = None;
 
         if let Some(config_value) = config_value {
             if let Some(value) = config_value.as_str() {
@@ -39,6 +45,16 @@ impl UpConfigGolang {
                 } else if let Some(value) = value.get("version_file") {
                     version_file = Some(value.as_str().unwrap().to_string());
                 }
+
+                if let Some(value) = value.get("plugin_url") {
+                    plugin_url = value.as_str();
+                }
+                if let Some(value) = value.get("plugin_ref") {
+                    plugin_ref = value.as_str_forced();
+                }
+                if let Some(value) = value.get("backend") {
+                    backend = value.as_str();
+                }
             }
         }
 
@@ -46,6 +62,9 @@ impl UpConfigGolang {
             asdf_base: OnceCell::new(),
             version,
             version_file,
+            plugin_url,
+            plugin_ref,
+            backend,
         }
     }
 
@@ -69,6 +88,8 @@ impl UpConfigGolang {
 
             let mut asdf_base = UpConfigAsdfBase::new("golang", version.as_ref());
             asdf_base.add_detect_version_func(detect_version_from_gomod);
+            asdf_base.set_plugin_source(self.plugin_url.clone(), self.plugin_ref.clone());
+            asdf_base.set_backend(self.backend.clone());
 
             Ok(asdf_base)
         })
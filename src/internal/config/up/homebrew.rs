@@ -440,6 +440,14 @@ impl HomebrewTap {
         Ok(())
     }
 
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn url(&self) -> Option<String> {
+        self.url.clone()
+    }
+
     fn is_tapped(&self) -> bool {
         let mut brew_tap_list = std::process::Command::new("brew");
         brew_tap_list.arg("tap");
@@ -634,10 +642,10 @@ impl HomebrewInstall {
         if bin_path.is_some() || brew_bin_path.is_some() {
             if let Err(err) = UpEnvironmentsCache::exclusive(|up_env| {
                 if let Some(bin_path) = bin_path {
-                    up_env.add_path(&workdir_id, bin_path);
+                    up_env.add_path(&workdir_id, bin_path, "homebrew");
                 }
                 if let Some(brew_bin_path) = brew_bin_path {
-                    up_env.add_path(&workdir_id, brew_bin_path);
+                    up_env.add_path(&workdir_id, brew_bin_path, "homebrew");
                 }
                 true
             }) {
@@ -815,15 +823,15 @@ impl HomebrewInstall {
         )
     }
 
-    fn name(&self) -> String {
+    pub fn name(&self) -> String {
         self.name.clone()
     }
 
-    fn version(&self) -> Option<String> {
+    pub fn version(&self) -> Option<String> {
         self.version.clone()
     }
 
-    fn is_cask(&self) -> bool {
+    pub fn is_cask(&self) -> bool {
         self.install_type == HomebrewInstallType::Cask
     }
 
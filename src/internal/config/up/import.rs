@@ -0,0 +1,371 @@
+use std::path::Path;
+
+use crate::internal::config::up::UpConfigTool;
+use crate::internal::config::ConfigSource;
+use crate::internal::config::ConfigValue;
+
+const MISE_CONFIG_FILES: [&str; 4] = [".config/mise.toml", "mise.toml", ".mise.toml", ".rtx.toml"];
+
+/// The mise configuration file holding the overrides of a developer, which
+/// is usually not committed and thus never imported into the `up` configuration
+const MISE_LOCAL_CONFIG_FILE: &str = "mise.local.toml";
+
+/// A tool version found in the configuration file of another tool manager,
+/// that can be imported as a step of the `up` configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedTool {
+    pub up_name: String,
+    pub version: String,
+    pub source: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportResult {
+    pub tools: Vec<ImportedTool>,
+    pub sources: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Reads the `.tool-versions` and mise configuration files of the given
+/// directory; when a tool is defined in multiple files, the mise one wins.
+pub fn import_tool_versions(dir: &Path) -> Result<ImportResult, String> {
+    let mut result = ImportResult::default();
+
+    let tool_versions_path = dir.join(".tool-versions");
+    if tool_versions_path.is_file() {
+        let content = std::fs::read_to_string(&tool_versions_path)
+            .map_err(|err| format!("failed to read .tool-versions: {}", err))?;
+        result.sources.push(".tool-versions".to_string());
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut parts = line.split_whitespace();
+            if let Some(tool) = parts.next() {
+                let versions = parts.map(String::from).collect::<Vec<String>>();
+                result.add(tool, versions, ".tool-versions");
+            }
+        }
+    }
+
+    for filename in MISE_CONFIG_FILES.iter() {
+        let path = dir.join(filename);
+        if !path.is_file() {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {}", filename, err))?;
+        let parsed: toml::Table = toml::from_str(&content)
+            .map_err(|err| format!("failed to parse {}: {}", filename, err))?;
+        result.sources.push(filename.to_string());
+
+        let tools = match parsed.get("tools").and_then(|tools| tools.as_table()) {
+            Some(tools) => tools,
+            None => continue,
+        };
+
+        for (tool, value) in tools {
+            let versions = match value {
+                toml::Value::String(version) => vec![version.to_string()],
+                toml::Value::Integer(version) => vec![version.to_string()],
+                toml::Value::Float(version) => vec![version.to_string()],
+                toml::Value::Array(versions) => versions
+                    .iter()
+                    .filter_map(|version| match version {
+                        toml::Value::String(version) => Some(version.to_string()),
+                        toml::Value::Table(table) => table
+                            .get("version")
+                            .and_then(|version| version.as_str())
+                            .map(String::from),
+                        _ => None,
+                    })
+                    .collect(),
+                toml::Value::Table(table) => table
+                    .get("version")
+                    .and_then(|version| version.as_str())
+                    .map(|version| vec![version.to_string()])
+                    .unwrap_or_default(),
+                _ => vec![],
+            };
+            result.add(tool, versions, filename);
+        }
+    }
+
+    if dir.join(MISE_LOCAL_CONFIG_FILE).is_file() {
+        result.skipped.push(format!(
+            "{}: local overrides are not imported",
+            MISE_LOCAL_CONFIG_FILE
+        ));
+    }
+
+    Ok(result)
+}
+
+impl ImportResult {
+    fn add(&mut self, tool: &str, versions: Vec<String>, source: &str) {
+        // mise allows to prefix tools with their backend (e.g. `asdf:nodejs`)
+        let tool_name = tool.rsplit(':').next().unwrap_or(tool);
+
+        let up_name = match up_name_for_tool(tool_name) {
+            Some(up_name) => up_name,
+            None => {
+                self.skipped
+                    .push(format!("{} ({}): unsupported tool", tool, source));
+                return;
+            }
+        };
+
+        // Only the first version is imported, as it is the one that would
+        // be used by default by the other tool manager
+        let version = versions.first().and_then(|version| import_version(version));
+        let version = match version {
+            Some(version) => version,
+            None => {
+                self.skipped.push(format!(
+                    "{} ({}): unsupported version '{}'",
+                    tool,
+                    source,
+                    versions.first().cloned().unwrap_or_default()
+                ));
+                return;
+            }
+        };
+
+        self.tools.retain(|imported| imported.up_name != up_name);
+        self.tools.push(ImportedTool {
+            up_name: up_name.to_string(),
+            version,
+            source: source.to_string(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+}
+
+/// Returns the canonical name of the `up` operation handling a tool,
+/// using the names and aliases known by `UpConfigTool`.
+fn up_name_for_tool(tool: &str) -> Option<&'static str> {
+    match UpConfigTool::from_config_value(tool, None)? {
+        UpConfigTool::Bash(_) => Some("bash"),
+        UpConfigTool::Go(_) => Some("go"),
+        UpConfigTool::Nodejs(_) => Some("node"),
+        UpConfigTool::Python(_) => Some("python"),
+        UpConfigTool::Ruby(_) => Some("ruby"),
+        UpConfigTool::Rust(_) => Some("rust"),
+        UpConfigTool::Terraform(_) => Some("terraform"),
+        _ => None,
+    }
+}
+
+fn import_version(version: &str) -> Option<String> {
+    let version = version.trim();
+    if version == "latest" {
+        return Some(version.to_string());
+    }
+
+    // `latest:<prefix>` is the latest version matching the prefix,
+    // which is what omni does with a version prefix
+    if let Some(prefix) = version.strip_prefix("latest:") {
+        return Some(prefix.to_string());
+    }
+
+    // Versions such as `system`, `lts`, `ref:<ref>` or `path:<path>`
+    // do not have any equivalent in omni
+    let version = version.strip_prefix('v').unwrap_or(version);
+    if !version.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(version.to_string())
+}
+
+/// Returns a copy of the configuration with the `up` section patched to
+/// use the imported versions; existing steps for the same tools are
+/// updated in place, and missing ones are appended.
+pub fn patch_up_config(config_value: &ConfigValue, tools: &[ImportedTool]) -> ConfigValue {
+    let mut yaml = config_value.as_serde_yaml();
+    if !yaml.is_mapping() {
+        yaml = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+    }
+    let mapping = yaml.as_mapping_mut().unwrap();
+
+    let up_key = serde_yaml::Value::String("up".to_string());
+    if !mapping.get(&up_key).is_some_and(|up| up.is_sequence()) {
+        mapping.insert(up_key.clone(), serde_yaml::Value::Sequence(vec![]));
+    }
+    let steps = mapping
+        .get_mut(&up_key)
+        .and_then(|up| up.as_sequence_mut())
+        .unwrap();
+
+    for tool in tools {
+        let version = serde_yaml::Value::String(tool.version.clone());
+
+        // Steps are either the bare name of the operation, or a mapping
+        // from the name of the operation to its parameters
+        let existing = steps.iter_mut().find_map(|step| {
+            let (is_bare, name) = match step {
+                serde_yaml::Value::String(name) => (true, name.clone()),
+                serde_yaml::Value::Mapping(step_mapping) if step_mapping.len() == 1 => {
+                    (false, step_mapping.keys().next()?.as_str()?.to_string())
+                }
+                _ => return None,
+            };
+
+            if up_name_for_tool(&name) == Some(tool.up_name.as_str()) {
+                Some((is_bare, name, step))
+            } else {
+                None
+            }
+        });
+
+        match existing {
+            Some((true, name, step)) => {
+                let mut step_mapping = serde_yaml::Mapping::new();
+                step_mapping.insert(serde_yaml::Value::String(name), version);
+                *step = serde_yaml::Value::Mapping(step_mapping);
+            }
+            Some((false, _, step)) => {
+                let params = step.as_mapping_mut().unwrap().values_mut().next().unwrap();
+                if let serde_yaml::Value::Mapping(params) = params {
+                    params.insert(serde_yaml::Value::String("version".to_string()), version);
+                } else {
+                    *params = version;
+                }
+            }
+            None => {
+                let mut step_mapping = serde_yaml::Mapping::new();
+                step_mapping.insert(serde_yaml::Value::String(tool.up_name.clone()), version);
+                steps.push(serde_yaml::Value::Mapping(step_mapping));
+            }
+        }
+    }
+
+    ConfigValue::from_value(ConfigSource::Null, vec![], yaml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn imported(up_name: &str, version: &str) -> ImportedTool {
+        ImportedTool {
+            up_name: up_name.to_string(),
+            version: version.to_string(),
+            source: "mise.toml".to_string(),
+        }
+    }
+
+    fn patch(yaml: &str, tools: &[ImportedTool]) -> String {
+        patch_up_config(&ConfigValue::from_str(yaml), tools).as_yaml()
+    }
+
+    #[test]
+    fn import_version_converts_versions() {
+        assert_eq!(import_version("1.2.3"), Some("1.2.3".to_string()));
+        assert_eq!(import_version(" v20.1 "), Some("20.1".to_string()));
+        assert_eq!(import_version("latest"), Some("latest".to_string()));
+        assert_eq!(import_version("latest:3.11"), Some("3.11".to_string()));
+
+        assert_eq!(import_version("system"), None);
+        assert_eq!(import_version("lts"), None);
+        assert_eq!(import_version("ref:main"), None);
+        assert_eq!(import_version("path:/opt/go"), None);
+    }
+
+    #[test]
+    fn patch_up_config_updates_existing_steps() {
+        let yaml = concat!(
+            "env:\n",
+            "  FOO: bar\n",
+            "up:\n",
+            "  - nodejs\n",
+            "  - python:\n",
+            "      version: 3.10\n",
+            "      dir: api\n",
+            "  - golang: 1.20\n",
+            "  - homebrew:\n",
+            "      install: jq\n",
+        );
+
+        assert_eq!(
+            patch(
+                yaml,
+                &[
+                    imported("node", "20.1.0"),
+                    imported("python", "3.11"),
+                    imported("go", "1.21"),
+                ]
+            ),
+            concat!(
+                "env:\n",
+                "  FOO: bar\n",
+                "up:\n",
+                "- nodejs: 20.1.0\n",
+                "- python:\n",
+                "    dir: api\n",
+                "    version: '3.11'\n",
+                "- golang: '1.21'\n",
+                "- homebrew:\n",
+                "    install: jq\n",
+            )
+        );
+    }
+
+    #[test]
+    fn patch_up_config_appends_missing_steps() {
+        assert_eq!(
+            patch("up:\n  - bash\n", &[imported("ruby", "3.2.2")]),
+            "up:\n- bash\n- ruby: 3.2.2\n"
+        );
+        assert_eq!(
+            patch("commands: {}\n", &[imported("rust", "1.75.0")]),
+            "commands: {}\nup:\n- rust: 1.75.0\n"
+        );
+        assert_eq!(patch("", &[imported("go", "1.21")]), "up:\n- go: '1.21'\n");
+    }
+
+    #[test]
+    fn import_ignores_local_mise_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join(".tool-versions"),
+            "nodejs 18.0.0\nruby 3.1.0\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tmp.path().join("mise.toml"),
+            "[tools]\nnode = \"20.1.0\"\nunknown = \"1.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tmp.path().join("mise.local.toml"),
+            "[tools]\nnode = \"21.0.0\"\n",
+        )
+        .unwrap();
+
+        let result = import_tool_versions(tmp.path()).unwrap();
+        assert_eq!(result.sources, vec![".tool-versions", "mise.toml"]);
+
+        let mut tools = result
+            .tools
+            .iter()
+            .map(|tool| format!("{} {} ({})", tool.up_name, tool.version, tool.source))
+            .collect::<Vec<_>>();
+        tools.sort();
+        assert_eq!(
+            tools,
+            vec!["node 20.1.0 (mise.toml)", "ruby 3.1.0 (.tool-versions)"]
+        );
+
+        assert_eq!(
+            result.skipped,
+            vec![
+                "unknown (mise.toml): unsupported tool",
+                "mise.local.toml: local overrides are not imported",
+            ]
+        );
+    }
+}
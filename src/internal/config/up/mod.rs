@@ -13,6 +13,9 @@ pub(crate) use bundler::UpConfigBundler;
 pub(crate) mod custom;
 pub(crate) use custom::UpConfigCustom;
 
+pub(crate) mod github_release;
+pub(crate) use github_release::UpConfigGithubReleases;
+
 pub(crate) mod golang;
 pub(crate) use golang::UpConfigGolang;
 
@@ -26,6 +29,16 @@ pub(crate) mod asdf_base;
 pub(crate) use asdf_base::UpConfigAsdfBase;
 pub(crate) use asdf_base::ASDF_PATH;
 
+pub(crate) mod backend;
+
+pub(crate) mod export;
+pub(crate) use export::UpExport;
+pub(crate) use export::UpExportFormat;
+
+pub(crate) mod import;
+
+pub(crate) mod native;
+
 pub(crate) mod error;
 pub(crate) use error::UpError;
 
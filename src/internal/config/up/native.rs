@@ -0,0 +1,895 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Read;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::internal::cache::AsdfOperationCache;
+use crate::internal::cache::CacheObject;
+use crate::internal::config::up::backend::tool_install_path;
+use crate::internal::config::up::backend::UpVersionBackend;
+use crate::internal::config::up::utils::ProgressHandler;
+use crate::internal::config::up::UpConfigAsdfBase;
+use crate::internal::config::up::UpError;
+
+const NATIVE_MARKER_FILE: &str = ".omni-native";
+
+lazy_static! {
+    pub static ref NATIVE_OS: String = {
+        let os = match std::env::consts::OS {
+            "macos" => "darwin",
+            _ => std::env::consts::OS,
+        };
+        os.to_string()
+    };
+    pub static ref NATIVE_ARCH: String = {
+        let arch = match std::env::consts::ARCH {
+            "x86_64" => "amd64",
+            "aarch64" => "arm64",
+            _ => std::env::consts::ARCH,
+        };
+        arch.to_string()
+    };
+}
+
+/// Installs tools from their official release archives, without
+/// going through asdf and its plugins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NativeBackend {
+    Golang,
+    Nodejs,
+    Terraform,
+}
+
+impl NativeBackend {
+    pub fn for_tool(tool: &str) -> Option<Self> {
+        match tool {
+            "golang" => Some(NativeBackend::Golang),
+            "nodejs" => Some(NativeBackend::Nodejs),
+            "terraform" => Some(NativeBackend::Terraform),
+            _ => None,
+        }
+    }
+
+    pub fn for_installed(tool: &str, version: &str) -> Option<Self> {
+        if tool_install_path(tool, version)
+            .join(NATIVE_MARKER_FILE)
+            .exists()
+        {
+            Self::for_tool(tool)
+        } else {
+            None
+        }
+    }
+
+    fn tool(&self) -> &'static str {
+        match self {
+            NativeBackend::Golang => "golang",
+            NativeBackend::Nodejs => "nodejs",
+            NativeBackend::Terraform => "terraform",
+        }
+    }
+
+    /// The mirror can be overridden through the environment, which allows
+    /// to use an internal mirror or a local stand-in of the release server.
+    fn mirror(&self) -> String {
+        let (env_var, default) = match self {
+            NativeBackend::Golang => ("OMNI_GOLANG_MIRROR", "https://go.dev/dl"),
+            NativeBackend::Nodejs => ("OMNI_NODEJS_MIRROR", "https://nodejs.org/dist"),
+            NativeBackend::Terraform => (
+                "OMNI_TERRAFORM_MIRROR",
+                "https://releases.hashicorp.com/terraform",
+            ),
+        };
+
+        let mirror = match std::env::var(env_var) {
+            Ok(mirror) if !mirror.is_empty() => mirror,
+            _ => default.to_string(),
+        };
+
+        mirror.trim_end_matches('/').to_string()
+    }
+
+    fn versions_cache_key(&self) -> String {
+        format!("native:{}", self.tool())
+    }
+
+    fn fetch_versions(&self) -> Result<Vec<String>, UpError> {
+        let mirror = self.mirror();
+
+        let mut versions = match self {
+            NativeBackend::Golang => {
+                let releases: Vec<GolangRelease> =
+                    http_get_json(&format!("{}/?mode=json&include=all", mirror))?;
+                releases
+                    .into_iter()
+                    .filter_map(|release| release.version.strip_prefix("go").map(String::from))
+                    .collect::<Vec<String>>()
+            }
+            NativeBackend::Nodejs => {
+                let releases: Vec<NodejsRelease> =
+                    http_get_json(&format!("{}/index.json", mirror))?;
+                releases
+                    .into_iter()
+                    .filter_map(|release| release.version.strip_prefix('v').map(String::from))
+                    .collect::<Vec<String>>()
+            }
+            NativeBackend::Terraform => {
+                let index: TerraformIndex = http_get_json(&format!("{}/index.json", mirror))?;
+                index.versions.into_keys().collect::<Vec<String>>()
+            }
+        };
+
+        // Versions are expected to be sorted from oldest to newest,
+        // as the last matching version is the one that gets installed
+        versions.sort_by(|a, b| compare_versions(a, b));
+        versions.dedup();
+
+        Ok(versions)
+    }
+
+    fn release(&self, version: &str) -> Result<NativeRelease, UpError> {
+        let mirror = self.mirror();
+
+        match self {
+            NativeBackend::Golang => {
+                let releases: Vec<GolangRelease> =
+                    http_get_json(&format!("{}/?mode=json&include=all", mirror))?;
+                let expected_version = format!("go{}", version);
+
+                let file = releases
+                    .into_iter()
+                    .filter(|release| release.version == expected_version)
+                    .flat_map(|release| release.files)
+                    .find(|file| {
+                        file.kind == "archive" && file.os == *NATIVE_OS && file.arch == *NATIVE_ARCH
+                    });
+
+                match file {
+                    Some(file) => Ok(NativeRelease {
+                        url: format!("{}/{}", mirror, file.filename),
+                        filename: file.filename,
+                        sha256: file.sha256,
+                    }),
+                    None => Err(UpError::Exec(format!(
+                        "no golang {} release found for {} {}",
+                        version, *NATIVE_OS, *NATIVE_ARCH,
+                    ))),
+                }
+            }
+            NativeBackend::Nodejs => {
+                let arch = match NATIVE_ARCH.as_str() {
+                    "amd64" => "x64",
+                    arch => arch,
+                };
+                let filename = format!("node-v{}-{}-{}.tar.gz", version, *NATIVE_OS, arch);
+                let sha256 = sha256_from_sums_file(
+                    &format!("{}/v{}/SHASUMS256.txt", mirror, version),
+                    &filename,
+                )?;
+
+                Ok(NativeRelease {
+                    url: format!("{}/v{}/{}", mirror, version, filename),
+                    filename,
+                    sha256,
+                })
+            }
+            NativeBackend::Terraform => {
+                let filename = format!("terraform_{}_{}_{}.zip", version, *NATIVE_OS, *NATIVE_ARCH);
+                let sha256 = sha256_from_sums_file(
+                    &format!("{}/{}/terraform_{}_SHA256SUMS", mirror, version, version),
+                    &filename,
+                )?;
+
+                Ok(NativeRelease {
+                    url: format!("{}/{}/{}", mirror, version, filename),
+                    filename,
+                    sha256,
+                })
+            }
+        }
+    }
+
+    fn extract(&self, archive_path: &Path, target: &Path) -> io::Result<()> {
+        match self {
+            // The golang archive contains a `go` directory, which is
+            // where the dynamic environment expects the GOROOT to be
+            NativeBackend::Golang => unpack_tar_gz(archive_path, target, 0),
+            // The nodejs archive contains a `node-v<version>-<os>-<arch>`
+            // directory that we do not want to keep
+            NativeBackend::Nodejs => unpack_tar_gz(archive_path, target, 1),
+            // The terraform archive only contains the binary
+            NativeBackend::Terraform => unpack_zip(archive_path, &target.join("bin")),
+        }
+    }
+}
+
+impl UpVersionBackend for NativeBackend {
+    fn setup(
+        &self,
+        _config: &UpConfigAsdfBase,
+        _progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        Ok(())
+    }
+
+    fn list_versions(
+        &self,
+        _config: &UpConfigAsdfBase,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<Vec<String>, UpError> {
+        if let Some(handler) = progress_handler {
+            handler.progress("checking available versions".to_string());
+        }
+
+        let cache_key = self.versions_cache_key();
+        if let Some(versions) = AsdfOperationCache::get().get_asdf_plugin_versions(&cache_key) {
+            return Ok(versions);
+        }
+
+        let versions = self.fetch_versions()?;
+
+        if let Err(err) = AsdfOperationCache::exclusive(|cache| {
+            cache.set_asdf_plugin_versions(&cache_key, versions.clone());
+            true
+        }) {
+            return Err(UpError::Cache(err.to_string()));
+        }
+
+        Ok(versions)
+    }
+
+    fn is_version_installed(&self, tool: &str, version: &str) -> bool {
+        tool_install_path(tool, version).is_dir()
+    }
+
+    fn install_version(
+        &self,
+        _config: &UpConfigAsdfBase,
+        version: &str,
+        progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        let release = self.release(version)?;
+
+        // Prepare a temporary directory to download the archive
+        let tmp_dir = tempfile::Builder::new()
+            .prefix("omni_up.")
+            .tempdir()
+            .map_err(|err| UpError::Exec(format!("failed to create temp dir: {}", err)))?;
+        let archive_path = tmp_dir.path().join(&release.filename);
+
+        if let Some(handler) = progress_handler {
+            handler.progress(format!("downloading: {}", release.url));
+        }
+        download_file(&release.url, &archive_path)?;
+
+        if let Some(handler) = progress_handler {
+            handler.progress("checking archive integrity (sha256)".to_string());
+        }
+        verify_sha256(&archive_path, &release.sha256)?;
+
+        if let Some(handler) = progress_handler {
+            handler.progress("extracting archive".to_string());
+        }
+
+        install_staged(&tool_install_path(self.tool(), version), |staging_path| {
+            self.extract(&archive_path, staging_path)
+                .map_err(|err| UpError::Exec(format!("failed to extract archive: {}", err)))?;
+
+            File::create(staging_path.join(NATIVE_MARKER_FILE))
+                .map_err(|err| UpError::Exec(format!("failed to write marker file: {}", err)))?;
+
+            Ok(())
+        })
+    }
+
+    fn uninstall_version(
+        &self,
+        tool: &str,
+        version: &str,
+        _progress_handler: Option<&dyn ProgressHandler>,
+    ) -> Result<(), UpError> {
+        let install_path = tool_install_path(tool, version);
+        std::fs::remove_dir_all(&install_path).map_err(|err| {
+            UpError::Exec(format!(
+                "failed to remove {}: {}",
+                install_path.display(),
+                err
+            ))
+        })
+    }
+}
+
+struct NativeRelease {
+    url: String,
+    filename: String,
+    sha256: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GolangRelease {
+    version: String,
+    #[serde(default)]
+    files: Vec<GolangReleaseFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GolangReleaseFile {
+    filename: String,
+    os: String,
+    arch: String,
+    sha256: String,
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodejsRelease {
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TerraformIndex {
+    versions: HashMap<String, serde_json::Value>,
+}
+
+pub fn http_get(url: &str) -> Result<reqwest::blocking::Response, UpError> {
+    let response = reqwest::blocking::get(url)
+        .map_err(|err| UpError::Exec(format!("failed to fetch {}: {}", url, err)))?;
+
+    if !response.status().is_success() {
+        return Err(UpError::Exec(format!(
+            "failed to fetch {}: {}",
+            url,
+            response.status()
+        )));
+    }
+
+    Ok(response)
+}
+
+fn http_get_json<T>(url: &str) -> Result<T, UpError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let mut response = http_get(url)?;
+
+    let mut content = String::new();
+    response
+        .read_to_string(&mut content)
+        .map_err(|err| UpError::Exec(format!("failed to read {}: {}", url, err)))?;
+
+    serde_json::from_str(&content)
+        .map_err(|err| UpError::Exec(format!("failed to parse {}: {}", url, err)))
+}
+
+pub fn download_file(url: &str, path: &Path) -> Result<(), UpError> {
+    let mut response = http_get(url)?;
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .map_err(|err| UpError::Exec(format!("failed to open {}: {}", path.display(), err)))?;
+
+    io::copy(&mut response, &mut file)
+        .map_err(|err| UpError::Exec(format!("failed to download {}: {}", url, err)))?;
+
+    Ok(())
+}
+
+fn sha256_from_sums_file(url: &str, filename: &str) -> Result<String, UpError> {
+    let mut response = http_get(url)?;
+
+    let mut content = String::new();
+    response
+        .read_to_string(&mut content)
+        .map_err(|err| UpError::Exec(format!("failed to read {}: {}", url, err)))?;
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        if let (Some(sha256), Some(name)) = (parts.next(), parts.next()) {
+            if name.trim_start_matches('*') == filename {
+                return Ok(sha256.to_lowercase());
+            }
+        }
+    }
+
+    Err(UpError::Exec(format!(
+        "no checksum found for {} in {}",
+        filename, url
+    )))
+}
+
+/// Installs to the given path by letting `populate` fill a staging directory
+/// created next to it, which is then moved in place, so that an interrupted
+/// installation never looks like a valid one
+pub fn install_staged<F>(install_path: &Path, populate: F) -> Result<(), UpError>
+where
+    F: FnOnce(&Path) -> Result<(), UpError>,
+{
+    let install_parent = install_path.parent().unwrap();
+    std::fs::create_dir_all(install_parent).map_err(|err| {
+        UpError::Exec(format!(
+            "failed to create {}: {}",
+            install_parent.display(),
+            err
+        ))
+    })?;
+
+    let staging_dir = tempfile::Builder::new()
+        .prefix(".omni_install.")
+        .tempdir_in(install_parent)
+        .map_err(|err| UpError::Exec(format!("failed to create temp dir: {}", err)))?;
+
+    populate(staging_dir.path())?;
+
+    if install_path.exists() {
+        std::fs::remove_dir_all(install_path).map_err(|err| {
+            UpError::Exec(format!(
+                "failed to remove {}: {}",
+                install_path.display(),
+                err
+            ))
+        })?;
+    }
+
+    std::fs::rename(staging_dir.path(), install_path).map_err(|err| {
+        UpError::Exec(format!(
+            "failed to move installation to {}: {}",
+            install_path.display(),
+            err
+        ))
+    })?;
+
+    Ok(())
+}
+
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<(), UpError> {
+    let mut hasher = Sha256::new();
+    let mut file = File::open(path)
+        .map_err(|err| UpError::Exec(format!("failed to open {}: {}", path.display(), err)))?;
+    io::copy(&mut file, &mut hasher)
+        .map_err(|err| UpError::Exec(format!("failed to read {}: {}", path.display(), err)))?;
+
+    let sha256 = format!("{:x}", hasher.finalize());
+    if sha256 != expected.to_lowercase() {
+        return Err(UpError::Exec(format!(
+            "hashes don't match: expected {}, got {}",
+            expected, sha256
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the relative path of an archive entry, without its first
+/// `strip_components` components; returns `None` if the path could
+/// escape the target directory or is empty once stripped.
+fn safe_entry_path(path: &Path, strip_components: usize) -> Option<PathBuf> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    let safe_path = parts
+        .into_iter()
+        .skip(strip_components)
+        .collect::<PathBuf>();
+    if safe_path.as_os_str().is_empty() {
+        None
+    } else {
+        Some(safe_path)
+    }
+}
+
+pub fn unpack_tar_gz(
+    archive_path: &Path,
+    target: &Path,
+    strip_components: usize,
+) -> io::Result<()> {
+    let file = File::open(archive_path)?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    archive.set_preserve_permissions(true);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        let entry_path = entry.path()?.to_path_buf();
+        let relpath = match safe_entry_path(&entry_path, strip_components) {
+            Some(relpath) => relpath,
+            None => continue,
+        };
+
+        let path = target.join(relpath);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&path)?;
+    }
+
+    Ok(())
+}
+
+pub fn unpack_zip(archive_path: &Path, target: &Path) -> io::Result<()> {
+    let file = File::open(archive_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+
+        let relpath = match safe_entry_path(Path::new(entry.name()), 0) {
+            Some(relpath) => relpath,
+            None => continue,
+        };
+        let path = target.join(relpath);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&path)?;
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&path)?;
+        io::copy(&mut entry, &mut file)?;
+
+        let mode = entry.unix_mode().map(|mode| mode & 0o777).unwrap_or(0);
+        let mode = if mode == 0 { 0o644 } else { mode };
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))?;
+    }
+
+    Ok(())
+}
+
+/// Compares versions part by part, numerically when possible; a part
+/// with a suffix (e.g. `21rc2`, `0-beta1`) is considered lower than
+/// the same part without suffix.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split_part(part: &str) -> (u64, &str) {
+        let digits_end = part
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(part.len());
+        let number = part[..digits_end].parse::<u64>().unwrap_or(0);
+        (number, &part[digits_end..])
+    }
+
+    let mut a_parts = a.split('.');
+    let mut b_parts = b.split('.');
+
+    loop {
+        match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(a_part), Some(b_part)) => {
+                let (a_number, a_suffix) = split_part(a_part);
+                let (b_number, b_suffix) = split_part(b_part);
+
+                let ordering = a_number.cmp(&b_number).then_with(|| {
+                    match (a_suffix.is_empty(), b_suffix.is_empty()) {
+                        (true, true) => Ordering::Equal,
+                        (true, false) => Ordering::Greater,
+                        (false, true) => Ordering::Less,
+                        (false, false) => compare_suffixes(a_suffix, b_suffix),
+                    }
+                });
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+/// Compares version suffixes (e.g. `rc2` and `rc10`), considering the
+/// numbers they contain numerically
+fn compare_suffixes(a: &str, b: &str) -> Ordering {
+    fn chunks(suffix: &str) -> Vec<(bool, &str)> {
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        for (idx, c) in suffix.char_indices().skip(1) {
+            let prev = suffix[..idx].chars().last().unwrap();
+            if prev.is_ascii_digit() != c.is_ascii_digit() {
+                chunks.push(&suffix[start..idx]);
+                start = idx;
+            }
+        }
+        chunks.push(&suffix[start..]);
+        chunks
+            .into_iter()
+            .filter(|chunk| !chunk.is_empty())
+            .map(|chunk| (chunk.starts_with(|c: char| c.is_ascii_digit()), chunk))
+            .collect()
+    }
+
+    let a_chunks = chunks(a);
+    let b_chunks = chunks(b);
+    for (a_chunk, b_chunk) in a_chunks.iter().zip(b_chunks.iter()) {
+        let ordering = match (a_chunk, b_chunk) {
+            ((true, a_number), (true, b_number)) => a_number
+                .parse::<u64>()
+                .unwrap_or(0)
+                .cmp(&b_number.parse::<u64>().unwrap_or(0)),
+            ((_, a_chunk), (_, b_chunk)) => a_chunk.cmp(b_chunk),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    a_chunks.len().cmp(&b_chunks.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    fn write_tar_gz(path: &Path, entries: &[(&str, &[u8])]) {
+        let file = File::create(path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        for (name, content) in entries {
+            let mut header = tar::Header::new_old();
+            // The path is written as raw bytes, since the tar crate refuses
+            // to build archives with entries escaping their directory
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(content.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append(&header, *content).unwrap();
+        }
+
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let file = File::create(path).unwrap();
+        let mut writer = zip::ZipWriter::new(file);
+
+        for (name, content) in entries {
+            let options = zip::write::FileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated)
+                .unix_permissions(0o755);
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+
+        writer.finish().unwrap();
+    }
+
+    /// Serves the given files over HTTP, as a stand-in for a release server
+    fn serve(files: Vec<(&'static str, Vec<u8>)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+
+                let mut request_line = String::new();
+                if BufReader::new(&stream)
+                    .read_line(&mut request_line)
+                    .is_err()
+                {
+                    continue;
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap_or("");
+
+                let response = match files.iter().find(|(file, _)| *file == path) {
+                    Some((_, content)) => {
+                        let mut response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            content.len()
+                        )
+                        .into_bytes();
+                        response.extend(content);
+                        response
+                    }
+                    None => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        address
+    }
+
+    fn sha256(content: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(content))
+    }
+
+    #[test]
+    fn safe_entry_path_rejects_escaping_paths() {
+        assert_eq!(
+            safe_entry_path(Path::new("dir/bin/node"), 1),
+            Some(PathBuf::from("bin/node"))
+        );
+        assert_eq!(
+            safe_entry_path(Path::new("./bin/node"), 0),
+            Some(PathBuf::from("bin/node"))
+        );
+        assert_eq!(safe_entry_path(Path::new("../evil"), 0), None);
+        assert_eq!(safe_entry_path(Path::new("../evil"), 1), None);
+        assert_eq!(safe_entry_path(Path::new("/tmp/evil"), 1), None);
+        assert_eq!(safe_entry_path(Path::new("dir/../../evil"), 1), None);
+        assert_eq!(safe_entry_path(Path::new("/etc/passwd"), 0), None);
+        assert_eq!(safe_entry_path(Path::new("dir/"), 1), None);
+    }
+
+    #[test]
+    fn unpack_tar_gz_skips_traversal_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let archive = tmp.path().join("archive.tar.gz");
+        let target = tmp.path().join("target");
+        std::fs::create_dir(&target).unwrap();
+
+        write_tar_gz(
+            &archive,
+            &[
+                ("node-v1/bin/node", b"binary"),
+                ("node-v1/../../evil", b"evil"),
+                ("../evil", b"evil"),
+                ("/tmp/evil", b"evil"),
+            ],
+        );
+
+        unpack_tar_gz(&archive, &target, 1).unwrap();
+
+        assert_eq!(std::fs::read(target.join("bin/node")).unwrap(), b"binary");
+        assert!(!tmp.path().join("evil").exists());
+        assert_eq!(
+            walkdir::WalkDir::new(tmp.path())
+                .into_iter()
+                .flatten()
+                .filter(|entry| entry.file_name() == "evil")
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn unpack_zip_skips_traversal_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let archive = tmp.path().join("archive.zip");
+        let target = tmp.path().join("target");
+
+        write_zip(
+            &archive,
+            &[
+                ("terraform", b"binary"),
+                ("../evil", b"evil"),
+                ("sub/../../evil", b"evil"),
+                ("/tmp/evil", b"evil"),
+            ],
+        );
+
+        unpack_zip(&archive, &target).unwrap();
+
+        let binary = target.join("terraform");
+        assert_eq!(std::fs::read(&binary).unwrap(), b"binary");
+        assert_eq!(
+            std::fs::metadata(&binary).unwrap().permissions().mode() & 0o777,
+            0o755
+        );
+        assert!(!tmp.path().join("evil").exists());
+        assert_eq!(std::fs::read_dir(&target).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn unpack_zip_rejects_invalid_archive() {
+        let tmp = tempfile::tempdir().unwrap();
+        let archive = tmp.path().join("archive.zip");
+        std::fs::write(&archive, b"not a zip archive").unwrap();
+
+        assert!(unpack_zip(&archive, &tmp.path().join("target")).is_err());
+    }
+
+    #[test]
+    fn verify_sha256_checks_content() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("file");
+        std::fs::write(&path, b"content").unwrap();
+
+        assert!(verify_sha256(&path, &sha256(b"content")).is_ok());
+        assert!(verify_sha256(&path, &sha256(b"content").to_uppercase()).is_ok());
+        assert!(verify_sha256(&path, &sha256(b"other content")).is_err());
+    }
+
+    #[test]
+    fn compare_versions_orders_numerically() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.9.1", "1.10"), Ordering::Less);
+        assert_eq!(compare_versions("1.2.0", "1.2"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2.3", "1.2.3"), Ordering::Equal);
+    }
+
+    #[test]
+    fn compare_versions_orders_prereleases_first() {
+        assert_eq!(compare_versions("1.21rc2", "1.21"), Ordering::Less);
+        assert_eq!(compare_versions("1.21rc2", "1.21rc10"), Ordering::Less);
+        assert_eq!(compare_versions("1.6.0-beta1", "1.6.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.6.0-beta1", "1.5.7"), Ordering::Greater);
+        assert_eq!(
+            compare_versions("1.0.0-rc.2", "1.0.0-rc.10"),
+            Ordering::Less
+        );
+
+        let mut versions = vec!["1.10.0", "1.9.0", "1.10.0-rc1", "1.2.0"];
+        versions.sort_by(|a, b| compare_versions(a, b));
+        assert_eq!(versions, vec!["1.2.0", "1.9.0", "1.10.0-rc1", "1.10.0"]);
+    }
+
+    #[test]
+    fn nodejs_release_from_mirror() {
+        let arch = match NATIVE_ARCH.as_str() {
+            "amd64" => "x64",
+            arch => arch,
+        };
+        let filename = format!("node-v20.1.0-{}-{}.tar.gz", *NATIVE_OS, arch);
+        let archive = b"archive content".to_vec();
+
+        let index = br#"[{"version":"v20.1.0"},{"version":"v9.0.0"},{"version":"v20.0.0"}]"#;
+        let sums = format!(
+            "{}  node-v20.1.0.tar.gz\n{}  {}\n",
+            sha256(b"source"),
+            sha256(&archive),
+            filename
+        );
+        let archive_path: &'static str =
+            Box::leak(format!("/v20.1.0/{}", filename).into_boxed_str());
+
+        let mirror = serve(vec![
+            ("/index.json", index.to_vec()),
+            ("/v20.1.0/SHASUMS256.txt", sums.into_bytes()),
+            (archive_path, archive.clone()),
+        ]);
+        std::env::set_var("OMNI_NODEJS_MIRROR", format!("{}/", mirror));
+
+        let backend = NativeBackend::Nodejs;
+        assert_eq!(
+            backend.fetch_versions().unwrap(),
+            vec!["9.0.0", "20.0.0", "20.1.0"]
+        );
+
+        let release = backend.release("20.1.0").unwrap();
+        assert_eq!(release.filename, filename);
+        assert_eq!(release.url, format!("{}{}", mirror, archive_path));
+        assert_eq!(release.sha256, sha256(&archive));
+
+        let tmp = tempfile::tempdir().unwrap();
+        let downloaded = tmp.path().join(&release.filename);
+        download_file(&release.url, &downloaded).unwrap();
+        assert!(verify_sha256(&downloaded, &release.sha256).is_ok());
+
+        assert!(backend.release("21.0.0").is_err());
+    }
+}
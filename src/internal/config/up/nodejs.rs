@@ -12,7 +12,7 @@ use crate::internal::ConfigValue;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpConfigNodejs {
-    #[serde(skip)]
+    #[serde(flatten)]
     pub asdf_base: UpConfigAsdfBase,
 }
 
@@ -4,6 +4,7 @@ use serde::Serialize;
 use crate::internal::config::up::UpConfigAsdfBase;
 use crate::internal::config::up::UpConfigBundler;
 use crate::internal::config::up::UpConfigCustom;
+use crate::internal::config::up::UpConfigGithubReleases;
 use crate::internal::config::up::UpConfigGolang;
 use crate::internal::config::up::UpConfigHomebrew;
 use crate::internal::config::up::UpConfigNodejs;
@@ -18,6 +19,7 @@ pub enum UpConfigTool {
     Bundler(UpConfigBundler),
     Custom(UpConfigCustom),
     // TODO: Dnf(UpConfigDnf),
+    GithubRelease(UpConfigGithubReleases),
     Go(UpConfigGolang),
     Homebrew(UpConfigHomebrew),
     // TODO: Java(UpConfigAsdfBase), // JAVA_HOME
@@ -46,6 +48,11 @@ impl UpConfigTool {
             "custom" => Some(UpConfigTool::Custom(UpConfigCustom::from_config_value(
                 config_value,
             ))),
+            "github-release" | "github-releases" | "ghrelease" => {
+                Some(UpConfigTool::GithubRelease(
+                    UpConfigGithubReleases::from_config_value(config_value),
+                ))
+            }
             "go" | "golang" => Some(UpConfigTool::Go(UpConfigGolang::from_config_value(
                 config_value,
             ))),
@@ -79,6 +86,7 @@ impl UpConfigTool {
             UpConfigTool::Bash(config) => config.up(options, progress),
             UpConfigTool::Bundler(config) => config.up(progress),
             UpConfigTool::Custom(config) => config.up(progress),
+            UpConfigTool::GithubRelease(config) => config.up(options, progress),
             UpConfigTool::Go(config) => config.up(options, progress),
             UpConfigTool::Homebrew(config) => config.up(options, progress),
             UpConfigTool::Nodejs(config) => config.up(options, progress),
@@ -94,6 +102,7 @@ impl UpConfigTool {
             UpConfigTool::Bash(config) => config.down(progress),
             UpConfigTool::Bundler(config) => config.down(progress),
             UpConfigTool::Custom(config) => config.down(progress),
+            UpConfigTool::GithubRelease(config) => config.down(progress),
             UpConfigTool::Go(config) => config.down(progress),
             UpConfigTool::Homebrew(config) => config.down(progress),
             UpConfigTool::Nodejs(config) => config.down(progress),
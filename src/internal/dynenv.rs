@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 
 use blake3::Hasher;
@@ -11,11 +13,14 @@ use shell_escape::escape;
 use crate::internal::cache::CacheObject;
 use crate::internal::cache::UpEnvironmentsCache;
 use crate::internal::config::up::ASDF_PATH;
+use crate::internal::config::RepoShellAliasConfig;
+use crate::internal::secrets::mask_secret;
+use crate::internal::secrets::resolve_secret;
 use crate::internal::user_interface::StringColor;
 use crate::internal::workdir;
 
 const DATA_SEPARATOR: &str = "\x1C";
-const DYNENV_VAR: &str = "__omni_dynenv";
+pub const DYNENV_VAR: &str = "__omni_dynenv";
 const DYNENV_SEPARATOR: &str = ";";
 
 pub fn update_dynamic_env(export_mode: DynamicEnvExportMode) {
@@ -104,10 +109,134 @@ fn print_update(status: &str) {
     eprintln!("{} {}", "omni:".to_string().force_light_cyan(), status);
 }
 
+/// Returns the changes the dynamic environment makes for the current
+/// directory, with the values the variables are restored to when leaving
+/// it, and the source of each change.
+pub fn explain_dynamic_env() -> DynamicEnvExplanation {
+    let (expected_env, envsetter) = expected_dynamic_env();
+    let changes = match envsetter {
+        Some(envsetter) => envsetter.get_env_changes(),
+        None => vec![],
+    };
+
+    DynamicEnvExplanation {
+        features: expected_env.features.clone(),
+        changes,
+    }
+}
+
+/// Returns the variables that the dynamic environment of the current
+/// directory sets (`Some`) or unsets (`None`), and the paths it adds to
+/// the `PATH`, in the order they are added.
+pub fn dynamic_env_variables() -> DynamicEnvVariables {
+    let (_, envsetter) = expected_dynamic_env();
+    let envsetter = match envsetter {
+        Some(envsetter) => envsetter,
+        None => return DynamicEnvVariables::default(),
+    };
+
+    let changes = envsetter.get_env_changes();
+
+    let secrets = changes
+        .iter()
+        .filter(|change| change.secret)
+        .map(|change| change.name.clone())
+        .collect();
+
+    let paths = changes
+        .into_iter()
+        .filter(|change| {
+            change.name == "PATH"
+                && matches!(
+                    change.operation,
+                    DynamicEnvChangeOperation::Prepend | DynamicEnvChangeOperation::Append
+                )
+        })
+        .filter_map(|change| change.value)
+        .collect();
+
+    DynamicEnvVariables {
+        env: envsetter.get_env_data().env.into_iter().collect(),
+        paths,
+        secrets,
+    }
+}
+
+fn expected_dynamic_env() -> (DynamicEnv, Option<DynamicEnvSetter>) {
+    let cache = UpEnvironmentsCache::get();
+
+    // Undo the dynamic environment currently loaded in the environment of
+    // the process, so that the changes are computed from a clean state
+    let mut current_env = DynamicEnv::from_env(cache.clone());
+    current_env.undo(DynamicEnvExportMode::Env);
+
+    let mut expected_env = DynamicEnv::new_with_path(None, cache);
+    let envsetter = match expected_env.setter() {
+        Some(envsetter) if expected_env.id() != 0 => Some(envsetter),
+        _ => None,
+    };
+
+    (expected_env, envsetter)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DynamicEnvVariables {
+    pub env: BTreeMap<String, Option<String>>,
+    pub paths: Vec<String>,
+    /// The names of the variables whose value comes from a secret
+    pub secrets: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DynamicEnvExplanation {
+    pub features: Vec<String>,
+    pub changes: Vec<DynamicEnvChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DynamicEnvChange {
+    pub operation: DynamicEnvChangeOperation,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub previous: Option<String>,
+    pub source: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DynamicEnvChangeOperation {
+    Set,
+    Unset,
+    Prepend,
+    Append,
+    Remove,
+    Alias,
+    Function,
+}
+
+impl DynamicEnvChangeOperation {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DynamicEnvChangeOperation::Set => "set",
+            DynamicEnvChangeOperation::Unset => "unset",
+            DynamicEnvChangeOperation::Prepend => "prepend",
+            DynamicEnvChangeOperation::Append => "append",
+            DynamicEnvChangeOperation::Remove => "remove",
+            DynamicEnvChangeOperation::Alias => "alias",
+            DynamicEnvChangeOperation::Function => "function",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DynamicEnvExportMode {
     Posix,
     Fish,
+    Nu,
+    Pwsh,
     Env,
 }
 
@@ -198,6 +327,29 @@ impl DynamicEnv {
                 hasher.update(DATA_SEPARATOR.as_bytes());
             }
 
+            // Add the secrets to the hash; only their commands are known
+            // here, which also avoids resolving them at every prompt
+            for (key, secret) in up_env.secrets.iter().sorted_by_key(|(key, _)| *key) {
+                hasher.update(key.as_bytes());
+                hasher.update(DATA_SEPARATOR.as_bytes());
+                hasher.update(secret.command.as_bytes());
+                hasher.update(DATA_SEPARATOR.as_bytes());
+            }
+
+            // Add the shell aliases to the hash
+            for (name, alias) in up_env.shell_aliases.iter().sorted_by_key(|(name, _)| *name) {
+                let (kind, definition) = match alias {
+                    RepoShellAliasConfig::Alias(command) => ("alias", command),
+                    RepoShellAliasConfig::Function(body) => ("function", body),
+                };
+                hasher.update(name.as_bytes());
+                hasher.update(DATA_SEPARATOR.as_bytes());
+                hasher.update(kind.as_bytes());
+                hasher.update(DATA_SEPARATOR.as_bytes());
+                hasher.update(definition.as_bytes());
+                hasher.update(DATA_SEPARATOR.as_bytes());
+            }
+
             // Add the requested paths to the hash
             for path in up_env.paths.iter().rev() {
                 hasher.update(path.to_str().unwrap().as_bytes());
@@ -225,7 +377,7 @@ impl DynamicEnv {
         format!("{:016x}", self.id())
     }
 
-    pub fn apply(&mut self, export_mode: DynamicEnvExportMode) {
+    fn setter(&mut self) -> Option<DynamicEnvSetter> {
         let mut envsetter = DynamicEnvSetter::new();
 
         let mut up_env = None;
@@ -235,21 +387,52 @@ impl DynamicEnv {
             if let Some(repo_id) = workdir.id() {
                 up_env = self.cache.get_env(&repo_id);
             } else {
-                return;
+                return None;
             }
         }
 
         if let Some(up_env) = &up_env {
             // Add the requested environments to the hash, sorted by key
-            if !up_env.env_vars.is_empty() {
+            if !up_env.env_vars.is_empty() || !up_env.secrets.is_empty() {
                 self.features.push("env".to_string());
             }
-            for (key, value) in up_env.env_vars.iter() {
+            for (key, value) in up_env.env_vars.iter().sorted() {
+                envsetter.set_source(&up_env.env_var_source(key));
                 envsetter.set_value(key, value);
             }
 
+            // Resolve the secrets, which are never stored in the cache
+            let repo_id = workdir.id().unwrap_or_default();
+            let root = workdir.root().unwrap_or(".");
+            for (key, secret) in up_env.secrets.iter().sorted_by_key(|(key, _)| *key) {
+                match resolve_secret(&repo_id, key, secret, root) {
+                    Ok(value) => {
+                        envsetter.set_secret_source(&up_env.env_var_source(key));
+                        envsetter.set_value(key, &value);
+                    }
+                    Err(err) => {
+                        eprintln!(
+                            "{} {} {}",
+                            "omni:".to_string().force_light_cyan(),
+                            format!("failed to resolve secret {}:", key).force_light_red(),
+                            err,
+                        );
+                    }
+                }
+            }
+
+            // Define the shell aliases and functions
+            if !up_env.shell_aliases.is_empty() {
+                self.features.push("aliases".to_string());
+            }
+            envsetter.set_source("config repo_shell_aliases");
+            for (name, alias) in up_env.shell_aliases.iter().sorted_by_key(|(name, _)| *name) {
+                envsetter.define_alias(name, alias);
+            }
+
             // Add the requested paths
             for path in up_env.paths.iter().rev() {
+                envsetter.set_source(&up_env.path_source(path));
                 envsetter.prepend_to_list("PATH", path.to_str().unwrap());
             }
 
@@ -262,6 +445,7 @@ impl DynamicEnv {
                 let tool_prefix = format!("{}/installs/{}/{}", *ASDF_PATH, tool, version);
 
                 self.features.push(format!("{}:{}", tool, version));
+                envsetter.set_source(&format!("tool {}:{}", tool, version));
 
                 match tool.as_str() {
                     "ruby" => {
@@ -341,12 +525,22 @@ impl DynamicEnv {
         // Set the OMNI_LOADED_FEATURES variable so that it can easily be used in
         // the shell to keep showing up loaded features in the prompt or anywhere
         // else users wish.
+        envsetter.set_source("omni");
         if !self.features.is_empty() {
             envsetter.set_value("OMNI_LOADED_FEATURES", &self.features.join(" "));
         } else {
             envsetter.unset_value("OMNI_LOADED_FEATURES");
         }
 
+        Some(envsetter)
+    }
+
+    pub fn apply(&mut self, export_mode: DynamicEnvExportMode) {
+        let mut envsetter = match self.setter() {
+            Some(envsetter) => envsetter,
+            None => return,
+        };
+
         // Set the dynamic env variable so we can easily undo things
         let json_data = envsetter.get_env_data().to_json();
         if self.id() == 0 {
@@ -390,33 +584,56 @@ enum DynamicEnvOperation {
     AppendToList(String, String),
     RemoveFromList(String, String),
     RemoveFromListByFn(String, Box<dyn Fn() -> Vec<String>>),
+    DefineAlias(String, RepoShellAliasConfig),
 }
 
 struct DynamicEnvSetter {
-    operations: Vec<DynamicEnvOperation>,
+    operations: Vec<(DynamicEnvOperation, String, bool)>,
+    source: String,
+    secret: bool,
 }
 
 impl DynamicEnvSetter {
     fn new() -> Self {
         DynamicEnvSetter {
             operations: Vec::new(),
+            source: String::new(),
+            secret: false,
         }
     }
 
+    /// Sets the source of the operations added after this call, which is
+    /// only used to explain the dynamic environment
+    fn set_source(&mut self, source: &str) {
+        self.source = source.to_string();
+        self.secret = false;
+    }
+
+    /// Same as `set_source`, for operations whose values need to be masked
+    /// when explaining the dynamic environment
+    fn set_secret_source(&mut self, source: &str) {
+        self.source = source.to_string();
+        self.secret = true;
+    }
+
+    fn push(&mut self, operation: DynamicEnvOperation) {
+        self.operations
+            .push((operation, self.source.clone(), self.secret));
+    }
+
     fn set_value(&mut self, key: &str, value: &str) {
-        self.operations.push(DynamicEnvOperation::SetValue(
+        self.push(DynamicEnvOperation::SetValue(
             key.to_string(),
             value.to_string(),
         ));
     }
 
     fn unset_value(&mut self, key: &str) {
-        self.operations
-            .push(DynamicEnvOperation::UnsetValue(key.to_string()));
+        self.push(DynamicEnvOperation::UnsetValue(key.to_string()));
     }
 
     fn prepend_to_list(&mut self, key: &str, value: &str) {
-        self.operations.push(DynamicEnvOperation::PrependToList(
+        self.push(DynamicEnvOperation::PrependToList(
             key.to_string(),
             value.to_string(),
         ));
@@ -424,14 +641,14 @@ impl DynamicEnvSetter {
 
     #[allow(dead_code)]
     fn append_to_list(&mut self, key: &str, value: &str) {
-        self.operations.push(DynamicEnvOperation::AppendToList(
+        self.push(DynamicEnvOperation::AppendToList(
             key.to_string(),
             value.to_string(),
         ));
     }
 
     fn remove_from_list(&mut self, key: &str, value: &str) {
-        self.operations.push(DynamicEnvOperation::RemoveFromList(
+        self.push(DynamicEnvOperation::RemoveFromList(
             key.to_string(),
             value.to_string(),
         ));
@@ -441,17 +658,113 @@ impl DynamicEnvSetter {
     where
         F: Fn() -> Vec<String> + 'static,
     {
-        self.operations
-            .push(DynamicEnvOperation::RemoveFromListByFn(
-                key.to_string(),
-                Box::new(f),
-            ));
+        self.push(DynamicEnvOperation::RemoveFromListByFn(
+            key.to_string(),
+            Box::new(f),
+        ));
+    }
+
+    fn define_alias(&mut self, name: &str, alias: &RepoShellAliasConfig) {
+        self.push(DynamicEnvOperation::DefineAlias(
+            name.to_string(),
+            alias.clone(),
+        ));
+    }
+
+    fn get_env_changes(&self) -> Vec<DynamicEnvChange> {
+        let mut data = DynamicEnvData::new();
+        let mut changes = Vec::new();
+
+        for (operation, source, secret) in self.operations.iter() {
+            let steps = match operation {
+                DynamicEnvOperation::DefineAlias(name, alias) => {
+                    // Aliases are not part of the environment, and are only
+                    // removed when leaving the directory
+                    let (change_operation, definition) = match alias {
+                        RepoShellAliasConfig::Alias(command) => {
+                            (DynamicEnvChangeOperation::Alias, command)
+                        }
+                        RepoShellAliasConfig::Function(body) => {
+                            (DynamicEnvChangeOperation::Function, body)
+                        }
+                    };
+                    changes.push(DynamicEnvChange {
+                        operation: change_operation,
+                        name: name.to_string(),
+                        value: Some(definition.to_string()),
+                        previous: None,
+                        source: source.to_string(),
+                        secret: false,
+                    });
+                    continue;
+                }
+                DynamicEnvOperation::SetValue(key, value) => {
+                    vec![(DynamicEnvChangeOperation::Set, key, Some(value.clone()))]
+                }
+                DynamicEnvOperation::UnsetValue(key) => {
+                    vec![(DynamicEnvChangeOperation::Unset, key, None)]
+                }
+                DynamicEnvOperation::PrependToList(key, value) => {
+                    vec![(DynamicEnvChangeOperation::Prepend, key, Some(value.clone()))]
+                }
+                DynamicEnvOperation::AppendToList(key, value) => {
+                    vec![(DynamicEnvChangeOperation::Append, key, Some(value.clone()))]
+                }
+                DynamicEnvOperation::RemoveFromList(key, value) => {
+                    vec![(DynamicEnvChangeOperation::Remove, key, Some(value.clone()))]
+                }
+                DynamicEnvOperation::RemoveFromListByFn(key, f) => f()
+                    .into_iter()
+                    .map(|value| (DynamicEnvChangeOperation::Remove, key, Some(value)))
+                    .collect(),
+            };
+
+            for (change_operation, key, value) in steps {
+                let before = data.env_get_var(key);
+                match (&change_operation, &value) {
+                    (DynamicEnvChangeOperation::Set, Some(value)) => data.set_value(key, value),
+                    (DynamicEnvChangeOperation::Prepend, Some(value)) => {
+                        data.prepend_to_list(key, value)
+                    }
+                    (DynamicEnvChangeOperation::Append, Some(value)) => {
+                        data.append_to_list(key, value)
+                    }
+                    (DynamicEnvChangeOperation::Remove, Some(value)) => {
+                        data.remove_from_list(key, value)
+                    }
+                    _ => data.unset_value(key),
+                }
+
+                // Skip the operations that did not have any effect
+                if data.env_get_var(key) == before {
+                    continue;
+                }
+
+                let mut previous = std::env::var(key).ok();
+                let mut value = value;
+                if *secret {
+                    previous = previous.map(|previous| mask_secret(&previous));
+                    value = value.map(|value| mask_secret(&value));
+                }
+
+                changes.push(DynamicEnvChange {
+                    operation: change_operation,
+                    name: key.to_string(),
+                    value,
+                    previous,
+                    source: source.to_string(),
+                    secret: *secret,
+                });
+            }
+        }
+
+        changes
     }
 
     fn get_env_data(&self) -> DynamicEnvData {
         let mut data = DynamicEnvData::new();
 
-        for operation in self.operations.iter() {
+        for (operation, _, _) in self.operations.iter() {
             match operation {
                 DynamicEnvOperation::SetValue(key, value) => {
                     data.set_value(key, value);
@@ -474,6 +787,9 @@ impl DynamicEnvSetter {
                         data.remove_from_list(key, value);
                     }
                 }
+                DynamicEnvOperation::DefineAlias(name, alias) => {
+                    data.define_alias(name, alias);
+                }
             }
         }
 
@@ -495,6 +811,12 @@ struct DynamicEnvData {
         skip_serializing_if = "HashMap::is_empty"
     )]
     lists: HashMap<String, Vec<DynamicEnvListValue>>,
+    #[serde(
+        rename = "a",
+        default = "Vec::new",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    aliases: Vec<DynamicEnvAlias>,
     #[serde(skip)]
     env: HashMap<String, Option<String>>,
 }
@@ -504,6 +826,7 @@ impl DynamicEnvData {
         DynamicEnvData {
             values: HashMap::new(),
             lists: HashMap::new(),
+            aliases: Vec::new(),
             env: HashMap::new(),
         }
     }
@@ -633,9 +956,28 @@ impl DynamicEnvData {
         };
     }
 
+    fn define_alias(&mut self, name: &str, alias: &RepoShellAliasConfig) {
+        let (function, definition) = match alias {
+            RepoShellAliasConfig::Alias(command) => (false, command),
+            RepoShellAliasConfig::Function(body) => (true, body),
+        };
+
+        self.aliases.retain(|alias| alias.name != name);
+        self.aliases.push(DynamicEnvAlias {
+            name: name.to_string(),
+            function,
+            definition: Some(definition.to_string()),
+        });
+    }
+
     fn prepare_undo(&mut self) {
         self.env = HashMap::new();
 
+        // Without a definition, the aliases get removed when exported
+        for alias in self.aliases.iter_mut() {
+            alias.definition = None;
+        }
+
         for (key, value) in self.values.clone().iter() {
             let _existing_var = self.env_get_var(key);
             if value.curr.clone() != self.env_get_var(key) {
@@ -697,6 +1039,14 @@ impl DynamicEnvData {
                 self.export_fish();
                 self.export_env();
             }
+            DynamicEnvExportMode::Nu => {
+                self.export_nu();
+                self.export_env();
+            }
+            DynamicEnvExportMode::Pwsh => {
+                self.export_pwsh();
+                self.export_env();
+            }
             DynamicEnvExportMode::Env => {
                 self.export_env();
             }
@@ -731,6 +1081,46 @@ impl DynamicEnvData {
                 }
             }
         }
+
+        // The shell keeps any alias or function the user had defined with
+        // the same name in backup variables, so they can be restored when
+        // our definitions are removed; the backup is only taken if none
+        // exists yet, so that our own definitions are never backed up
+        for alias in self.aliases.iter() {
+            let (alias_backup, function_backup) = alias.posix_backup_names();
+            if alias.definition.is_some() {
+                println!(
+                    "[ -n \"${{{alias_backup}+x}}\" ] || {{ {alias_backup}=\"$(alias {name} 2>/dev/null)\"; {function_backup}=\"$(declare -f {name} 2>/dev/null)\"; }}",
+                    name = alias.name,
+                );
+            }
+
+            match (&alias.definition, alias.function) {
+                (Some(command), false) => {
+                    println!(
+                        "alias {}={}",
+                        alias.name,
+                        escape(std::borrow::Cow::Borrowed(command))
+                    );
+                }
+                (Some(body), true) => {
+                    // An alias with the same name would take precedence
+                    // over the function
+                    println!("unalias {} 2>/dev/null", alias.name);
+                    println!("function {} {{\n{}\n}}", alias.name, body.trim_end());
+                }
+                (None, false) => println!("unalias {} 2>/dev/null", alias.name),
+                (None, true) => println!("unset -f {}", alias.name),
+            }
+
+            if alias.definition.is_none() {
+                // bash prints aliases as `alias name='value'` while zsh
+                // prints them as `name='value'`
+                println!(
+                    "if [ -n \"${{{alias_backup}+x}}\" ]; then case \"${alias_backup}\" in alias\\ *) eval \"${alias_backup}\" ;; ?*) eval \"alias ${alias_backup}\" ;; esac; [ -z \"${function_backup}\" ] || eval \"${function_backup}\"; unset {alias_backup} {function_backup}; fi",
+                );
+            }
+        }
     }
 
     fn export_fish(&self) {
@@ -756,6 +1146,127 @@ impl DynamicEnvData {
                 }
             }
         }
+
+        // The shell integration evaluates the output line by line, so the
+        // definitions need to fit on a single line
+        // Aliases are functions in fish, so any existing function with the
+        // same name is copied aside to be restored when ours is removed
+        for alias in self.aliases.iter() {
+            let backup = alias.fish_backup_name();
+            if alias.definition.is_some() {
+                println!(
+                    "if functions -q {name}; and not functions -q {backup}; functions -c {name} {backup}; end",
+                    name = alias.name,
+                );
+            }
+
+            match (&alias.definition, alias.function) {
+                (Some(command), false) => {
+                    println!("alias {} {}", alias.name, fish_quote(command));
+                }
+                (Some(body), true) => {
+                    println!(
+                        "function {}; eval {}; end",
+                        alias.name,
+                        fish_quote(body.trim_end())
+                    );
+                }
+                (None, _) => {
+                    println!("functions -e {}", alias.name);
+                    println!(
+                        "if functions -q {backup}; functions -c {backup} {name}; functions -e {backup}; end",
+                        name = alias.name,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Nushell does not have an equivalent to `eval`, so the changes are
+    /// exported as a record on a single line, that the shell integration
+    /// parses to call `load-env` and `hide-env`.
+    fn export_nu(&self) {
+        let mut set = serde_json::Map::new();
+        let mut unset = Vec::new();
+
+        for (key, value) in self.env.iter().sorted_by_key(|(key, _)| *key) {
+            match value {
+                Some(value) => {
+                    let value = if key == "PATH" {
+                        serde_json::Value::Array(
+                            value
+                                .split(':')
+                                .filter(|s| !s.is_empty())
+                                .map(|s| serde_json::Value::String(s.to_string()))
+                                .collect(),
+                        )
+                    } else {
+                        serde_json::Value::String(value.to_string())
+                    };
+                    set.insert(key.to_string(), value);
+                }
+                None => unset.push(serde_json::Value::String(key.to_string())),
+            }
+        }
+
+        println!(
+            "{}",
+            serde_json::json!({
+                "set": set,
+                "unset": unset,
+            })
+        );
+    }
+
+    fn export_pwsh(&self) {
+        for (key, value) in self.env.iter() {
+            match value {
+                Some(value) => {
+                    if key == "PATH" {
+                        let path = value.split(':').map(pwsh_quote).join(", ");
+                        println!("$env:{} = @({}) -join [IO.Path]::PathSeparator", key, path);
+                    } else {
+                        println!("$env:{} = {}", key, pwsh_quote(value));
+                    }
+                }
+                None => {
+                    println!(
+                        "Remove-Item -Path Env:{} -ErrorAction SilentlyContinue",
+                        key
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct DynamicEnvAlias {
+    #[serde(rename = "n")]
+    name: String,
+    #[serde(rename = "f", default, skip_serializing_if = "std::ops::Not::not")]
+    function: bool,
+    #[serde(skip)]
+    definition: Option<String>,
+}
+
+impl DynamicEnvAlias {
+    /// Alias names can contain characters that are not valid in shell
+    /// variable or function names, so the backups are named after a hash
+    fn backup_suffix(&self) -> String {
+        blake3::hash(self.name.as_bytes()).to_hex()[..16].to_string()
+    }
+
+    fn posix_backup_names(&self) -> (String, String) {
+        let suffix = self.backup_suffix();
+        (
+            format!("__omni_alias_backup_{}", suffix),
+            format!("__omni_function_backup_{}", suffix),
+        )
+    }
+
+    fn fish_backup_name(&self) -> String {
+        format!("__omni_backup_{}", self.backup_suffix())
     }
 }
 
@@ -795,6 +1306,19 @@ impl DynamicEnvListValue {
     // }
 }
 
+fn pwsh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Quotes a value for fish, keeping newlines as escape sequences outside
+/// of the quotes so that the result fits on a single line.
+fn fish_quote(value: &str) -> String {
+    value
+        .split('\n')
+        .map(|line| format!("'{}'", line.replace('\\', "\\\\").replace('\'', "\\'")))
+        .join("\\n")
+}
+
 fn set_none() -> Option<String> {
     None
 }
@@ -803,6 +1327,12 @@ fn is_none(value: &Option<String>) -> bool {
     value.is_none()
 }
 
+/// Returns the id of the dynamic environment currently loaded, without
+/// parsing its data, or 0 if there is none.
+pub fn current_dynamic_env_id() -> u64 {
+    current_env().0
+}
+
 fn current_env() -> (u64, Option<String>) {
     let dynenv = std::env::var(DYNENV_VAR);
     if dynenv.is_err() {
@@ -839,3 +1369,42 @@ fn hex_to_id(hex: &str) -> Option<u64> {
     }
     Some(cur_id.unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::secrets::SECRET_MASK;
+
+    #[test]
+    fn get_env_changes_masks_secrets() {
+        let mut setter = DynamicEnvSetter::new();
+        setter.set_source("env");
+        setter.set_value("OMNI_TEST_DYNENV_PUBLIC", "public-value");
+        setter.set_secret_source("env (secret)");
+        setter.set_value("OMNI_TEST_DYNENV_SECRET", "secret-value");
+        setter.set_source("env");
+        setter.set_value("OMNI_TEST_DYNENV_OTHER", "other-value");
+
+        let changes = setter.get_env_changes();
+        let change = |name: &str| {
+            changes
+                .iter()
+                .find(|change| change.name == name)
+                .unwrap_or_else(|| panic!("no change for {}", name))
+        };
+
+        let public = change("OMNI_TEST_DYNENV_PUBLIC");
+        assert_eq!(public.value.as_deref(), Some("public-value"));
+        assert!(!public.secret);
+
+        let secret = change("OMNI_TEST_DYNENV_SECRET");
+        assert_eq!(secret.value.as_deref(), Some(SECRET_MASK));
+        assert_eq!(secret.source, "env (secret)");
+        assert!(secret.secret);
+
+        // Switching back to a regular source stops masking values
+        let other = change("OMNI_TEST_DYNENV_OTHER");
+        assert_eq!(other.value.as_deref(), Some("other-value"));
+        assert!(!other.secret);
+    }
+}
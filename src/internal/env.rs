@@ -689,6 +689,8 @@ pub enum Shell {
     Bash,
     Zsh,
     Fish,
+    Nu,
+    Pwsh,
     Posix,
     Unknown(String),
 }
@@ -708,6 +710,8 @@ impl Shell {
             "bash" => Shell::Bash,
             "zsh" => Shell::Zsh,
             "fish" => Shell::Fish,
+            "nu" | "nushell" => Shell::Nu,
+            "pwsh" | "powershell" => Shell::Pwsh,
             "posix" => Shell::Posix,
             _ => Shell::Unknown(shell.to_string()),
         }
@@ -718,6 +722,8 @@ impl Shell {
             Shell::Bash => "bash",
             Shell::Zsh => "zsh",
             Shell::Fish => "fish",
+            Shell::Nu => "nu",
+            Shell::Pwsh => "pwsh",
             Shell::Posix => "posix",
             Shell::Unknown(shell) => shell,
         }
@@ -727,6 +733,8 @@ impl Shell {
         match self {
             Shell::Bash | Shell::Zsh | Shell::Posix => Some(DynamicEnvExportMode::Posix),
             Shell::Fish => Some(DynamicEnvExportMode::Fish),
+            Shell::Nu => Some(DynamicEnvExportMode::Nu),
+            Shell::Pwsh => Some(DynamicEnvExportMode::Pwsh),
             Shell::Unknown(_) => None,
         }
     }
@@ -740,6 +748,10 @@ impl Shell {
             Shell::Bash => PathBuf::from(user_home()).join(".bashrc"),
             Shell::Zsh => PathBuf::from(user_home()).join(".zshrc"),
             Shell::Fish => PathBuf::from(xdg_config_home()).join("fish/omni.fish"),
+            Shell::Nu => PathBuf::from(xdg_config_home()).join("nushell/env.nu"),
+            Shell::Pwsh => {
+                PathBuf::from(xdg_config_home()).join("powershell/Microsoft.PowerShell_profile.ps1")
+            }
             Shell::Posix => PathBuf::from("/dev/null"),
             Shell::Unknown(_) => PathBuf::from("/dev/null"),
         }
@@ -750,6 +762,15 @@ impl Shell {
             Shell::Bash => "eval \"$(omni hook init bash)\"".to_string(),
             Shell::Zsh => "eval \"$(omni hook init zsh)\"".to_string(),
             Shell::Fish => "omni hook init fish | source".to_string(),
+            // Nushell cannot evaluate the output of a command, so we generate
+            // the integration in its autoload directory, which is loaded at
+            // startup after env.nu and config.nu
+            Shell::Nu => concat!(
+                "mkdir ($nu.data-dir | path join vendor autoload); ",
+                "omni hook init nu | save --force ($nu.data-dir | path join vendor autoload omni.nu)",
+            )
+            .to_string(),
+            Shell::Pwsh => "Invoke-Expression (& omni hook init pwsh | Out-String)".to_string(),
             Shell::Posix => String::new(),
             Shell::Unknown(_) => String::new(),
         }
@@ -24,5 +24,7 @@ pub(crate) use user_interface::StringColor;
 
 pub(crate) mod dynenv;
 
+pub(crate) mod secrets;
+
 pub(crate) mod self_updater;
 pub(crate) use self_updater::self_update;
@@ -0,0 +1,301 @@
+use std::io::Write;
+use std::os::unix::fs::DirBuilderExt;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use blake3::Hasher;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::internal::config::EnvSecretConfig;
+use crate::internal::env::cache_home;
+
+pub const SECRET_MASK: &str = "****";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedSecret {
+    value: String,
+    #[serde(with = "time::serde::rfc3339")]
+    expires_at: OffsetDateTime,
+}
+
+/// Returns the value of a secret, by running its command from the given
+/// directory, unless a cached value that has not expired is available.
+pub fn resolve_secret(
+    workdir_id: &str,
+    key: &str,
+    secret: &EnvSecretConfig,
+    dir: &str,
+) -> Result<String, String> {
+    resolve_secret_with_cache_dir(&secrets_cache_dir(), workdir_id, key, secret, dir)
+}
+
+fn resolve_secret_with_cache_dir(
+    cache_dir: &Path,
+    workdir_id: &str,
+    key: &str,
+    secret: &EnvSecretConfig,
+    dir: &str,
+) -> Result<String, String> {
+    let cache_file = secret
+        .ttl
+        .map(|ttl| (secret_cache_file(cache_dir, workdir_id, key, secret), ttl));
+
+    if let Some((cache_file, _)) = &cache_file {
+        if let Some(value) = read_cached_secret(cache_file) {
+            return Ok(value);
+        }
+    }
+
+    // The standard input and error are inherited, so that the command can
+    // ask for a passphrase if needed
+    let output = std::process::Command::new("bash")
+        .arg("-c")
+        .arg(&secret.command)
+        .current_dir(dir)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|err| format!("failed to run command: {}", err))?;
+
+    if !output.status.success() {
+        return Err(format!("command failed ({})", output.status));
+    }
+
+    let value = String::from_utf8(output.stdout)
+        .map_err(|_| "command output is not valid UTF-8".to_string())?;
+    let value = value
+        .strip_suffix('\n')
+        .map(|value| value.strip_suffix('\r').unwrap_or(value))
+        .unwrap_or(&value)
+        .to_string();
+
+    if let Some((cache_file, ttl)) = cache_file {
+        // Failing to cache the secret only means it will be resolved again
+        // next time, so we do not want to fail for that
+        let _ = write_cached_secret(cache_dir, &cache_file, &value, ttl);
+    }
+
+    Ok(value)
+}
+
+pub fn mask_secret(value: &str) -> String {
+    if value.is_empty() {
+        value.to_string()
+    } else {
+        SECRET_MASK.to_string()
+    }
+}
+
+fn secrets_cache_dir() -> PathBuf {
+    PathBuf::from(cache_home()).join("secrets")
+}
+
+fn secret_cache_file(
+    cache_dir: &Path,
+    workdir_id: &str,
+    key: &str,
+    secret: &EnvSecretConfig,
+) -> PathBuf {
+    let mut hasher = Hasher::new();
+    hasher.update(workdir_id.as_bytes());
+    hasher.update(b"\x1C");
+    hasher.update(key.as_bytes());
+    hasher.update(b"\x1C");
+    hasher.update(secret.command.as_bytes());
+
+    cache_dir.join(hasher.finalize().to_hex().as_str())
+}
+
+fn read_cached_secret(path: &Path) -> Option<String> {
+    // Do not trust cache files that could have been read or written by
+    // other users
+    let metadata = std::fs::metadata(path).ok()?;
+    if metadata.permissions().mode() & 0o077 != 0 {
+        let _ = std::fs::remove_file(path);
+        return None;
+    }
+
+    let content = std::fs::read_to_string(path).ok()?;
+    let cached: CachedSecret = serde_json::from_str(&content).ok()?;
+    if cached.expires_at <= OffsetDateTime::now_utc() {
+        let _ = std::fs::remove_file(path);
+        return None;
+    }
+
+    Some(cached.value)
+}
+
+fn write_cached_secret(dir: &Path, path: &Path, value: &str, ttl: u64) -> std::io::Result<()> {
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+
+    let cached = CachedSecret {
+        value: value.to_string(),
+        expires_at: OffsetDateTime::now_utc() + time::Duration::seconds(ttl as i64),
+    };
+    let content = serde_json::to_string(&cached)?;
+
+    // Remove any existing file, so that the new one is created with the
+    // expected permissions
+    let _ = std::fs::remove_file(path);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A secret provider that counts how many times it was called
+    fn counting_secret(counter: &Path, value: &str, ttl: Option<u64>) -> EnvSecretConfig {
+        EnvSecretConfig {
+            command: format!("echo called >> '{}'; echo '{}'", counter.display(), value),
+            ttl,
+        }
+    }
+
+    fn calls(counter: &Path) -> usize {
+        std::fs::read_to_string(counter)
+            .map(|content| content.lines().count())
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn resolve_secret_without_ttl_is_not_cached() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_dir = tmp.path().join("secrets");
+        let counter = tmp.path().join("counter");
+        let secret = counting_secret(&counter, "value", None);
+        let dir = tmp.path().to_str().unwrap();
+
+        for _ in 0..2 {
+            assert_eq!(
+                resolve_secret_with_cache_dir(&cache_dir, "repo", "KEY", &secret, dir),
+                Ok("value".to_string())
+            );
+        }
+
+        assert_eq!(calls(&counter), 2);
+        assert!(!cache_dir.exists());
+    }
+
+    #[test]
+    fn resolve_secret_uses_cache_until_expiry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_dir = tmp.path().join("secrets");
+        let counter = tmp.path().join("counter");
+        let secret = counting_secret(&counter, "value", Some(3600));
+        let dir = tmp.path().to_str().unwrap();
+
+        for _ in 0..2 {
+            assert_eq!(
+                resolve_secret_with_cache_dir(&cache_dir, "repo", "KEY", &secret, dir),
+                Ok("value".to_string())
+            );
+        }
+        assert_eq!(calls(&counter), 1);
+
+        // The cache is per work directory and variable
+        assert_eq!(
+            resolve_secret_with_cache_dir(&cache_dir, "other-repo", "KEY", &secret, dir),
+            Ok("value".to_string())
+        );
+        assert_eq!(calls(&counter), 2);
+
+        // Once expired, the command is run again
+        let cache_file = secret_cache_file(&cache_dir, "repo", "KEY", &secret);
+        let expired = CachedSecret {
+            value: "stale".to_string(),
+            expires_at: OffsetDateTime::now_utc() - time::Duration::seconds(1),
+        };
+        std::fs::write(&cache_file, serde_json::to_string(&expired).unwrap()).unwrap();
+        assert_eq!(
+            resolve_secret_with_cache_dir(&cache_dir, "repo", "KEY", &secret, dir),
+            Ok("value".to_string())
+        );
+        assert_eq!(calls(&counter), 3);
+    }
+
+    #[test]
+    fn resolve_secret_cache_permissions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_dir = tmp.path().join("secrets");
+        let counter = tmp.path().join("counter");
+        let secret = counting_secret(&counter, "value", Some(3600));
+        let dir = tmp.path().to_str().unwrap();
+
+        resolve_secret_with_cache_dir(&cache_dir, "repo", "KEY", &secret, dir).unwrap();
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let cache_file = secret_cache_file(&cache_dir, "repo", "KEY", &secret);
+        assert_eq!(mode(&cache_dir), 0o700);
+        assert_eq!(mode(&cache_file), 0o600);
+
+        // A cache file readable by other users is not trusted
+        std::fs::set_permissions(&cache_file, std::fs::Permissions::from_mode(0o644)).unwrap();
+        resolve_secret_with_cache_dir(&cache_dir, "repo", "KEY", &secret, dir).unwrap();
+        assert_eq!(calls(&counter), 2);
+        assert_eq!(mode(&cache_file), 0o600);
+    }
+
+    #[test]
+    fn resolve_secret_failing_provider() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_dir = tmp.path().join("secrets");
+        let secret = EnvSecretConfig {
+            command: "echo partial; exit 3".to_string(),
+            ttl: Some(3600),
+        };
+
+        let result = resolve_secret_with_cache_dir(
+            &cache_dir,
+            "repo",
+            "KEY",
+            &secret,
+            tmp.path().to_str().unwrap(),
+        );
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("command failed"));
+
+        // Failures are never cached
+        assert!(!secret_cache_file(&cache_dir, "repo", "KEY", &secret).exists());
+    }
+
+    #[test]
+    fn resolve_secret_strips_trailing_newline_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let secret = EnvSecretConfig {
+            command: "printf 'line1\\nline2 \\r\\n'".to_string(),
+            ttl: None,
+        };
+
+        assert_eq!(
+            resolve_secret_with_cache_dir(
+                &tmp.path().join("secrets"),
+                "repo",
+                "KEY",
+                &secret,
+                tmp.path().to_str().unwrap(),
+            ),
+            Ok("line1\nline2 ".to_string())
+        );
+    }
+
+    #[test]
+    fn mask_secret_hides_value() {
+        assert_eq!(mask_secret("super-secret"), SECRET_MASK);
+        assert_eq!(mask_secret(""), "");
+    }
+}
@@ -1,5 +1,9 @@
+use std::collections::BTreeMap;
+
 use crate::internal::cache::CacheObject;
 use crate::internal::cache::RepositoriesCache;
+use crate::internal::cache::WatchedFile;
+use crate::internal::config::config;
 use crate::internal::env::shell_is_interactive;
 use crate::internal::git::ORG_LOADER;
 use crate::internal::git_env;
@@ -94,3 +98,121 @@ pub fn add_trust(path: &str) -> bool {
     }
     true
 }
+
+/// Returns a fingerprint of what `omni up` applies to the work directory,
+/// which is its configuration and the content of the watched files, such
+/// as lockfiles; this is 0 if there is nothing for `omni up` to apply.
+/// The content of the watched files is only hashed when their size or
+/// modification time changed since it was last hashed.
+pub fn up_fingerprint(path: &str) -> u64 {
+    let config = config(path);
+    let config_fingerprint = config.up_fingerprint();
+    if config_fingerprint == 0 {
+        return 0;
+    }
+
+    let wd = workdir(path);
+    let (wd_id, root) = match (wd.id(), wd.root()) {
+        (Some(wd_id), Some(root)) => (wd_id, std::path::PathBuf::from(root)),
+        _ => return config_fingerprint,
+    };
+
+    let cached_files = RepositoriesCache::get().get_watched_files(&wd_id);
+    let mut watched_files = BTreeMap::new();
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&config_fingerprint.to_le_bytes());
+    for file in config.up_command.watch_files.iter() {
+        let watched_file = match watched_file(&root.join(file), cached_files.get(file)) {
+            Some(watched_file) => watched_file,
+            None => continue,
+        };
+
+        hasher.update(file.as_bytes());
+        hasher.update(b"\x1C");
+        match blake3::Hash::from_hex(&watched_file.hash) {
+            Ok(hash) => hasher.update(hash.as_bytes()),
+            Err(_) => hasher.update(watched_file.hash.as_bytes()),
+        };
+        watched_files.insert(file.to_string(), watched_file);
+    }
+
+    if watched_files != cached_files {
+        // Failing to cache the state of the files only means their content
+        // will be hashed again next time
+        let _ = RepositoriesCache::exclusive(|repos| {
+            repos.update_watched_files(&wd_id, watched_files.clone())
+        });
+    }
+
+    let hash_bytes = hasher.finalize();
+    u64::from_le_bytes(hash_bytes.as_bytes()[..8].try_into().unwrap())
+}
+
+fn watched_file(path: &std::path::Path, cached: Option<&WatchedFile>) -> Option<WatchedFile> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+
+    let size = metadata.len();
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|mtime| mtime.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|mtime| mtime.as_nanos())
+        .unwrap_or(0);
+
+    if let Some(cached) = cached {
+        if cached.size == size && cached.mtime == mtime {
+            return Some(cached.clone());
+        }
+    }
+
+    let content = std::fs::read(path).ok()?;
+    Some(WatchedFile {
+        size,
+        mtime,
+        hash: blake3::hash(&content).to_hex().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watched_file_reuses_hash_while_unchanged() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("Gemfile.lock");
+
+        assert!(watched_file(&path, None).is_none());
+
+        std::fs::write(&path, "content").unwrap();
+        let first = watched_file(&path, None).unwrap();
+        assert_eq!(first.size, 7);
+        assert_eq!(first.hash, blake3::hash(b"content").to_hex().to_string());
+
+        // The content is not read again when the size and modification
+        // time did not change
+        let cached = WatchedFile {
+            hash: "cached".to_string(),
+            ..first.clone()
+        };
+        assert_eq!(watched_file(&path, Some(&cached)), Some(cached));
+
+        // But it is when any of them changed
+        let stale = WatchedFile {
+            size: 1,
+            hash: "stale".to_string(),
+            ..first.clone()
+        };
+        assert_eq!(watched_file(&path, Some(&stale)), Some(first));
+    }
+
+    #[test]
+    fn watched_file_ignores_directories() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(watched_file(tmp.path(), None).is_none());
+    }
+}
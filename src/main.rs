@@ -5,6 +5,7 @@ mod internal;
 use internal::command_loader;
 use internal::commands::HookEnvCommand;
 use internal::commands::HookInitCommand;
+use internal::commands::HookPromptCommand;
 use internal::commands::HookUuidCommand;
 use internal::config::ensure_bootstrap;
 use internal::git::auto_update_async;
@@ -154,6 +155,11 @@ fn run_omni_subcommand(parsed: &MainArgs) {
                     command.exec(parsed.args[2..].to_vec());
                     panic!("exec returned");
                 }
+                "prompt" => {
+                    let command = HookPromptCommand::new();
+                    command.exec(parsed.args[2..].to_vec());
+                    panic!("exec returned");
+                }
                 _ => {}
             }
         }